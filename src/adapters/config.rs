@@ -95,6 +95,38 @@ fn default_backoff_multiplier() -> f64 {
     2.0
 }
 
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_retry(),
+            initial_delay: default_initial_delay(),
+            max_delay: default_max_delay(),
+            backoff_multiplier: default_backoff_multiplier(),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Get the delay before a retry attempt (1-based), capped at `max_delay`
+    pub fn delay(&self, attempt: u32) -> std::time::Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay as f64 * self.backoff_multiplier.max(1.0).powi(exponent);
+        std::time::Duration::from_millis(delay.min(self.max_delay as f64) as u64)
+    }
+}
+
+/// Heartbeat intervals missed before a connection is considered lost
+/// when no explicit timeout is configured
+pub const DEFAULT_MISSED_HEARTBEATS: u64 = 3;
+
+impl HeartbeatConfig {
+    /// Get the time without heartbeat after which the connection is lost
+    pub fn timeout_duration(&self) -> std::time::Duration {
+        let secs = self.timeout.unwrap_or(self.interval.saturating_mul(DEFAULT_MISSED_HEARTBEATS));
+        std::time::Duration::from_secs(secs)
+    }
+}
+
 /// Adapter configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdapterConfig {
@@ -304,4 +336,21 @@ mod tests {
         assert_eq!(retry.max_delay, 30000);
         assert_eq!(retry.backoff_multiplier, 2.0);
     }
+
+    #[test]
+    fn test_retry_delay() {
+        let retry = RetryConfig {
+            max_attempts: 5,
+            initial_delay: 100,
+            max_delay: 500,
+            backoff_multiplier: 2.0,
+        };
+        assert_eq!(retry.delay(1).as_millis(), 100);
+        assert_eq!(retry.delay(2).as_millis(), 200);
+        assert_eq!(retry.delay(3).as_millis(), 400);
+        assert_eq!(retry.delay(4).as_millis(), 500);
+
+        let heartbeat = HeartbeatConfig { interval: 5, timeout: None, enabled: true };
+        assert_eq!(heartbeat.timeout_duration().as_secs(), 15);
+    }
 }
@@ -1,14 +1,159 @@
 //! Console Adapter - reads input from stdin and writes output to stdout
 
+use async_trait::async_trait;
 use crate::adapters::{
-    Adapter, AdapterConfig, AdapterStatus,
+    Adapter, AdapterConfig, AdapterStatus, Message, Segment, Target,
+    DEFAULT_EVENT_CHANNEL_CAPACITY,
     types::AdapterStatistics,
 };
-use crate::events::EventEnum;
+use crate::events::{EventEnum, EventMetadata, EventSource, MessageEvent};
 use crate::errors::{AdapterError, LoquatError, Result};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use tokio::task::JoinHandle;
+
+/// Default user ID of console messages
+pub const DEFAULT_CONSOLE_USER_ID: &str = "console-user";
+
+/// Default self ID of the console bot
+pub const DEFAULT_CONSOLE_SELF_ID: &str = "console";
+
+const HELP: &str = "directives: /as user:<id>, /group <id>, /private, /whoami, /help; 'quit' or 'exit' to stop";
+
+/// Parsed console input line
+#[derive(Debug, Clone)]
+pub enum ConsoleInput {
+    /// A message event to publish
+    Event(Box<EventEnum>),
+    /// A directive was handled; the reply is printed to the console
+    Directive(String),
+    /// Stop reading input
+    Quit,
+    /// Blank line
+    Empty,
+}
+
+/// Sender context of console messages, changed by directives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleSession {
+    /// Adapter ID stamped on events
+    pub adapter_id: String,
+    /// Bot self ID
+    pub self_id: String,
+    /// Current sender
+    pub user_id: String,
+    /// Current group, `None` for private chat
+    pub group_id: Option<String>,
+}
+
+impl ConsoleSession {
+    /// Create a new session in private chat
+    pub fn new(adapter_id: &str, self_id: &str, user_id: &str) -> Self {
+        Self {
+            adapter_id: adapter_id.to_string(),
+            self_id: self_id.to_string(),
+            user_id: user_id.to_string(),
+            group_id: None,
+        }
+    }
+
+    /// Create a session from the adapter config
+    ///
+    /// Reads `platform.self_id`, `platform.user_id` and `platform.group_id`.
+    pub fn from_config(config: &AdapterConfig) -> Self {
+        let platform = &config.platform;
+        let mut session = Self::new(
+            &config.adapter_id,
+            platform["self_id"].as_str().unwrap_or(DEFAULT_CONSOLE_SELF_ID),
+            platform["user_id"].as_str().unwrap_or(DEFAULT_CONSOLE_USER_ID),
+        );
+        session.group_id = platform["group_id"].as_str().map(|id| id.to_string());
+        session
+    }
+
+    /// Describe the current sender context
+    pub fn describe(&self) -> String {
+        match &self.group_id {
+            Some(group_id) => format!("user:{} in group:{}", self.user_id, group_id),
+            None => format!("user:{} (private)", self.user_id),
+        }
+    }
+
+    /// Parse a line, applying directives to the session
+    ///
+    /// Unknown `/` commands are passed through as text so that bot commands
+    /// can still be typed.
+    pub fn parse_line(&mut self, line: &str) -> ConsoleInput {
+        let line = line.trim();
+        if line.is_empty() {
+            return ConsoleInput::Empty;
+        }
+        if line.eq_ignore_ascii_case("quit") || line.eq_ignore_ascii_case("exit") {
+            return ConsoleInput::Quit;
+        }
+
+        let (command, arg) = match line.split_once(char::is_whitespace) {
+            Some((command, arg)) => (command, arg.trim()),
+            None => (line, ""),
+        };
+        match command {
+            "/as" => {
+                let user_id = arg.strip_prefix("user:").unwrap_or(arg).trim();
+                if user_id.is_empty() {
+                    return ConsoleInput::Directive("usage: /as user:<id>".to_string());
+                }
+                self.user_id = user_id.to_string();
+                ConsoleInput::Directive(format!("now speaking as {}", self.describe()))
+            }
+            "/group" => {
+                let group_id = arg.strip_prefix("group:").unwrap_or(arg).trim();
+                self.group_id = (!group_id.is_empty()).then(|| group_id.to_string());
+                ConsoleInput::Directive(format!("now speaking as {}", self.describe()))
+            }
+            "/private" => {
+                self.group_id = None;
+                ConsoleInput::Directive(format!("now speaking as {}", self.describe()))
+            }
+            "/whoami" => ConsoleInput::Directive(self.describe()),
+            "/help" => ConsoleInput::Directive(HELP.to_string()),
+            _ => ConsoleInput::Event(Box::new(EventEnum::Message(self.message_event(line)))),
+        }
+    }
+
+    /// Build a text message event from the current context
+    pub fn message_event(&self, text: &str) -> MessageEvent {
+        let mut metadata = EventMetadata::new("console.message")
+            .with_source(EventSource::User)
+            .with_user_id(&self.user_id)
+            .with_self_id(&self.self_id)
+            .with_extra("platform", "console")
+            .with_adapter_id(&self.adapter_id);
+        if let Some(group_id) = &self.group_id {
+            metadata = metadata.with_group_id(group_id);
+        }
+
+        MessageEvent::Text {
+            text: text.to_string(),
+            metadata,
+        }
+    }
+}
+
+/// Render an outbound segment as console text
+fn render_segment(segment: &Segment) -> String {
+    match segment {
+        Segment::Text { text } => text.clone(),
+        Segment::At { user_id } => format!("@{}", user_id),
+        Segment::Reply { message_id } => format!("[reply {}] ", message_id),
+        Segment::Image { url } => format!("[image] {}", url),
+        Segment::Voice { url } => format!("[voice] {}", url),
+        Segment::Video { url } => format!("[video] {}", url),
+        Segment::File { url, name } => format!("[file {}] {}", name, url),
+        Segment::Markdown { content } => content.clone(),
+        Segment::Face { id } => format!("[face {}]", id),
+    }
+}
 
 /// Console adapter implementation
 #[derive(Debug)]
@@ -17,123 +162,88 @@ pub struct ConsoleAdapter {
     status: Arc<RwLock<AdapterStatus>>,
     statistics: Arc<RwLock<AdapterStatistics>>,
     running: Arc<RwLock<bool>>,
-    event_sender: Option<mpsc::UnboundedSender<EventEnum>>,
+    event_sender: broadcast::Sender<EventEnum>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl ConsoleAdapter {
     /// Create a new console adapter
     pub fn new(config: AdapterConfig) -> Self {
+        let (event_sender, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
         Self {
             config,
             status: Arc::new(RwLock::new(AdapterStatus::Ready)),
             statistics: Arc::new(RwLock::new(AdapterStatistics::default())),
             running: Arc::new(RwLock::new(false)),
-            event_sender: None,
+            event_sender,
+            tasks: Mutex::new(Vec::new()),
         }
     }
 
-    /// Set the event sender channel
-    pub fn set_event_sender(&mut self, sender: mpsc::UnboundedSender<EventEnum>) {
-        self.event_sender = Some(sender);
-    }
-
-    /// Start the console adapter
-    pub async fn start(&self) -> Result<()> {
-        let mut running = self.running.write().await;
-        if *running {
-            return Err(LoquatError::Adapter(AdapterError::LoadFailed(
-                "Adapter is already running".to_string()
-            )));
-        }
-
-        *running = true;
-        *self.status.write().await = AdapterStatus::Running;
-        drop(running);
-
-        // Spawn stdin reader task
-        let running_clone = Arc::clone(&self.running);
-        let status_clone = Arc::clone(&self.status);
-        let stats_clone = Arc::clone(&self.statistics);
-        let sender_clone = self.event_sender.clone();
-        let adapter_id = self.config.adapter_id.clone();
+    /// Spawn the task reading input lines from `reader`
+    async fn spawn_reader<R>(&self, reader: R)
+    where
+        R: AsyncBufRead + Unpin + Send + 'static,
+    {
+        let running = Arc::clone(&self.running);
+        let status = Arc::clone(&self.status);
+        let statistics = Arc::clone(&self.statistics);
+        let event_sender = self.event_sender.clone();
+        let mut session = ConsoleSession::from_config(&self.config);
 
-        tokio::spawn(async move {
-            let stdin = tokio::io::stdin();
-            let reader = BufReader::new(stdin);
+        let task = tokio::spawn(async move {
+            let adapter_id = session.adapter_id.clone();
             let mut lines = reader.lines();
 
-            println!("[{}] Console adapter started. Type messages and press Enter to send.", adapter_id);
-            println!("[{}] Type 'quit' or 'exit' to stop the adapter.", adapter_id);
-
-            while *running_clone.read().await {
-                match lines.next_line().await {
-                    Ok(Some(line)) => {
-                        let line = line.trim();
-                        
-                        // Check for quit command
-                        if line.eq_ignore_ascii_case("quit") || line.eq_ignore_ascii_case("exit") {
-                            println!("[{}] Stopping adapter...", adapter_id);
-                            *running_clone.write().await = false;
-                            *status_clone.write().await = AdapterStatus::Stopped;
-                            break;
-                        }
-
-                        // Create a simple message event
-                        // This is a simplified event - in a real implementation,
-                        // you would create proper event structures
-                        println!("[{}] Received: {}", adapter_id, line);
-                        
-                        // Update statistics
-                        let mut stats = stats_clone.write().await;
-                        stats.events_received += 1;
-                        stats.last_activity = Some(chrono::Utc::now().timestamp());
-                        drop(stats);
+            println!("[{}] Console adapter started, speaking as {}.", adapter_id, session.describe());
+            println!("[{}] {}", adapter_id, HELP);
 
-                        // Send event if channel exists
-                        if let Some(ref sender) = sender_clone {
-                            // For now, we'll just log that we would send an event
-                            // In a full implementation, we would construct and send EventEnum::Message
-                            println!("[{}] Event would be sent to event system", adapter_id);
-                        }
-                    }
+            loop {
+                let line = match lines.next_line().await {
+                    Ok(Some(line)) => line,
                     Ok(None) => {
-                        // EOF reached
                         println!("[{}] End of input", adapter_id);
-                        *running_clone.write().await = false;
-                        *status_clone.write().await = AdapterStatus::Stopped;
+                        *status.write().await = AdapterStatus::Stopped;
                         break;
                     }
                     Err(e) => {
                         println!("[{}] Error reading input: {}", adapter_id, e);
-                        
-                        let mut stats = stats_clone.write().await;
-                        stats.errors += 1;
+                        statistics.write().await.errors += 1;
+                        *status.write().await = AdapterStatus::Error(e.to_string());
+                        break;
+                    }
+                };
+
+                match session.parse_line(&line) {
+                    ConsoleInput::Event(event) => {
+                        let mut stats = statistics.write().await;
+                        stats.events_received += 1;
+                        stats.last_activity = Some(chrono::Utc::now().timestamp());
                         drop(stats);
-                        
-                        *running_clone.write().await = false;
-                        *status_clone.write().await = AdapterStatus::Error(e.to_string());
+
+                        if event_sender.send(*event).is_err() {
+                            println!("[{}] No subscribers, message dropped", adapter_id);
+                        }
+                    }
+                    ConsoleInput::Directive(reply) => println!("[{}] {}", adapter_id, reply),
+                    ConsoleInput::Quit => {
+                        println!("[{}] Stopping adapter...", adapter_id);
+                        *status.write().await = AdapterStatus::Stopped;
                         break;
                     }
+                    ConsoleInput::Empty => {}
                 }
             }
 
+            *running.write().await = false;
             println!("[{}] Console adapter stopped", adapter_id);
         });
 
-        Ok(())
-    }
-
-    /// Stop the console adapter
-    pub async fn stop(&self) -> Result<()> {
-        let mut running = self.running.write().await;
-        *running = false;
-        *self.status.write().await = AdapterStatus::Stopped;
-        drop(running);
-
-        Ok(())
+        self.tasks.lock().await.push(task);
     }
 }
 
+#[async_trait]
 impl Adapter for ConsoleAdapter {
     fn name(&self) -> &str {
         "ConsoleAdapter"
@@ -176,6 +286,70 @@ impl Adapter for ConsoleAdapter {
             guard.clone()
         })
     }
+
+    /// Start the console adapter
+    async fn start(&self) -> Result<()> {
+        let mut running = self.running.write().await;
+        if *running {
+            return Err(LoquatError::Adapter(AdapterError::LoadFailed(
+                "Adapter is already running".to_string()
+            )));
+        }
+
+        *running = true;
+        *self.status.write().await = AdapterStatus::Running;
+        drop(running);
+
+        self.spawn_reader(BufReader::new(tokio::io::stdin())).await;
+        Ok(())
+    }
+
+    /// Stop the console adapter
+    async fn stop(&self) -> Result<()> {
+        let mut running = self.running.write().await;
+        *running = false;
+        *self.status.write().await = AdapterStatus::Stopped;
+        drop(running);
+
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    /// Print an outbound message to stdout
+    async fn send(&self, target: Target, message: Message) -> Result<String> {
+        if !*self.running.read().await {
+            return Err(AdapterError::NotRunning(self.config.adapter_id.clone()).into());
+        }
+
+        let destination = match &target {
+            Target::User { user_id } => format!("user:{}", user_id),
+            Target::Group { group_id } => format!("group:{}", group_id),
+            Target::Channel { channel_id } => format!("channel:{}", channel_id),
+        };
+        let content = match &message {
+            Message::Text { content } => content.clone(),
+            Message::Image { url, caption: Some(caption) } => format!("[image] {} {}", url, caption),
+            Message::Image { url, caption: None } => format!("[image] {}", url),
+            Message::Voice { url, .. } => format!("[voice] {}", url),
+            Message::Video { url, .. } => format!("[video] {}", url),
+            Message::Sticker { sticker_id } => format!("[sticker] {}", sticker_id),
+            Message::Chain { segments } => segments.iter().map(render_segment).collect(),
+        };
+        println!("[{}] -> {}: {}", self.config.adapter_id, destination, content);
+
+        let mut stats = self.statistics.write().await;
+        stats.messages_sent += 1;
+        stats.last_activity = Some(chrono::Utc::now().timestamp());
+        drop(stats);
+
+        Ok(uuid::Uuid::new_v4().to_string())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.event_sender.subscribe()
+    }
 }
 
 #[cfg(test)]
@@ -214,4 +388,79 @@ mod tests {
         assert_eq!(stats.messages_sent, 0);
         assert_eq!(stats.errors, 0);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_console_adapter_send_requires_running() {
+        let config = AdapterConfig::new("console", "console-test-004", "stdio://");
+        let adapter = ConsoleAdapter::new(config);
+
+        let target = Target::User { user_id: "10001".to_string() };
+        let message = Message::Text { content: "hello".to_string() };
+        assert!(adapter.send(target.clone(), message.clone()).await.is_err());
+
+        *adapter.running.write().await = true;
+        assert!(adapter.send(target, message).await.is_ok());
+        assert_eq!(adapter.statistics().messages_sent, 1);
+    }
+
+    #[test]
+    fn test_console_session_directives() {
+        let mut session = ConsoleSession::new("console-test-005", "bot", "10001");
+
+        assert!(matches!(session.parse_line("   "), ConsoleInput::Empty));
+        assert!(matches!(session.parse_line("Exit"), ConsoleInput::Quit));
+
+        assert!(matches!(session.parse_line("/as user:20002"), ConsoleInput::Directive(_)));
+        assert!(matches!(session.parse_line("/group 30003"), ConsoleInput::Directive(_)));
+        assert_eq!(session.user_id, "20002");
+        assert_eq!(session.group_id.as_deref(), Some("30003"));
+
+        let ConsoleInput::Event(event) = session.parse_line("/start hello") else {
+            panic!("expected event");
+        };
+        let EventEnum::Message(MessageEvent::Text { text, metadata }) = *event else {
+            panic!("expected text event");
+        };
+        assert_eq!(text, "/start hello");
+        assert_eq!(metadata.user_id.as_deref(), Some("20002"));
+        assert_eq!(metadata.group_id.as_deref(), Some("30003"));
+        assert_eq!(metadata.self_id.as_deref(), Some("bot"));
+        assert_eq!(metadata.adapter_id.as_deref(), Some("console-test-005"));
+
+        session.parse_line("/private");
+        assert_eq!(session.group_id, None);
+        assert!(matches!(session.parse_line("/as"), ConsoleInput::Directive(_)));
+        assert_eq!(session.user_id, "20002");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_console_adapter_emits_events() {
+        let config = AdapterConfig::new("console", "console-test-006", "stdio://");
+        let adapter = ConsoleAdapter::new(config);
+        let mut events = adapter.subscribe();
+
+        *adapter.running.write().await = true;
+        *adapter.status.write().await = AdapterStatus::Running;
+        let input: &'static [u8] = b"/group 42\nhi there\nquit\nignored\n";
+        adapter.spawn_reader(BufReader::new(input)).await;
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let EventEnum::Message(MessageEvent::Text { text, metadata }) = event else {
+            panic!("expected text event");
+        };
+        assert_eq!(text, "hi there");
+        assert_eq!(metadata.user_id.as_deref(), Some(DEFAULT_CONSOLE_USER_ID));
+        assert_eq!(metadata.group_id.as_deref(), Some("42"));
+
+        for task in adapter.tasks.lock().await.drain(..) {
+            task.await.unwrap();
+        }
+        assert!(events.try_recv().is_err());
+        assert_eq!(adapter.status(), AdapterStatus::Stopped);
+        assert_eq!(adapter.statistics().events_received, 1);
+        assert!(!*adapter.running.read().await);
+    }
 }
@@ -1,12 +1,15 @@
 //! Echo Adapter - echoes back received messages
 
+use async_trait::async_trait;
 use crate::adapters::{
-    Adapter, AdapterConfig, AdapterStatus,
+    Adapter, AdapterConfig, AdapterStatus, Message, Target,
+    DEFAULT_EVENT_CHANNEL_CAPACITY,
     types::AdapterStatistics,
 };
+use crate::events::EventEnum;
 use crate::errors::{AdapterError, LoquatError, Result};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 /// Echo adapter implementation
 #[derive(Debug)]
@@ -15,16 +18,19 @@ pub struct EchoAdapter {
     status: Arc<RwLock<AdapterStatus>>,
     statistics: Arc<RwLock<AdapterStatistics>>,
     running: Arc<RwLock<bool>>,
+    event_sender: broadcast::Sender<EventEnum>,
 }
 
 impl EchoAdapter {
     /// Create a new echo adapter
     pub fn new(config: AdapterConfig) -> Self {
+        let (event_sender, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
         Self {
             config,
             status: Arc::new(RwLock::new(AdapterStatus::Ready)),
             statistics: Arc::new(RwLock::new(AdapterStatistics::default())),
             running: Arc::new(RwLock::new(false)),
+            event_sender,
         }
     }
 
@@ -39,34 +45,9 @@ impl EchoAdapter {
 
         format!("Echo: {}", message)
     }
-
-    /// Start the echo adapter
-    pub async fn start(&self) -> Result<()> {
-        let mut running = self.running.write().await;
-        if *running {
-            return Err(LoquatError::Adapter(AdapterError::LoadFailed(
-                "Adapter is already running".to_string()
-            )));
-        }
-
-        *running = true;
-        *self.status.write().await = AdapterStatus::Running;
-        drop(running);
-
-        Ok(())
-    }
-
-    /// Stop the echo adapter
-    pub async fn stop(&self) -> Result<()> {
-        let mut running = self.running.write().await;
-        *running = false;
-        *self.status.write().await = AdapterStatus::Stopped;
-        drop(running);
-
-        Ok(())
-    }
 }
 
+#[async_trait]
 impl Adapter for EchoAdapter {
     fn name(&self) -> &str {
         "EchoAdapter"
@@ -109,6 +90,53 @@ impl Adapter for EchoAdapter {
             guard.clone()
         })
     }
+
+    /// Start the echo adapter
+    async fn start(&self) -> Result<()> {
+        let mut running = self.running.write().await;
+        if *running {
+            return Err(LoquatError::Adapter(AdapterError::LoadFailed(
+                "Adapter is already running".to_string()
+            )));
+        }
+
+        *running = true;
+        *self.status.write().await = AdapterStatus::Running;
+        drop(running);
+
+        Ok(())
+    }
+
+    /// Stop the echo adapter
+    async fn stop(&self) -> Result<()> {
+        let mut running = self.running.write().await;
+        *running = false;
+        *self.status.write().await = AdapterStatus::Stopped;
+        drop(running);
+
+        Ok(())
+    }
+
+    /// Echo an outbound message back instead of delivering it
+    async fn send(&self, _target: Target, message: Message) -> Result<String> {
+        if !*self.running.read().await {
+            return Err(AdapterError::NotRunning(self.config.adapter_id.clone()).into());
+        }
+
+        if let Message::Text { content } = &message {
+            self.echo(content).await;
+        } else {
+            let mut stats = self.statistics.write().await;
+            stats.messages_sent += 1;
+            stats.last_activity = Some(chrono::Utc::now().timestamp());
+        }
+
+        Ok(uuid::Uuid::new_v4().to_string())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.event_sender.subscribe()
+    }
 }
 
 #[cfg(test)]
@@ -147,4 +175,24 @@ mod tests {
         assert_eq!(stats.messages_sent, 0);
         assert_eq!(stats.errors, 0);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_echo_adapter_lifecycle_and_send() {
+        let config = AdapterConfig::new("echo", "echo-test-004", "echo://");
+        let adapter = EchoAdapter::new(config);
+
+        let target = Target::User { user_id: "10001".to_string() };
+        let message = Message::Text { content: "ping".to_string() };
+        assert!(adapter.send(target.clone(), message.clone()).await.is_err());
+
+        adapter.start().await.unwrap();
+        assert!(adapter.is_running());
+        assert!(adapter.start().await.is_err());
+
+        assert!(!adapter.send(target, message).await.unwrap().is_empty());
+        assert_eq!(adapter.statistics().messages_sent, 1);
+
+        adapter.stop().await.unwrap();
+        assert_eq!(adapter.status(), AdapterStatus::Stopped);
+    }
 }
@@ -123,6 +123,7 @@ mod tests {
         config: AdapterConfig,
     }
 
+    #[async_trait::async_trait]
     impl crate::adapters::Adapter for MockAdapter {
         fn name(&self) -> &str {
             "MockAdapter"
@@ -155,6 +156,22 @@ mod tests {
         fn statistics(&self) -> crate::adapters::AdapterStatistics {
             crate::adapters::AdapterStatistics::default()
         }
+
+        async fn start(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, _target: crate::adapters::Target, _message: crate::adapters::Message) -> Result<String> {
+            Ok("mock-msg".to_string())
+        }
+
+        fn subscribe(&self) -> tokio::sync::broadcast::Receiver<crate::events::EventEnum> {
+            tokio::sync::broadcast::channel(1).1
+        }
     }
 
     /// Mock factory for testing
@@ -3,18 +3,19 @@
 use crate::adapters::factory::{AdapterFactoryRegistry, AdapterFactory};
 use crate::adapters::config::AdapterConfig as AdapterInstanceConfig;
 use crate::adapters::status::AdapterStatus;
-use crate::adapters::{Adapter};
+use crate::adapters::{Adapter, Message, Target};
 use crate::adapters::types::{AdapterInfo, AdapterStatistics};
 use crate::adapters::state_manager::AdapterStateManager;
 use crate::logging::traits::{LogContext, LogLevel, Logger};
 use crate::errors::{AdapterError, Result};
+use crate::events::EventEnum;
 use crate::config::loquat_config::AdapterConfig as ManagerConfig;
 use crate::utils::{LruCache, HotReloadHistory, VersionData};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 pub type AdapterManagerConfig = ManagerConfig;
 
@@ -137,6 +138,109 @@ impl AdapterManager {
         adapters.iter().filter(|a| a.status().is_active()).count()
     }
 
+    /// Add an already constructed adapter instance
+    pub async fn add_adapter(&self, adapter: Box<dyn Adapter>) -> Result<()> {
+        let mut adapters = self.adapters.write().await;
+        if adapters.iter().any(|a| a.adapter_id() == adapter.adapter_id()) {
+            return Err(AdapterError::AlreadyLoaded(adapter.adapter_id().to_string()).into());
+        }
+        adapters.push(Arc::from(adapter));
+        Ok(())
+    }
+
+    /// Start a loaded adapter
+    pub async fn start_adapter(&self, adapter_id: &str) -> Result<()> {
+        let adapter = self.get_adapter(adapter_id).await
+            .ok_or_else(|| AdapterError::NotFound(adapter_id.to_string()))?;
+
+        let mut log_context = LogContext::new();
+        log_context.component = Some("AdapterManager".to_string());
+        log_context.add("adapter_id", adapter_id.to_string());
+
+        adapter.start().await.map_err(|e| {
+            self.logger.log(
+                LogLevel::Error,
+                &format!("Failed to start adapter {}: {}", adapter_id, e),
+                &log_context,
+            );
+            e
+        })?;
+
+        self.logger.log(
+            LogLevel::Info,
+            &format!("Adapter {} started", adapter_id),
+            &log_context,
+        );
+
+        Ok(())
+    }
+
+    /// Stop a loaded adapter
+    pub async fn stop_adapter(&self, adapter_id: &str) -> Result<()> {
+        let adapter = self.get_adapter(adapter_id).await
+            .ok_or_else(|| AdapterError::NotFound(adapter_id.to_string()))?;
+
+        adapter.stop().await?;
+
+        let mut log_context = LogContext::new();
+        log_context.component = Some("AdapterManager".to_string());
+        log_context.add("adapter_id", adapter_id.to_string());
+
+        self.logger.log(
+            LogLevel::Info,
+            &format!("Adapter {} stopped", adapter_id),
+            &log_context,
+        );
+
+        Ok(())
+    }
+
+    /// Start all loaded adapters that are not running yet
+    pub async fn start_all(&self) -> Result<()> {
+        for adapter in self.list_adapters().await {
+            if adapter.is_running() {
+                continue;
+            }
+            let _ = self.start_adapter(adapter.adapter_id()).await;
+        }
+        Ok(())
+    }
+
+    /// Stop all running adapters
+    pub async fn stop_all(&self) -> Result<()> {
+        for adapter in self.list_adapters().await {
+            if adapter.is_running() {
+                let _ = self.stop_adapter(adapter.adapter_id()).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Send a message through an adapter, returning the platform message ID
+    pub async fn send_message(&self, adapter_id: &str, target: Target, message: Message) -> Result<String> {
+        let adapter = self.get_adapter(adapter_id).await
+            .ok_or_else(|| AdapterError::NotFound(adapter_id.to_string()))?;
+        adapter.send(target, message).await
+    }
+
+    /// Send a message through an adapter from a bot account (`self_id`)
+    pub async fn send_message_as(
+        &self,
+        adapter_id: &str,
+        self_id: Option<&str>,
+        target: Target,
+        message: Message,
+    ) -> Result<String> {
+        let adapter = self.get_adapter(adapter_id).await
+            .ok_or_else(|| AdapterError::NotFound(adapter_id.to_string()))?;
+        adapter.send_as(self_id, target, message).await
+    }
+
+    /// Subscribe to the events of an adapter
+    pub async fn subscribe(&self, adapter_id: &str) -> Option<broadcast::Receiver<EventEnum>> {
+        self.get_adapter(adapter_id).await.map(|a| a.subscribe())
+    }
+
     pub async fn discover_adapters(&self) -> Result<Vec<PathBuf>> {
         let adapter_dir = PathBuf::from(&self.config.adapter_dir);
 
@@ -270,11 +374,17 @@ impl AdapterManager {
             "unknown"
         };
 
-        Ok(AdapterInstanceConfig::new(
+        let mut config = AdapterInstanceConfig::new(
             adapter_type,
             &adapter_id,
             "ws://localhost:8080",
-        ))
+        );
+        // Telegram is reached over the Bot API, not a WebSocket
+        if adapter_type == "telegram" {
+            config.connection.conn_type = "polling".to_string();
+            config.connection.url = String::new();
+        }
+        Ok(config)
     }
 
     pub async fn unload_adapter(&self, adapter_id: &str) -> Result<()> {
@@ -298,9 +408,13 @@ impl AdapterManager {
             .position(|a| a.adapter_id() == adapter_id)
             .ok_or_else(|| AdapterError::NotFound(adapter_id.to_string()))?;
 
-        let _adapter = adapters.remove(adapter_index);
+        let adapter = adapters.remove(adapter_index);
         drop(adapters);
 
+        if adapter.is_running() {
+            adapter.stop().await?;
+        }
+
         self.logger.log(
             LogLevel::Info,
             &format!("Adapter {} unloaded successfully", adapter_id),
@@ -615,4 +729,33 @@ mod tests {
         assert!(config.should_load("wechat"));
         assert!(config.should_load("telegram"));
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_adapter_manager_lifecycle() {
+        let logger = create_test_logger();
+        let manager = AdapterManager::new(AdapterManagerConfig::default(), logger);
+
+        let config = AdapterInstanceConfig::new("echo", "echo-001", "echo://");
+        let adapter = crate::adapters::EchoAdapter::new(config);
+        manager.add_adapter(Box::new(adapter)).await.unwrap();
+
+        let duplicate = crate::adapters::EchoAdapter::new(
+            AdapterInstanceConfig::new("echo", "echo-001", "echo://"),
+        );
+        assert!(manager.add_adapter(Box::new(duplicate)).await.is_err());
+
+        let target = Target::User { user_id: "10001".to_string() };
+        let message = Message::Text { content: "hi".to_string() };
+        assert!(manager.send_message("echo-001", target.clone(), message.clone()).await.is_err());
+
+        manager.start_all().await.unwrap();
+        assert_eq!(manager.active_adapter_count().await, 1);
+        assert!(manager.send_message("echo-001", target.clone(), message.clone()).await.is_ok());
+        assert!(manager.send_message("missing", target, message).await.is_err());
+        assert!(manager.subscribe("echo-001").await.is_some());
+
+        manager.stop_all().await.unwrap();
+        let adapter = manager.get_adapter("echo-001").await.unwrap();
+        assert_eq!(adapter.status(), AdapterStatus::Stopped);
+    }
 }
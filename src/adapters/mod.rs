@@ -15,6 +15,12 @@ pub mod console_factory;
 pub mod echo_adapter;
 pub mod echo_factory;
 pub mod state_manager;
+pub mod segment;
+pub mod supervisor;
+pub mod onebot11;
+pub mod onebot12;
+pub mod satori;
+pub mod telegram;
 
 pub use traits::*;
 pub use config::*;
@@ -27,3 +33,9 @@ pub use console_adapter::*;
 pub use console_factory::*;
 pub use echo_adapter::*;
 pub use echo_factory::*;
+pub use segment::{MessageBuilder, Segment};
+pub use supervisor::AdapterSupervisor;
+pub use onebot11::{OneBot11Adapter, OneBot11AdapterFactory, OneBot11Converter, OneBot11Mode, OneBotSession};
+pub use onebot12::{OneBot12Adapter, OneBot12AdapterFactory, OneBot12Converter};
+pub use satori::{SatoriAdapter, SatoriAdapterFactory, SatoriConverter};
+pub use telegram::{TelegramAdapter, TelegramAdapterFactory, TelegramConverter, TelegramMode};
@@ -0,0 +1,634 @@
+//! OneBot v11 adapter - forward WebSocket client
+
+use async_trait::async_trait;
+use crate::adapters::converter::ConversionContext;
+use crate::adapters::onebot11::converter::OneBot11Converter;
+use crate::adapters::onebot11::server::{http_post_router, reverse_ws_router, OneBot11ServerState};
+use crate::adapters::onebot11::session::{HttpApiClient, OneBotSession};
+use crate::adapters::onebot11::types::message_to_segments;
+use crate::adapters::{
+    Adapter, AdapterConfig, AdapterStatus, Message, Target,
+    DEFAULT_EVENT_CHANNEL_CAPACITY,
+    types::AdapterStatistics,
+};
+use crate::errors::{AdapterError, LoquatError, Result};
+use crate::events::EventEnum;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+/// OneBot v11 connection mode, selected by `connection.conn_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OneBot11Mode {
+    /// Dial out to the implementation's WebSocket server (`ws`)
+    ForwardWs,
+    /// Accept connections on `/onebot/v11/ws` (`ws_reverse`)
+    ReverseWs,
+    /// Accept event posts on `/onebot/v11/http` (`http_post`)
+    HttpPost,
+}
+
+impl OneBot11Mode {
+    /// Parse a connection type
+    pub fn from_conn_type(conn_type: &str) -> Option<Self> {
+        match conn_type.to_lowercase().as_str() {
+            "ws" | "ws_forward" | "forward_ws" => Some(Self::ForwardWs),
+            "ws_reverse" | "reverse_ws" => Some(Self::ReverseWs),
+            "http_post" | "http" | "webhook" => Some(Self::HttpPost),
+            _ => None,
+        }
+    }
+
+    /// Check if this mode binds a listener
+    pub fn is_server(&self) -> bool {
+        !matches!(self, Self::ForwardWs)
+    }
+}
+
+/// OneBot v11 adapter implementation (NapCat / Lagrange)
+#[derive(Debug)]
+pub struct OneBot11Adapter {
+    config: AdapterConfig,
+    mode: OneBot11Mode,
+    local_addr: RwLock<Option<SocketAddr>>,
+    status: Arc<RwLock<AdapterStatus>>,
+    statistics: Arc<RwLock<AdapterStatistics>>,
+    session: Arc<OneBotSession>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl OneBot11Adapter {
+    /// Create a new OneBot v11 adapter
+    pub fn new(config: AdapterConfig) -> Self {
+        let (event_sender, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
+        let statistics = Arc::new(RwLock::new(AdapterStatistics::default()));
+        let self_id = config.platform["self_id"].as_str().unwrap_or_default().to_string();
+        let converter = OneBot11Converter::new(ConversionContext::new(
+            &config.adapter_id,
+            "onebot11",
+            &self_id,
+        ));
+        let mut session = OneBotSession::new(
+            converter,
+            event_sender,
+            Arc::clone(&statistics),
+            Duration::from_secs(config.connection.timeout.max(1)),
+        )
+        .with_default_account(&self_id);
+        if let Some(api_url) = config.platform["api_url"].as_str() {
+            let token = config.platform["access_token"].as_str().filter(|t| !t.is_empty());
+            session = session.with_http_api(HttpApiClient::new(api_url, token));
+        }
+        let mode = OneBot11Mode::from_conn_type(&config.connection.conn_type)
+            .unwrap_or(OneBot11Mode::ForwardWs);
+
+        Self {
+            config,
+            mode,
+            local_addr: RwLock::new(None),
+            status: Arc::new(RwLock::new(AdapterStatus::Ready)),
+            statistics,
+            session: Arc::new(session),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get the access token from platform configuration
+    pub fn access_token(&self) -> Option<&str> {
+        self.config.platform["access_token"].as_str().filter(|t| !t.is_empty())
+    }
+
+    /// Get the underlying session
+    pub fn session(&self) -> Arc<OneBotSession> {
+        Arc::clone(&self.session)
+    }
+
+    /// Call a raw OneBot action and return its response data
+    pub async fn call_action(&self, action: &str, params: Value) -> Result<Value> {
+        self.session.call_action(action, params).await
+    }
+
+    /// Send a private message, returning the message ID
+    pub async fn send_private_msg(&self, user_id: &str, segments: Vec<Value>) -> Result<String> {
+        let target = Target::User { user_id: user_id.to_string() };
+        self.send_segments(None, &target, segments).await
+    }
+
+    /// Send a group message, returning the message ID
+    pub async fn send_group_msg(&self, group_id: &str, segments: Vec<Value>) -> Result<String> {
+        let target = Target::Group { group_id: group_id.to_string() };
+        self.send_segments(None, &target, segments).await
+    }
+
+    /// Send segments from an account via `send_private_msg` / `send_group_msg`
+    async fn send_segments(&self, self_id: Option<&str>, target: &Target, segments: Vec<Value>) -> Result<String> {
+        let (action, params) = match target {
+            Target::User { user_id } => ("send_private_msg", json!({"user_id": id_param(user_id), "message": segments})),
+            Target::Group { group_id } => ("send_group_msg", json!({"group_id": id_param(group_id), "message": segments})),
+            Target::Channel { channel_id } => {
+                return Err(AdapterError::SendFailed(format!(
+                    "OneBot v11 does not support channel targets: {}",
+                    channel_id
+                ))
+                .into());
+            }
+        };
+        let data = self.session.call_action_as(self_id, action, params).await?;
+        Ok(message_id_of(&data))
+    }
+
+    /// Recall a message
+    pub async fn delete_msg(&self, message_id: &str) -> Result<()> {
+        self.call_action("delete_msg", json!({"message_id": id_param(message_id)}))
+            .await
+            .map(|_| ())
+    }
+
+    /// Handle a friend request (the flag comes from `metadata.extra["flag"]`)
+    pub async fn set_friend_add_request(&self, flag: &str, approve: bool, remark: Option<&str>) -> Result<()> {
+        self.call_action(
+            "set_friend_add_request",
+            json!({"flag": flag, "approve": approve, "remark": remark.unwrap_or_default()}),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Handle a group join request or invitation
+    pub async fn set_group_add_request(
+        &self,
+        flag: &str,
+        sub_type: &str,
+        approve: bool,
+        reason: Option<&str>,
+    ) -> Result<()> {
+        self.call_action(
+            "set_group_add_request",
+            json!({
+                "flag": flag,
+                "sub_type": sub_type,
+                "approve": approve,
+                "reason": reason.unwrap_or_default(),
+            }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Get the bot's login info
+    pub async fn get_login_info(&self) -> Result<Value> {
+        self.call_action("get_login_info", json!({})).await
+    }
+
+    /// Get the connection mode
+    pub fn mode(&self) -> OneBot11Mode {
+        self.mode
+    }
+
+    /// Get the bound listener address (server modes, after start)
+    pub async fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.read().await
+    }
+
+    /// Connect to the forward WebSocket and start reading frames
+    async fn start_forward_ws(&self) -> Result<()> {
+        let account = self.config.platform["self_id"].as_str().unwrap_or_default();
+        let connect_timeout = Duration::from_secs(self.config.connection.timeout.max(1));
+        let tasks = connect_forward_ws(
+            &self.session,
+            &self.config.connection.url,
+            self.access_token(),
+            connect_timeout,
+            account,
+            Arc::clone(&self.status),
+        )
+        .await?;
+        self.tasks.lock().await.extend(tasks);
+
+        Ok(())
+    }
+
+    /// Bind the reverse WebSocket / HTTP POST listener
+    async fn start_server(&self) -> Result<()> {
+        let addr = bind_address(&self.config.connection.url);
+        let listener = TcpListener::bind(&addr).await.map_err(|e| {
+            let reason = format!("Failed to bind to {}: {}", addr, e);
+            AdapterError::ConnectionFailed(reason)
+        });
+        let listener = match listener {
+            Ok(listener) => listener,
+            Err(e) => {
+                *self.status.write().await = AdapterStatus::Error(e.to_string());
+                return Err(e.into());
+            }
+        };
+        *self.local_addr.write().await = listener.local_addr().ok();
+
+        let state = OneBot11ServerState::new(Arc::clone(&self.session), self.access_token());
+        let router = match self.mode {
+            OneBot11Mode::HttpPost => http_post_router(state),
+            _ => reverse_ws_router(state),
+        };
+
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        self.tasks.lock().await.push(server);
+        *self.status.write().await = AdapterStatus::Running;
+
+        Ok(())
+    }
+}
+
+/// Connect to a OneBot forward WebSocket and spawn the writer/reader tasks
+///
+/// Sets `status` to `Running` once connected and to `Error` if the connection
+/// fails or is closed by the remote while running.
+pub(crate) async fn connect_forward_ws(
+    session: &Arc<OneBotSession>,
+    url: &str,
+    access_token: Option<&str>,
+    connect_timeout: Duration,
+    account: &str,
+    status: Arc<RwLock<AdapterStatus>>,
+) -> Result<Vec<JoinHandle<()>>> {
+    let mut request = url.into_client_request()
+        .map_err(|e| AdapterError::InvalidConfig(format!("Invalid OneBot URL: {}", e)))?;
+    if let Some(token) = access_token {
+        let header = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|e| AdapterError::InvalidConfig(format!("Invalid access token: {}", e)))?;
+        request.headers_mut().insert("Authorization", header);
+    }
+
+    let connected = tokio::time::timeout(connect_timeout, tokio_tungstenite::connect_async(request)).await;
+    let ws_stream = match connected {
+        Ok(Ok((ws_stream, _))) => ws_stream,
+        Ok(Err(e)) => {
+            let reason = format!("Failed to connect to {}: {}", url, e);
+            *status.write().await = AdapterStatus::Error(reason.clone());
+            return Err(AdapterError::ConnectionFailed(reason).into());
+        }
+        Err(_) => {
+            let reason = format!("Timed out connecting to {}", url);
+            *status.write().await = AdapterStatus::Error(reason.clone());
+            return Err(AdapterError::ConnectionFailed(reason).into());
+        }
+    };
+
+    let (mut sink, mut stream) = ws_stream.split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let token = session.attach(account, tx).await;
+    *status.write().await = AdapterStatus::Running;
+
+    let writer = tokio::spawn(async move {
+        while let Some(frame) = rx.recv().await {
+            if sink.send(WsMessage::Text(frame)).await.is_err() {
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    let session = Arc::clone(session);
+    let account = account.to_string();
+    let reader = tokio::spawn(async move {
+        while let Some(message) = stream.next().await {
+            match message {
+                Ok(WsMessage::Text(text)) => {
+                    let _ = session.handle_frame(&text).await;
+                }
+                Ok(WsMessage::Binary(bytes)) => {
+                    if let Ok(text) = String::from_utf8(bytes) {
+                        let _ = session.handle_frame(&text).await;
+                    }
+                }
+                Ok(WsMessage::Close(_)) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    session.detach(&account, token).await;
+                    *status.write().await = AdapterStatus::Error(e.to_string());
+                    return;
+                }
+            }
+        }
+        session.detach(&account, token).await;
+        let mut status = status.write().await;
+        if *status == AdapterStatus::Running {
+            *status = AdapterStatus::Error("Connection closed by remote".to_string());
+        }
+    });
+
+    Ok(vec![writer, reader])
+}
+
+/// Encode an ID as a number when possible (OneBot v11 uses int64 IDs)
+pub(crate) fn id_param(id: &str) -> Value {
+    id.parse::<i64>().map(Value::from).unwrap_or_else(|_| Value::from(id))
+}
+
+/// Strip the scheme and path from a listener URL (`http://0.0.0.0:8080/` -> `0.0.0.0:8080`)
+pub(crate) fn bind_address(url: &str) -> String {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    without_scheme.split('/').next().unwrap_or(without_scheme).to_string()
+}
+
+/// Extract `message_id` from an action response
+pub(crate) fn message_id_of(data: &Value) -> String {
+    crate::adapters::onebot11::types::value_to_id(&data["message_id"]).unwrap_or_default()
+}
+
+#[async_trait]
+impl Adapter for OneBot11Adapter {
+    fn name(&self) -> &str {
+        "OneBot11Adapter"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn adapter_id(&self) -> &str {
+        &self.config.adapter_id
+    }
+
+    fn config(&self) -> AdapterConfig {
+        self.config.clone()
+    }
+
+    fn status(&self) -> AdapterStatus {
+        // Use blocking read for synchronous method
+        tokio::task::block_in_place(|| {
+            let guard = tokio::runtime::Handle::current()
+                .block_on(self.status.read());
+            guard.clone()
+        })
+    }
+
+    fn statistics(&self) -> AdapterStatistics {
+        // Use blocking read for synchronous method
+        tokio::task::block_in_place(|| {
+            let guard = tokio::runtime::Handle::current()
+                .block_on(self.statistics.read());
+            guard.clone()
+        })
+    }
+
+    /// Connect (forward WebSocket) or bind the listener (server modes)
+    async fn start(&self) -> Result<()> {
+        if *self.status.read().await == AdapterStatus::Running {
+            return Err(LoquatError::Adapter(AdapterError::LoadFailed(
+                "Adapter is already running".to_string()
+            )));
+        }
+        *self.status.write().await = AdapterStatus::Initializing;
+
+        match self.mode {
+            OneBot11Mode::ForwardWs => self.start_forward_ws().await,
+            OneBot11Mode::ReverseWs | OneBot11Mode::HttpPost => self.start_server().await,
+        }
+    }
+
+    /// Close the connection
+    async fn stop(&self) -> Result<()> {
+        *self.status.write().await = AdapterStatus::Stopped;
+        self.session.detach_all().await;
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    /// Send a message via `send_private_msg` / `send_group_msg`
+    async fn send(&self, target: Target, message: Message) -> Result<String> {
+        self.send_as(None, target, message).await
+    }
+
+    /// Send a message from the connection of account `self_id`
+    async fn send_as(&self, self_id: Option<&str>, target: Target, message: Message) -> Result<String> {
+        let segments = message_to_segments(&message);
+        let message_id = self.send_segments(self_id, &target, segments).await?;
+
+        let mut stats = self.statistics.write().await;
+        stats.messages_sent += 1;
+        stats.last_activity = Some(chrono::Utc::now().timestamp());
+        drop(stats);
+
+        Ok(message_id)
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.session.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::onebot11::server::{HTTP_POST_PATH, REVERSE_WS_PATH};
+    use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+
+    #[allow(clippy::result_large_err)]
+    fn check_token(req: &Request, resp: Response) -> std::result::Result<Response, ErrorResponse> {
+        let auth = req.headers().get("Authorization").and_then(|v| v.to_str().ok());
+        assert_eq!(auth, Some("Bearer secret"));
+        Ok(resp)
+    }
+
+    /// Spawn a mock OneBot implementation that pushes one event and answers actions
+    async fn spawn_mock_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let ws = tokio_tungstenite::accept_hdr_async(socket, check_token).await.unwrap();
+            let (mut sink, mut stream) = ws.split();
+
+            let event = json!({
+                "post_type": "message", "message_type": "group", "sub_type": "normal",
+                "self_id": 10000, "user_id": 12345, "group_id": 67890, "message_id": 1,
+                "message": [{"type": "text", "data": {"text": "hello"}}],
+                "sender": {"nickname": "alice"}
+            });
+            sink.send(WsMessage::Text(event.to_string())).await.unwrap();
+
+            while let Some(Ok(WsMessage::Text(text))) = stream.next().await {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                let response = match request["action"].as_str() {
+                    Some("send_group_msg") => json!({
+                        "status": "ok", "retcode": 0,
+                        "data": {"message_id": 555}, "echo": request["echo"]
+                    }),
+                    _ => json!({
+                        "status": "failed", "retcode": 1404,
+                        "data": null, "wording": "unknown action", "echo": request["echo"]
+                    }),
+                };
+                sink.send(WsMessage::Text(response.to_string())).await.unwrap();
+            }
+        });
+
+        format!("ws://{}", addr)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_onebot11_forward_ws() {
+        let url = spawn_mock_server().await;
+        let config = AdapterConfig::new("onebot11", "onebot-001", &url)
+            .with_platform_config("access_token", "secret")
+            .unwrap();
+        let adapter = OneBot11Adapter::new(config);
+        let mut events = adapter.subscribe();
+
+        adapter.start().await.unwrap();
+        assert!(adapter.is_running());
+
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type(), "message.text");
+        assert_eq!(event.group_id(), Some("67890"));
+
+        let target = Target::Group { group_id: "67890".to_string() };
+        let message = Message::Text { content: "pong".to_string() };
+        let message_id = adapter.send(target, message).await.unwrap();
+        assert_eq!(message_id, "555");
+
+        let result = adapter.set_friend_add_request("flag-1", true, None).await;
+        assert!(result.is_err());
+
+        let stats = adapter.statistics();
+        assert_eq!(stats.events_received, 1);
+        assert_eq!(stats.messages_sent, 1);
+
+        adapter.stop().await.unwrap();
+        assert_eq!(adapter.status(), AdapterStatus::Stopped);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_onebot11_connect_failure() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let adapter = OneBot11Adapter::new(AdapterConfig::new("onebot11", "onebot-002", &url));
+        assert!(adapter.start().await.is_err());
+        assert!(adapter.status().is_error());
+    }
+
+    fn server_config(adapter_id: &str, conn_type: &str) -> AdapterConfig {
+        let mut config = AdapterConfig::new("onebot11", adapter_id, "127.0.0.1:0")
+            .with_platform_config("access_token", "secret")
+            .unwrap();
+        config.connection.conn_type = conn_type.to_string();
+        config
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_onebot11_reverse_ws() {
+        let adapter = OneBot11Adapter::new(server_config("onebot-003", "ws_reverse"));
+        let mut events = adapter.subscribe();
+        adapter.start().await.unwrap();
+        assert!(adapter.is_running());
+        let url = format!("ws://{}{}", adapter.local_addr().await.unwrap(), REVERSE_WS_PATH);
+
+        let mut request = url.as_str().into_client_request().unwrap();
+        request.headers_mut().insert("Authorization", HeaderValue::from_static("Bearer wrong"));
+        assert!(tokio_tungstenite::connect_async(request).await.is_err());
+
+        let mut request = url.as_str().into_client_request().unwrap();
+        request.headers_mut().insert("Authorization", HeaderValue::from_static("Bearer secret"));
+        request.headers_mut().insert("X-Self-ID", HeaderValue::from_static("20000"));
+        let (ws, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+        let (mut sink, mut stream) = ws.split();
+
+        let event = json!({
+            "post_type": "message", "message_type": "private", "user_id": 12345,
+            "message": [{"type": "text", "data": {"text": "hi"}}]
+        });
+        sink.send(WsMessage::Text(event.to_string())).await.unwrap();
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.self_id(), Some("20000"));
+        assert_eq!(adapter.session().connected_accounts().await, vec!["20000".to_string()]);
+
+        tokio::spawn(async move {
+            while let Some(Ok(WsMessage::Text(text))) = stream.next().await {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                let response = json!({
+                    "status": "ok", "retcode": 0,
+                    "data": {"message_id": 777}, "echo": request["echo"]
+                });
+                sink.send(WsMessage::Text(response.to_string())).await.unwrap();
+            }
+        });
+
+        let target = Target::User { user_id: "12345".to_string() };
+        let message = Message::Text { content: "pong".to_string() };
+        assert_eq!(adapter.send(target, message).await.unwrap(), "777");
+
+        adapter.stop().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_onebot11_http_post() {
+        let api = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_url = format!("http://{}", api.local_addr().unwrap());
+        let router = axum::Router::new().route(
+            "/send_group_msg",
+            axum::routing::post(|| async {
+                axum::Json(json!({"status": "ok", "retcode": 0, "data": {"message_id": 888}}))
+            }),
+        );
+        tokio::spawn(async move { axum::serve(api, router).await.unwrap() });
+
+        let config = server_config("onebot-004", "http_post")
+            .with_platform_config("api_url", api_url)
+            .unwrap();
+        let adapter = OneBot11Adapter::new(config);
+        let mut events = adapter.subscribe();
+        adapter.start().await.unwrap();
+        let url = format!("http://{}{}", adapter.local_addr().await.unwrap(), HTTP_POST_PATH);
+
+        let client = reqwest::Client::new();
+        let event = json!({
+            "post_type": "notice", "notice_type": "friend_add", "self_id": 30000, "user_id": 5
+        });
+        let response = client.post(&url).bearer_auth("wrong").json(&event).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+        let response = client.post(&url).bearer_auth("secret").json(&event).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.self_id(), Some("30000"));
+
+        let target = Target::Group { group_id: "67890".to_string() };
+        let message = Message::Text { content: "pong".to_string() };
+        assert_eq!(adapter.send(target, message).await.unwrap(), "888");
+
+        adapter.stop().await.unwrap();
+    }
+
+    #[test]
+    fn test_bind_address() {
+        assert_eq!(bind_address("http://0.0.0.0:8080/onebot"), "0.0.0.0:8080");
+        assert_eq!(bind_address("127.0.0.1:3000"), "127.0.0.1:3000");
+    }
+
+    #[test]
+    fn test_id_param() {
+        assert_eq!(id_param("123"), json!(123));
+        assert_eq!(id_param("abc"), json!("abc"));
+    }
+}
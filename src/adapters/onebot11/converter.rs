@@ -0,0 +1,541 @@
+//! OneBot v11 event converter
+
+use crate::adapters::converter::{
+    ConversionContext, EventConverter, MessageConverter, MetaConverter, NoticeConverter,
+    RequestConverter,
+};
+use crate::adapters::onebot11::types::{normalize_segments, segment_data_str, value_to_id};
+use crate::errors::{AdapterError, Result};
+use crate::events::{
+    EventEnum, EventMetadata, EventSource, LifecyclePhase, MessageEvent, MessageSegment, MetaEvent,
+    NoticeEvent, RequestEvent,
+};
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+
+/// Converts OneBot v11 posts into Loquat events
+#[derive(Debug, Clone)]
+pub struct OneBot11Converter {
+    context: ConversionContext,
+}
+
+impl OneBot11Converter {
+    /// Create a new converter
+    pub fn new(context: ConversionContext) -> Self {
+        Self { context }
+    }
+
+    /// Get the conversion context
+    pub fn context(&self) -> &ConversionContext {
+        &self.context
+    }
+
+    /// Build metadata shared by all events of a post
+    fn metadata(&self, post: &Value, source: EventSource) -> EventMetadata {
+        let post_type = post["post_type"].as_str().unwrap_or("unknown");
+        let mut metadata = EventMetadata::new(&format!("onebot11.{}", post_type))
+            .with_source(source)
+            .with_extra("platform", &self.context.platform_type)
+            .with_adapter_id(&self.context.adapter_id);
+
+        if let Some(time) = post["time"].as_i64()
+            && let Some(ts) = Utc.timestamp_opt(time, 0).single()
+        {
+            metadata.timestamp = ts;
+        }
+
+        match value_to_id(&post["self_id"]) {
+            Some(self_id) => metadata = metadata.with_self_id(&self_id),
+            None if !self.context.self_id.is_empty() => {
+                metadata = metadata.with_self_id(&self.context.self_id)
+            }
+            None => {}
+        }
+        if let Some(user_id) = value_to_id(&post["user_id"]) {
+            metadata = metadata.with_user_id(&user_id);
+        }
+        if let Some(group_id) = value_to_id(&post["group_id"]) {
+            metadata = metadata.with_group_id(&group_id);
+        }
+        if self.context.options.include_raw {
+            metadata = metadata.with_extra("raw", post);
+        }
+
+        metadata
+    }
+
+    fn require_id(post: &Value, key: &str) -> Result<String> {
+        value_to_id(&post[key]).ok_or_else(|| {
+            AdapterError::ConversionFailed(format!("missing field `{}`", key)).into()
+        })
+    }
+}
+
+impl EventConverter<Value> for OneBot11Converter {
+    fn convert(&self, event: Value) -> Result<EventEnum> {
+        match event["post_type"].as_str() {
+            Some("message") | Some("message_sent") => {
+                self.convert_message(event).map(EventEnum::Message)
+            }
+            Some("notice") => self.convert_notice(event).map(EventEnum::Notice),
+            Some("request") => self.convert_request(event).map(EventEnum::Request),
+            Some("meta_event") => self.convert_meta(event).map(EventEnum::Meta),
+            other => Err(AdapterError::ConversionFailed(format!(
+                "unsupported post_type: {:?}",
+                other
+            ))
+            .into()),
+        }
+    }
+
+    fn supported_types(&self) -> Vec<String> {
+        vec![
+            "message".to_string(),
+            "message_sent".to_string(),
+            "notice".to_string(),
+            "request".to_string(),
+            "meta_event".to_string(),
+        ]
+    }
+}
+
+impl MessageConverter<Value> for OneBot11Converter {
+    fn convert_message(&self, message: Value) -> Result<MessageEvent> {
+        let segments = normalize_segments(&message["message"]);
+        let mut metadata = self
+            .metadata(&message, EventSource::User)
+            .with_extra("message_type", message["message_type"].clone())
+            .with_extra("sub_type", message["sub_type"].clone());
+
+        if let Some(message_id) = value_to_id(&message["message_id"]) {
+            metadata = metadata.with_extra("message_id", message_id);
+        }
+        if let Some(nickname) = message["sender"]["nickname"].as_str() {
+            metadata = metadata.with_extra("sender_nickname", nickname);
+        }
+        if let Some(card) = message["sender"]["card"].as_str().filter(|c| !c.is_empty()) {
+            metadata = metadata.with_extra("sender_card", card);
+        }
+
+        Ok(segments_to_message_event(&segments, metadata))
+    }
+
+    fn supports_message(&self, message_type: &str) -> bool {
+        matches!(message_type, "private" | "group")
+    }
+}
+
+/// Fold a list of OneBot v11 segments into a single `MessageEvent`
+///
+/// Messages mixing content a single-content variant cannot hold (e.g. a
+/// quote with mentions, or several images) become `MessageEvent::Chain`.
+pub fn segments_to_message_event(segments: &[Value], metadata: EventMetadata) -> MessageEvent {
+    if !fits_single_variant(segments) {
+        let message_id = metadata.extra["message_id"]
+            .as_str()
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| metadata.event_id.clone());
+        return MessageEvent::Chain {
+            message_id,
+            segments: segments.iter().filter_map(to_message_segment).collect(),
+            metadata,
+        };
+    }
+
+    let text: String = segments
+        .iter()
+        .filter(|s| s["type"] == "text")
+        .filter_map(|s| s["data"]["text"].as_str())
+        .collect();
+    let find = |seg_type: &str| segments.iter().find(|s| s["type"] == seg_type);
+
+    if let Some(reply) = find("reply") {
+        return MessageEvent::Reply {
+            reply_to: segment_data_str(reply, "id").unwrap_or_default(),
+            text,
+            metadata,
+        };
+    }
+
+    let at_list: Vec<String> = segments
+        .iter()
+        .filter(|s| s["type"] == "at")
+        .filter_map(|s| segment_data_str(s, "qq"))
+        .collect();
+    if !at_list.is_empty() {
+        return MessageEvent::At { text, at_list, metadata };
+    }
+
+    let optional_text = || if text.is_empty() { None } else { Some(text.clone()) };
+
+    if let Some(image) = find("image") {
+        return MessageEvent::Image {
+            url: media_url(image),
+            caption: optional_text(),
+            metadata,
+        };
+    }
+    if let Some(record) = find("record") {
+        return MessageEvent::Voice { url: media_url(record), duration: 0, metadata };
+    }
+    if let Some(video) = find("video") {
+        return MessageEvent::Video {
+            url: media_url(video),
+            duration: 0,
+            cover_url: segment_data_str(video, "thumb"),
+            metadata,
+        };
+    }
+    if let Some(file) = find("file") {
+        return MessageEvent::File {
+            url: media_url(file),
+            name: segment_data_str(file, "name").unwrap_or_default(),
+            size: segment_data_str(file, "file_size")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            metadata,
+        };
+    }
+    if let Some(location) = find("location") {
+        let coord = |key: &str| {
+            segment_data_str(location, key)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0)
+        };
+        return MessageEvent::Location {
+            latitude: coord("lat"),
+            longitude: coord("lon"),
+            address: segment_data_str(location, "title"),
+            metadata,
+        };
+    }
+    if let Some(forward) = find("forward") {
+        return MessageEvent::Forward {
+            forward_from: segment_data_str(forward, "id").unwrap_or_default(),
+            text: optional_text(),
+            metadata,
+        };
+    }
+    if let Some(markdown) = find("markdown") {
+        return MessageEvent::Markdown {
+            content: segment_data_str(markdown, "content").unwrap_or_default(),
+            metadata,
+        };
+    }
+    if text.is_empty()
+        && let Some(face) = find("face").or_else(|| find("mface"))
+    {
+        return MessageEvent::Sticker {
+            sticker_id: segment_data_str(face, "id")
+                .or_else(|| segment_data_str(face, "emoji_id"))
+                .unwrap_or_default(),
+            metadata,
+        };
+    }
+
+    MessageEvent::Text { text, metadata }
+}
+
+/// Check whether segments fit one of the single-content `MessageEvent` variants
+///
+/// Text may accompany mentions, a quote, an image or a forward; any other
+/// content must stand alone.
+fn fits_single_variant(segments: &[Value]) -> bool {
+    let has_text = segments
+        .iter()
+        .any(|s| s["type"] == "text" && s["data"]["text"].as_str().is_some_and(|t| !t.is_empty()));
+    let kinds: Vec<&str> = segments
+        .iter()
+        .filter_map(|s| s["type"].as_str())
+        .filter(|t| *t != "text")
+        .collect();
+
+    match kinds.as_slice() {
+        [] => true,
+        ["at", rest @ ..] => rest.iter().all(|t| *t == "at"),
+        [kind] => matches!(*kind, "reply" | "image" | "forward") || !has_text,
+        _ => false,
+    }
+}
+
+/// Get the URL of a media segment, falling back to its file
+fn media_url(segment: &Value) -> String {
+    segment_data_str(segment, "url")
+        .or_else(|| segment_data_str(segment, "file"))
+        .unwrap_or_default()
+}
+
+/// Convert a OneBot v11 segment into a message segment
+///
+/// Unknown segment types are skipped.
+pub fn to_message_segment(segment: &Value) -> Option<MessageSegment> {
+    let data = |key: &str| segment_data_str(segment, key);
+    let message_segment = match segment["type"].as_str()? {
+        "text" => MessageSegment::Text { text: data("text").unwrap_or_default() },
+        "at" => MessageSegment::At { user_id: data("qq")? },
+        "reply" => MessageSegment::Reply { message_id: data("id")? },
+        "image" => MessageSegment::Image { url: media_url(segment) },
+        "record" => MessageSegment::Voice { url: media_url(segment), duration: 0 },
+        "video" => MessageSegment::Video { url: media_url(segment), cover_url: data("thumb") },
+        "file" => MessageSegment::File {
+            url: media_url(segment),
+            name: data("name").unwrap_or_default(),
+            size: data("file_size").and_then(|s| s.parse().ok()).unwrap_or(0),
+        },
+        "location" => MessageSegment::Location {
+            latitude: data("lat").and_then(|s| s.parse().ok()).unwrap_or(0.0),
+            longitude: data("lon").and_then(|s| s.parse().ok()).unwrap_or(0.0),
+            address: data("title"),
+        },
+        "forward" => MessageSegment::Forward { forward_from: data("id").unwrap_or_default() },
+        "face" | "mface" => MessageSegment::Sticker {
+            sticker_id: data("id").or_else(|| data("emoji_id")).unwrap_or_default(),
+        },
+        "markdown" => MessageSegment::Markdown { content: data("content").unwrap_or_default() },
+        _ => return None,
+    };
+    Some(message_segment)
+}
+
+impl NoticeConverter<Value> for OneBot11Converter {
+    fn convert_notice(&self, notice: Value) -> Result<NoticeEvent> {
+        let notice_type = notice["notice_type"].as_str().unwrap_or_default().to_string();
+        let sub_type = notice["sub_type"].as_str().unwrap_or_default();
+        let metadata = self
+            .metadata(&notice, EventSource::System)
+            .with_extra("notice_type", &notice_type)
+            .with_extra("sub_type", sub_type);
+        let operator_id = value_to_id(&notice["operator_id"]).unwrap_or_default();
+
+        let event = match notice_type.as_str() {
+            "group_increase" => NoticeEvent::GroupMemberJoin {
+                user_id: Self::require_id(&notice, "user_id")?,
+                group_id: Self::require_id(&notice, "group_id")?,
+                user_info: None,
+                metadata,
+            },
+            "group_decrease" if sub_type == "leave" => NoticeEvent::GroupMemberLeave {
+                user_id: Self::require_id(&notice, "user_id")?,
+                group_id: Self::require_id(&notice, "group_id")?,
+                reason: None,
+                metadata,
+            },
+            "group_decrease" => NoticeEvent::GroupMemberKick {
+                user_id: Self::require_id(&notice, "user_id")?,
+                group_id: Self::require_id(&notice, "group_id")?,
+                operator_id,
+                reason: None,
+                metadata,
+            },
+            "group_ban" => NoticeEvent::GroupMemberMute {
+                user_id: Self::require_id(&notice, "user_id")?,
+                group_id: Self::require_id(&notice, "group_id")?,
+                operator_id,
+                duration: if sub_type == "lift_ban" {
+                    Some(0)
+                } else {
+                    notice["duration"].as_u64()
+                },
+                reason: None,
+                metadata,
+            },
+            "friend_add" => NoticeEvent::FriendAdd {
+                user_id: Self::require_id(&notice, "user_id")?,
+                user_info: None,
+                metadata,
+            },
+            _ => NoticeEvent::SystemNotice {
+                notice_type,
+                content: notice.to_string(),
+                metadata,
+            },
+        };
+
+        Ok(event)
+    }
+
+    fn supports_notice(&self, notice_type: &str) -> bool {
+        matches!(
+            notice_type,
+            "group_increase" | "group_decrease" | "group_ban" | "friend_add"
+        )
+    }
+}
+
+impl RequestConverter<Value> for OneBot11Converter {
+    fn convert_request(&self, request: Value) -> Result<RequestEvent> {
+        let request_type = request["request_type"].as_str().unwrap_or_default();
+        let sub_type = request["sub_type"].as_str().unwrap_or_default();
+        let mut metadata = self
+            .metadata(&request, EventSource::User)
+            .with_extra("request_type", request_type)
+            .with_extra("sub_type", sub_type);
+        if let Some(flag) = request["flag"].as_str() {
+            metadata = metadata.with_extra("flag", flag);
+        }
+        let comment = request["comment"].as_str().map(|s| s.to_string());
+
+        match (request_type, sub_type) {
+            ("friend", _) => Ok(RequestEvent::FriendRequest {
+                from_user_id: Self::require_id(&request, "user_id")?,
+                comment,
+                metadata,
+            }),
+            ("group", "invite") => Ok(RequestEvent::GroupInvite {
+                inviter_id: Self::require_id(&request, "user_id")?,
+                group_id: Self::require_id(&request, "group_id")?,
+                message: comment,
+                metadata,
+            }),
+            ("group", _) => Ok(RequestEvent::GroupJoinRequest {
+                user_id: Self::require_id(&request, "user_id")?,
+                group_id: Self::require_id(&request, "group_id")?,
+                reason: comment,
+                metadata,
+            }),
+            _ => Err(AdapterError::ConversionFailed(format!(
+                "unsupported request_type: {}",
+                request_type
+            ))
+            .into()),
+        }
+    }
+
+    fn supports_request(&self, request_type: &str) -> bool {
+        matches!(request_type, "friend" | "group")
+    }
+}
+
+impl MetaConverter<Value> for OneBot11Converter {
+    fn convert_meta(&self, meta: Value) -> Result<MetaEvent> {
+        let metadata = self.metadata(&meta, EventSource::System);
+
+        match meta["meta_event_type"].as_str() {
+            Some("heartbeat") => Ok(MetaEvent::Heartbeat {
+                interval: meta["interval"].as_u64().unwrap_or(0) as u32,
+                metadata: metadata.with_extra("status", meta["status"].clone()),
+            }),
+            Some("lifecycle") => {
+                let phase = match meta["sub_type"].as_str() {
+                    Some("disable") => LifecyclePhase::Stopped,
+                    _ => LifecyclePhase::Started,
+                };
+                Ok(MetaEvent::Lifecycle { phase, metadata })
+            }
+            other => Err(AdapterError::ConversionFailed(format!(
+                "unsupported meta_event_type: {:?}",
+                other
+            ))
+            .into()),
+        }
+    }
+
+    fn supports_meta(&self, meta_type: &str) -> bool {
+        matches!(meta_type, "heartbeat" | "lifecycle")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn converter() -> OneBot11Converter {
+        OneBot11Converter::new(ConversionContext::new("onebot-001", "onebot11", ""))
+    }
+
+    #[test]
+    fn test_convert_group_message() {
+        let post = json!({
+            "post_type": "message", "message_type": "group", "sub_type": "normal",
+            "time": 1700000000, "self_id": 10000, "user_id": 12345, "group_id": 67890,
+            "message_id": 42,
+            "message": [
+                {"type": "at", "data": {"qq": "10000"}},
+                {"type": "text", "data": {"text": " hello"}}
+            ],
+            "sender": {"nickname": "alice", "card": ""}
+        });
+
+        let event = converter().convert(post).unwrap();
+        let EventEnum::Message(MessageEvent::At { text, at_list, metadata }) = event else {
+            panic!("expected at message");
+        };
+        assert_eq!(text, " hello");
+        assert_eq!(at_list, vec!["10000".to_string()]);
+        assert_eq!(metadata.user_id.as_deref(), Some("12345"));
+        assert_eq!(metadata.group_id.as_deref(), Some("67890"));
+        assert_eq!(metadata.self_id.as_deref(), Some("10000"));
+        assert_eq!(metadata.extra["message_id"], "42");
+        assert_eq!(metadata.timestamp.timestamp(), 1700000000);
+    }
+
+    #[test]
+    fn test_convert_cq_string_message() {
+        let post = json!({
+            "post_type": "message", "message_type": "private", "user_id": 1,
+            "message": "[CQ:reply,id=99]ok"
+        });
+
+        let event = converter().convert_message(post).unwrap();
+        assert!(matches!(event, MessageEvent::Reply { ref reply_to, ref text, .. } if reply_to == "99" && text == "ok"));
+
+        let post = json!({
+            "post_type": "message", "message_type": "group", "user_id": 1, "group_id": 2, "message_id": 100,
+            "message": "[CQ:reply,id=99][CQ:at,qq=3] look[CQ:image,file=a.png,url=http://x/a.png]"
+        });
+        let event = converter().convert_message(post).unwrap();
+        let MessageEvent::Chain { ref message_id, ref segments, .. } = event else {
+            panic!("expected message chain");
+        };
+        assert_eq!(message_id, "100");
+        assert_eq!(segments.len(), 4);
+        assert_eq!(event.reply_to(), Some("99"));
+        assert_eq!(event.mentions(), vec!["3"]);
+        assert_eq!(event.images(), vec!["http://x/a.png"]);
+        assert_eq!(event.plain_text(), " look");
+    }
+
+    #[test]
+    fn test_convert_notice() {
+        let post = json!({
+            "post_type": "notice", "notice_type": "group_decrease", "sub_type": "kick",
+            "user_id": 1, "group_id": 2, "operator_id": 3
+        });
+        let event = converter().convert(post).unwrap();
+        assert!(matches!(
+            event,
+            EventEnum::Notice(NoticeEvent::GroupMemberKick { ref operator_id, .. }) if operator_id == "3"
+        ));
+
+        let post = json!({"post_type": "notice", "notice_type": "group_recall", "group_id": 2});
+        let event = converter().convert(post).unwrap();
+        assert!(matches!(event, EventEnum::Notice(NoticeEvent::SystemNotice { .. })));
+    }
+
+    #[test]
+    fn test_convert_request() {
+        let post = json!({
+            "post_type": "request", "request_type": "friend", "user_id": 5,
+            "comment": "hi", "flag": "flag-1"
+        });
+        let event = converter().convert(post).unwrap();
+        let EventEnum::Request(RequestEvent::FriendRequest { from_user_id, metadata, .. }) = event else {
+            panic!("expected friend request");
+        };
+        assert_eq!(from_user_id, "5");
+        assert_eq!(metadata.extra["flag"], "flag-1");
+    }
+
+    #[test]
+    fn test_convert_meta() {
+        let post = json!({
+            "post_type": "meta_event", "meta_event_type": "heartbeat",
+            "interval": 5000, "status": {"online": true}
+        });
+        let event = converter().convert(post).unwrap();
+        assert!(matches!(event, EventEnum::Meta(MetaEvent::Heartbeat { interval: 5000, .. })));
+
+        assert!(converter().convert(json!({"post_type": "bogus"})).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! OneBot v11 Adapter Factory
+
+use crate::adapters::{
+    Adapter, AdapterConfig, AdapterFactory,
+};
+use crate::errors::{AdapterError, Result};
+use super::adapter::{OneBot11Adapter, OneBot11Mode};
+
+/// Factory for creating OneBot11Adapter instances
+pub struct OneBot11AdapterFactory;
+
+impl AdapterFactory for OneBot11AdapterFactory {
+    fn adapter_type(&self) -> &str {
+        "onebot11"
+    }
+
+    fn create(&self, config: AdapterConfig) -> Result<Box<dyn Adapter>> {
+        let url = &config.connection.url;
+        let mode = OneBot11Mode::from_conn_type(&config.connection.conn_type).ok_or_else(|| {
+            AdapterError::InvalidConfig(format!(
+                "Unsupported OneBot v11 connection type: {}",
+                config.connection.conn_type
+            ))
+        })?;
+
+        if mode.is_server() {
+            if url.is_empty() {
+                return Err(AdapterError::InvalidConfig(
+                    "OneBot v11 server mode requires a bind address".to_string(),
+                )
+                .into());
+            }
+        } else if !url.starts_with("ws://") && !url.starts_with("wss://") {
+            return Err(AdapterError::InvalidConfig(format!(
+                "OneBot v11 forward WebSocket URL must start with ws:// or wss://, got {}",
+                url
+            ))
+            .into());
+        }
+        Ok(Box::new(OneBot11Adapter::new(config)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::AdapterFactoryRegistry;
+
+    #[test]
+    fn test_onebot11_factory_type() {
+        let factory = OneBot11AdapterFactory;
+        assert_eq!(factory.adapter_type(), "onebot11");
+    }
+
+    #[test]
+    fn test_onebot11_factory_create() {
+        let registry = AdapterFactoryRegistry::new();
+        registry.register(Box::new(OneBot11AdapterFactory)).unwrap();
+
+        let config = AdapterConfig::new("onebot11", "onebot-001", "ws://127.0.0.1:3001");
+        let adapter = registry.create(config).unwrap();
+        assert_eq!(adapter.name(), "OneBot11Adapter");
+        assert_eq!(adapter.adapter_id(), "onebot-001");
+
+        let config = AdapterConfig::new("onebot11", "onebot-002", "http://127.0.0.1:3000");
+        assert!(registry.create(config).is_err());
+
+        let mut config = AdapterConfig::new("onebot11", "onebot-003", "0.0.0.0:8080");
+        config.connection.conn_type = "ws_reverse".to_string();
+        assert!(registry.create(config).is_ok());
+
+        let mut config = AdapterConfig::new("onebot11", "onebot-004", "0.0.0.0:8080");
+        config.connection.conn_type = "grpc".to_string();
+        assert!(registry.create(config).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+//! OneBot v11 adapter
+//!
+//! Connects to OneBot v11 implementations such as NapCat or Lagrange.
+
+pub mod types;
+pub mod converter;
+pub mod session;
+pub mod server;
+pub mod adapter;
+pub mod factory;
+
+pub use types::*;
+pub use converter::*;
+pub use session::*;
+pub use server::*;
+pub use adapter::*;
+pub use factory::*;
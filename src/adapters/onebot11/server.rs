@@ -0,0 +1,177 @@
+//! OneBot v11 server endpoints - reverse WebSocket and HTTP POST
+
+use crate::adapters::onebot11::session::OneBotSession;
+use axum::{
+    Json, Router,
+    extract::{Query, State, ws::{Message as WsMessage, WebSocket, WebSocketUpgrade}},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Reverse WebSocket route
+pub const REVERSE_WS_PATH: &str = "/onebot/v11/ws";
+
+/// HTTP POST route
+pub const HTTP_POST_PATH: &str = "/onebot/v11/http";
+
+/// Shared state of the OneBot v11 server routes
+#[derive(Debug, Clone)]
+pub struct OneBot11ServerState {
+    session: Arc<OneBotSession>,
+    access_token: Option<String>,
+}
+
+impl OneBot11ServerState {
+    /// Create a new server state
+    pub fn new(session: Arc<OneBotSession>, access_token: Option<&str>) -> Self {
+        Self {
+            session,
+            access_token: access_token.map(|t| t.to_string()),
+        }
+    }
+
+    /// Verify the `access_token` from the `Authorization` header or query string
+    fn authorize(&self, headers: &HeaderMap, query: &HashMap<String, String>) -> Result<(), StatusCode> {
+        let Some(expected) = &self.access_token else {
+            return Ok(());
+        };
+
+        let provided = headers
+            .get("Authorization")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.strip_prefix("Bearer ").or_else(|| v.strip_prefix("Token ")).unwrap_or(v).trim())
+            .or_else(|| query.get("access_token").map(|t| t.as_str()));
+
+        match provided {
+            None => Err(StatusCode::UNAUTHORIZED),
+            Some(token) if token == expected => Ok(()),
+            Some(_) => Err(StatusCode::FORBIDDEN),
+        }
+    }
+}
+
+/// Get the account ID from the `X-Self-ID` header
+fn self_id_header(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("X-Self-ID")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Build the router for the reverse WebSocket route
+pub fn reverse_ws_router(state: OneBot11ServerState) -> Router {
+    Router::new()
+        .route(REVERSE_WS_PATH, get(reverse_ws_handler))
+        .with_state(state)
+}
+
+/// Build the router for the HTTP POST route
+pub fn http_post_router(state: OneBot11ServerState) -> Router {
+    Router::new()
+        .route(HTTP_POST_PATH, post(http_post_handler))
+        .with_state(state)
+}
+
+async fn reverse_ws_handler(
+    State(state): State<OneBot11ServerState>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if let Err(status) = state.authorize(&headers, &query) {
+        return status.into_response();
+    }
+
+    let self_id = self_id_header(&headers);
+    ws.on_upgrade(move |socket| handle_reverse_socket(state.session, socket, self_id))
+}
+
+async fn handle_reverse_socket(session: Arc<OneBotSession>, socket: WebSocket, self_id: Option<String>) {
+    let (mut sink, mut stream) = socket.split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let account = self_id.clone().unwrap_or_default();
+    let token = session.attach(&account, tx).await;
+
+    let writer = tokio::spawn(async move {
+        while let Some(frame) = rx.recv().await {
+            if sink.send(WsMessage::Text(frame)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(Ok(message)) = stream.next().await {
+        match message {
+            WsMessage::Text(text) => {
+                let _ = session.handle_frame_from(self_id.as_deref(), &text).await;
+            }
+            WsMessage::Binary(bytes) => {
+                if let Ok(text) = String::from_utf8(bytes) {
+                    let _ = session.handle_frame_from(self_id.as_deref(), &text).await;
+                }
+            }
+            WsMessage::Close(_) => break,
+            _ => {}
+        }
+    }
+
+    session.detach(&account, token).await;
+    writer.abort();
+}
+
+async fn http_post_handler(
+    State(state): State<OneBot11ServerState>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    Json(post): Json<Value>,
+) -> Response {
+    if let Err(status) = state.authorize(&headers, &query) {
+        return status.into_response();
+    }
+
+    let self_id = self_id_header(&headers);
+    match state.session.handle_post_from(self_id.as_deref(), post).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize() {
+        let (events, _) = tokio::sync::broadcast::channel(1);
+        let session = Arc::new(OneBotSession::new(
+            crate::adapters::OneBot11Converter::new(
+                crate::adapters::ConversionContext::new("ob", "onebot11", ""),
+            ),
+            events,
+            Arc::new(tokio::sync::RwLock::new(crate::adapters::AdapterStatistics::default())),
+            std::time::Duration::from_secs(1),
+        ));
+        let state = OneBot11ServerState::new(session, Some("secret"));
+        let mut headers = HeaderMap::new();
+        let mut query = HashMap::new();
+
+        assert_eq!(state.authorize(&headers, &query), Err(StatusCode::UNAUTHORIZED));
+
+        headers.insert("Authorization", "Bearer wrong".parse().unwrap());
+        assert_eq!(state.authorize(&headers, &query), Err(StatusCode::FORBIDDEN));
+
+        headers.insert("Authorization", "Bearer secret".parse().unwrap());
+        assert!(state.authorize(&headers, &query).is_ok());
+
+        headers.clear();
+        query.insert("access_token".to_string(), "secret".to_string());
+        assert!(state.authorize(&headers, &query).is_ok());
+    }
+}
@@ -0,0 +1,447 @@
+//! OneBot session - transport independent action/event handling
+//!
+//! OneBot v11 and v12 share the action wire format (`action`/`params`/`echo`
+//! requests, `status`/`retcode`/`data` responses), so both adapters use this.
+
+use crate::adapters::converter::EventConverter;
+use crate::adapters::onebot11::types::{ActionRequest, ActionResponse};
+use crate::adapters::types::AdapterStatistics;
+use crate::errors::{AdapterError, LoquatError, Result};
+use crate::events::EventEnum;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, RwLock};
+
+/// OneBot HTTP API endpoint used when no WebSocket connection is available
+#[derive(Debug, Clone)]
+pub struct HttpApiClient {
+    client: reqwest::Client,
+    base_url: String,
+    access_token: Option<String>,
+}
+
+impl HttpApiClient {
+    /// Create a new HTTP API client
+    pub fn new(base_url: &str, access_token: Option<&str>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            access_token: access_token.map(|t| t.to_string()),
+        }
+    }
+
+    /// Call an action via `POST {base_url}/{action}`
+    pub async fn call(&self, action: &str, params: &Value, timeout: Duration) -> Result<ActionResponse> {
+        let mut request = self.client
+            .post(format!("{}/{}", self.base_url, action))
+            .timeout(timeout)
+            .json(params);
+        if let Some(token) = &self.access_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await
+            .map_err(|e| AdapterError::ConnectionFailed(format!("HTTP API request failed: {}", e)))?;
+        response.json::<ActionResponse>().await
+            .map_err(|e| LoquatError::Serialization(e.to_string()))
+    }
+}
+
+/// Identifies one attached connection of an account
+///
+/// An account that reconnects gets a new token, so the old connection's
+/// teardown cannot detach its replacement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConnectionToken(u64);
+
+/// Shared state of a OneBot adapter
+///
+/// Inbound frames are fed through `handle_frame`; outbound action calls are
+/// written to an attached connection (keyed by `self_id`) and resolved by
+/// their `echo`, or sent to the HTTP API when no connection is attached.
+pub struct OneBotSession {
+    converter: Box<dyn EventConverter<Value>>,
+    default_account: String,
+    event_sender: broadcast::Sender<EventEnum>,
+    statistics: Arc<RwLock<AdapterStatistics>>,
+    outbound: RwLock<HashMap<String, (ConnectionToken, mpsc::UnboundedSender<String>)>>,
+    pending: Mutex<HashMap<String, (ConnectionToken, oneshot::Sender<ActionResponse>)>>,
+    next_token: AtomicU64,
+    http_api: Option<HttpApiClient>,
+    action_timeout: Duration,
+}
+
+impl std::fmt::Debug for OneBotSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OneBotSession")
+            .field("default_account", &self.default_account)
+            .field("http_api", &self.http_api)
+            .field("action_timeout", &self.action_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OneBotSession {
+    /// Create a new session
+    pub fn new<C: EventConverter<Value> + 'static>(
+        converter: C,
+        event_sender: broadcast::Sender<EventEnum>,
+        statistics: Arc<RwLock<AdapterStatistics>>,
+        action_timeout: Duration,
+    ) -> Self {
+        Self {
+            converter: Box::new(converter),
+            default_account: String::new(),
+            event_sender,
+            statistics,
+            outbound: RwLock::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            next_token: AtomicU64::new(0),
+            http_api: None,
+            action_timeout,
+        }
+    }
+
+    /// Set the account used for actions that do not name one
+    pub fn with_default_account(mut self, self_id: &str) -> Self {
+        self.default_account = self_id.to_string();
+        self
+    }
+
+    /// Use an HTTP API endpoint for actions when no connection is attached
+    pub fn with_http_api(mut self, http_api: HttpApiClient) -> Self {
+        self.http_api = Some(http_api);
+        self
+    }
+
+    /// Attach an outbound frame sender for an account (a live connection)
+    ///
+    /// Replaces an older connection of the account. Returns the token that
+    /// detaches this connection.
+    pub async fn attach(&self, self_id: &str, sender: mpsc::UnboundedSender<String>) -> ConnectionToken {
+        let token = ConnectionToken(self.next_token.fetch_add(1, Ordering::Relaxed));
+        self.outbound.write().await.insert(self_id.to_string(), (token, sender));
+        token
+    }
+
+    /// Detach a connection and fail the actions pending on it
+    ///
+    /// The account stays attached if it has reconnected in the meantime.
+    pub async fn detach(&self, self_id: &str, token: ConnectionToken) {
+        let mut outbound = self.outbound.write().await;
+        if outbound.get(self_id).is_some_and(|(current, _)| *current == token) {
+            outbound.remove(self_id);
+        }
+        drop(outbound);
+        self.pending.lock().await.retain(|_, (owner, _)| *owner != token);
+    }
+
+    /// Detach all connections and fail all pending actions
+    pub async fn detach_all(&self) {
+        self.outbound.write().await.clear();
+        self.pending.lock().await.clear();
+    }
+
+    /// Check if any connection is attached
+    pub async fn is_attached(&self) -> bool {
+        !self.outbound.read().await.is_empty()
+    }
+
+    /// Get the accounts (`self_id`s) with a live connection
+    pub async fn connected_accounts(&self) -> Vec<String> {
+        self.outbound.read().await.keys().cloned().collect()
+    }
+
+    /// Subscribe to converted events
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.event_sender.subscribe()
+    }
+
+    /// Call an action on the default account and return its response data
+    pub async fn call_action(&self, action: &str, params: Value) -> Result<Value> {
+        self.call_action_as(None, action, params).await
+    }
+
+    /// Call an action on a specific account and return its response data
+    pub async fn call_action_as(&self, self_id: Option<&str>, action: &str, params: Value) -> Result<Value> {
+        let connection = {
+            let outbound = self.outbound.read().await;
+            let key = match self_id {
+                Some(id) if outbound.contains_key(id) => Some(id.to_string()),
+                // A connection of unknown account (no `self_id` configured) serves any account
+                Some(_) => outbound.contains_key("").then(String::new),
+                None if outbound.contains_key(&self.default_account) => {
+                    Some(self.default_account.clone())
+                }
+                None if outbound.len() > 1 => {
+                    return Err(AdapterError::SendFailed(format!(
+                        "action {} names no account and {} accounts are connected",
+                        action,
+                        outbound.len()
+                    ))
+                    .into());
+                }
+                None => outbound.keys().next().cloned(),
+            };
+            key.and_then(|k| outbound.get(&k).map(|(token, sender)| (*token, sender.clone())))
+        };
+
+        let response = match (connection, &self.http_api) {
+            (Some((owner, sender)), _) => self.call_over_connection(owner, sender, action, params).await?,
+            (None, Some(http_api)) => match http_api.call(action, &params, self.action_timeout).await {
+                Ok(response) => response,
+                Err(e) => {
+                    self.record_error().await;
+                    return Err(e);
+                }
+            },
+            (None, None) => {
+                return Err(AdapterError::NotRunning(format!("no connection for action {}", action)).into());
+            }
+        };
+
+        if response.is_ok() {
+            Ok(response.data)
+        } else {
+            self.record_error().await;
+            Err(AdapterError::SendFailed(format!(
+                "action {} failed: {}",
+                action,
+                response.error_message()
+            ))
+            .into())
+        }
+    }
+
+    async fn call_over_connection(
+        &self,
+        owner: ConnectionToken,
+        sender: mpsc::UnboundedSender<String>,
+        action: &str,
+        params: Value,
+    ) -> Result<ActionResponse> {
+        let echo = uuid::Uuid::new_v4().to_string();
+        let request = ActionRequest::new(action, params, &echo);
+        let frame = serde_json::to_string(&request)
+            .map_err(|e| LoquatError::Serialization(e.to_string()))?;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(echo.clone(), (owner, tx));
+
+        if sender.send(frame).is_err() {
+            self.pending.lock().await.remove(&echo);
+            return Err(AdapterError::NotRunning(format!("connection closed for action {}", action)).into());
+        }
+
+        match tokio::time::timeout(self.action_timeout, rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(AdapterError::ConnectionFailed(format!(
+                "connection closed before {} responded",
+                action
+            ))
+            .into()),
+            Err(_) => {
+                self.pending.lock().await.remove(&echo);
+                self.record_error().await;
+                Err(AdapterError::SendFailed(format!("action {} timed out", action)).into())
+            }
+        }
+    }
+
+    /// Handle an inbound text frame (action response or event post)
+    pub async fn handle_frame(&self, frame: &str) -> Result<()> {
+        self.handle_frame_from(None, frame).await
+    }
+
+    /// Handle an inbound text frame received on an account's connection
+    pub async fn handle_frame_from(&self, self_id: Option<&str>, frame: &str) -> Result<()> {
+        let value: Value = serde_json::from_str(frame)
+            .map_err(|e| LoquatError::Serialization(e.to_string()))?;
+
+        if value.get("retcode").is_none() {
+            return self.handle_post_from(self_id, value).await.map(|_| ());
+        }
+
+        let response: ActionResponse = serde_json::from_value(value)
+            .map_err(|e| LoquatError::Serialization(e.to_string()))?;
+        if let Some(echo) = response.echo_str()
+            && let Some((_, tx)) = self.pending.lock().await.remove(&echo)
+        {
+            let _ = tx.send(response);
+        }
+        Ok(())
+    }
+
+    /// Convert an event post and publish it to subscribers
+    pub async fn handle_post(&self, post: Value) -> Result<EventEnum> {
+        self.handle_post_from(None, post).await
+    }
+
+    /// Convert an event post, mapping the account's `X-Self-ID` into `self_id`
+    pub async fn handle_post_from(&self, self_id: Option<&str>, mut post: Value) -> Result<EventEnum> {
+        if let (Some(self_id), Some(obj)) = (self_id, post.as_object_mut()) {
+            obj.insert("self_id".to_string(), Value::String(self_id.to_string()));
+        }
+
+        let event = match self.converter.convert(post) {
+            Ok(event) => event,
+            Err(e) => {
+                self.record_error().await;
+                return Err(e);
+            }
+        };
+
+        let mut stats = self.statistics.write().await;
+        stats.events_received += 1;
+        stats.last_activity = Some(chrono::Utc::now().timestamp());
+        drop(stats);
+
+        let _ = self.event_sender.send(event.clone());
+        Ok(event)
+    }
+
+    async fn record_error(&self) {
+        self.statistics.write().await.errors += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::converter::ConversionContext;
+    use crate::adapters::onebot11::converter::OneBot11Converter;
+    use serde_json::json;
+
+    fn session() -> Arc<OneBotSession> {
+        let (events, _) = broadcast::channel(16);
+        Arc::new(OneBotSession::new(
+            OneBot11Converter::new(ConversionContext::new("ob-001", "onebot11", "")),
+            events,
+            Arc::new(RwLock::new(AdapterStatistics::default())),
+            Duration::from_millis(500),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_call_action_echo_correlation() {
+        let session = session();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        session.attach("", tx).await;
+
+        let responder = Arc::clone(&session);
+        tokio::spawn(async move {
+            let frame = rx.recv().await.unwrap();
+            let request: ActionRequest = serde_json::from_str(&frame).unwrap();
+            assert_eq!(request.action, "get_login_info");
+            let response = json!({
+                "status": "ok", "retcode": 0,
+                "data": {"user_id": 10000}, "echo": request.echo
+            });
+            responder.handle_frame(&response.to_string()).await.unwrap();
+        });
+
+        let data = session.call_action("get_login_info", json!({})).await.unwrap();
+        assert_eq!(data["user_id"], 10000);
+    }
+
+    #[tokio::test]
+    async fn test_call_action_without_connection() {
+        let session = session();
+        assert!(session.call_action("send_private_msg", json!({})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_post_publishes_event() {
+        let session = session();
+        let mut events = session.subscribe();
+
+        let post = json!({
+            "post_type": "message", "message_type": "private", "user_id": 1,
+            "message": [{"type": "text", "data": {"text": "hi"}}]
+        });
+        session.handle_frame(&post.to_string()).await.unwrap();
+
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.event_type(), "message.text");
+        assert_eq!(session.statistics.read().await.events_received, 1);
+    }
+
+    #[tokio::test]
+    async fn test_handle_post_maps_self_id() {
+        let session = session();
+        let mut events = session.subscribe();
+
+        let post = json!({
+            "post_type": "meta_event", "meta_event_type": "lifecycle", "sub_type": "connect",
+            "self_id": 1
+        });
+        session.handle_frame_from(Some("20002"), &post.to_string()).await.unwrap();
+
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.self_id(), Some("20002"));
+    }
+
+    #[tokio::test]
+    async fn test_call_action_routes_by_self_id() {
+        let session = session();
+        let (tx_a, mut rx_a) = mpsc::unbounded_channel();
+        let (tx_b, mut rx_b) = mpsc::unbounded_channel();
+        session.attach("100", tx_a).await;
+        let token_b = session.attach("200", tx_b).await;
+
+        let caller = Arc::clone(&session);
+        let call = tokio::spawn(async move {
+            caller.call_action_as(Some("200"), "get_status", json!({})).await
+        });
+
+        let frame = rx_b.recv().await.unwrap();
+        assert!(rx_a.try_recv().is_err());
+        let request: ActionRequest = serde_json::from_str(&frame).unwrap();
+        let response = json!({"status": "ok", "retcode": 0, "data": {"online": true}, "echo": request.echo});
+        session.handle_frame_from(Some("200"), &response.to_string()).await.unwrap();
+
+        assert_eq!(call.await.unwrap().unwrap()["online"], true);
+
+        session.detach("200", token_b).await;
+        assert_eq!(session.connected_accounts().await, vec!["100".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_overlap_keeps_new_connection() {
+        let session = session();
+        let (old_tx, _old_rx) = mpsc::unbounded_channel();
+        let (new_tx, mut new_rx) = mpsc::unbounded_channel();
+        let old = session.attach("100", old_tx).await;
+        session.attach("100", new_tx).await;
+
+        let caller = Arc::clone(&session);
+        let call = tokio::spawn(async move { caller.call_action_as(Some("100"), "get_status", json!({})).await });
+        let frame = new_rx.recv().await.unwrap();
+
+        // The old socket's read loop ends after the reconnect
+        session.detach("100", old).await;
+        assert_eq!(session.connected_accounts().await, vec!["100".to_string()]);
+
+        let request: ActionRequest = serde_json::from_str(&frame).unwrap();
+        let response = json!({"status": "ok", "retcode": 0, "data": {"online": true}, "echo": request.echo});
+        session.handle_frame_from(Some("100"), &response.to_string()).await.unwrap();
+        assert_eq!(call.await.unwrap().unwrap()["online"], true);
+    }
+
+    #[tokio::test]
+    async fn test_call_action_without_account_is_ambiguous() {
+        let session = session();
+        let (tx_a, mut rx_a) = mpsc::unbounded_channel();
+        let (tx_b, mut rx_b) = mpsc::unbounded_channel();
+        session.attach("100", tx_a).await;
+        session.attach("200", tx_b).await;
+
+        let result = session.call_action("send_private_msg", json!({})).await;
+        assert!(matches!(result, Err(LoquatError::Adapter(AdapterError::SendFailed(_)))));
+        assert!(rx_a.try_recv().is_err());
+        assert!(rx_b.try_recv().is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! OneBot v11 protocol types and message segment helpers
+
+use crate::adapters::{Message, Segment};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Action request sent to the OneBot implementation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActionRequest {
+    /// Action name (e.g. `send_group_msg`)
+    pub action: String,
+    /// Action parameters
+    #[serde(default)]
+    pub params: Value,
+    /// Echo used to correlate the response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo: Option<String>,
+}
+
+impl ActionRequest {
+    /// Create a new action request with an echo value
+    pub fn new(action: &str, params: Value, echo: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            params,
+            echo: Some(echo.to_string()),
+        }
+    }
+}
+
+/// Action response returned by the OneBot implementation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActionResponse {
+    /// `ok`, `async` or `failed`
+    pub status: String,
+    /// Return code (0 on success)
+    pub retcode: i64,
+    /// Response data
+    #[serde(default)]
+    pub data: Value,
+    /// Error message (go-cqhttp style)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Error description (NapCat / Lagrange style)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wording: Option<String>,
+    /// Echo copied from the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo: Option<Value>,
+}
+
+impl ActionResponse {
+    /// Check if the action succeeded
+    pub fn is_ok(&self) -> bool {
+        self.retcode == 0 && self.status != "failed"
+    }
+
+    /// Get a human readable error description
+    pub fn error_message(&self) -> String {
+        self.wording
+            .clone()
+            .or_else(|| self.message.clone())
+            .unwrap_or_else(|| format!("retcode {}", self.retcode))
+    }
+
+    /// Get the echo as a string
+    pub fn echo_str(&self) -> Option<String> {
+        match &self.echo {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Null) | None => None,
+            Some(other) => Some(other.to_string()),
+        }
+    }
+}
+
+/// Convert an outbound message into OneBot v11 message segments
+pub fn message_to_segments(message: &Message) -> Vec<Value> {
+    match message {
+        Message::Text { content } => vec![text_segment(content)],
+        Message::Image { url, caption } => {
+            let mut segments = vec![json!({"type": "image", "data": {"file": url}})];
+            if let Some(caption) = caption {
+                segments.push(text_segment(caption));
+            }
+            segments
+        }
+        Message::Voice { url, .. } => vec![json!({"type": "record", "data": {"file": url}})],
+        Message::Video { url, .. } => vec![json!({"type": "video", "data": {"file": url}})],
+        Message::Sticker { sticker_id } => vec![json!({"type": "face", "data": {"id": sticker_id}})],
+        Message::Chain { segments } => segments.iter().map(segment_to_v11).collect(),
+    }
+}
+
+/// Convert an outbound segment into a OneBot v11 message segment
+pub fn segment_to_v11(segment: &Segment) -> Value {
+    match segment {
+        Segment::Text { text } => text_segment(text),
+        Segment::At { user_id } => json!({"type": "at", "data": {"qq": user_id}}),
+        Segment::Reply { message_id } => json!({"type": "reply", "data": {"id": message_id}}),
+        Segment::Image { url } => json!({"type": "image", "data": {"file": url}}),
+        Segment::Voice { url } => json!({"type": "record", "data": {"file": url}}),
+        Segment::Video { url } => json!({"type": "video", "data": {"file": url}}),
+        Segment::File { url, name } => json!({"type": "file", "data": {"file": url, "name": name}}),
+        Segment::Markdown { content } => json!({"type": "markdown", "data": {"content": content}}),
+        Segment::Face { id } => json!({"type": "face", "data": {"id": id}}),
+    }
+}
+
+/// Build a text segment
+pub fn text_segment(text: &str) -> Value {
+    json!({"type": "text", "data": {"text": text}})
+}
+
+/// Normalize a `message` field (array or CQ code string) into segments
+pub fn normalize_segments(message: &Value) -> Vec<Value> {
+    match message {
+        Value::Array(segments) => segments.clone(),
+        Value::String(s) => parse_cq_string(s),
+        Value::Object(_) => vec![message.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// Parse a CQ code string into message segments
+pub fn parse_cq_string(input: &str) -> Vec<Value> {
+    let mut segments = Vec::new();
+    let mut rest = input;
+
+    while let Some(start) = rest.find("[CQ:") {
+        if start > 0 {
+            segments.push(text_segment(&unescape_cq(&rest[..start], false)));
+        }
+        let Some(end) = rest[start..].find(']') else {
+            break;
+        };
+        let code = &rest[start + 4..start + end];
+        let mut parts = code.split(',');
+        let seg_type = parts.next().unwrap_or_default();
+        let mut data = serde_json::Map::new();
+        for part in parts {
+            if let Some((key, value)) = part.split_once('=') {
+                data.insert(key.to_string(), Value::String(unescape_cq(value, true)));
+            }
+        }
+        segments.push(json!({"type": seg_type, "data": data}));
+        rest = &rest[start + end + 1..];
+    }
+
+    if !rest.is_empty() {
+        segments.push(text_segment(&unescape_cq(rest, false)));
+    }
+
+    segments
+}
+
+fn unescape_cq(s: &str, in_param: bool) -> String {
+    let s = s.replace("&#91;", "[").replace("&#93;", "]");
+    let s = if in_param { s.replace("&#44;", ",") } else { s };
+    s.replace("&amp;", "&")
+}
+
+/// Get a segment's data field as a string (numbers are stringified)
+pub fn segment_data_str(segment: &Value, key: &str) -> Option<String> {
+    value_to_id(&segment["data"][key])
+}
+
+/// Convert an ID value (string or number) into a string
+pub fn value_to_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cq_string() {
+        let segments = parse_cq_string("hi [CQ:at,qq=123] look&#44; [CQ:image,file=a.png,url=http://x/y?a=1&amp;b=2]");
+
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[0]["data"]["text"], "hi ");
+        assert_eq!(segments[1]["type"], "at");
+        assert_eq!(segments[1]["data"]["qq"], "123");
+        assert_eq!(segments[2]["data"]["text"], " look&#44; ");
+        assert_eq!(segments[3]["data"]["url"], "http://x/y?a=1&b=2");
+    }
+
+    #[test]
+    fn test_action_response() {
+        let resp: ActionResponse = serde_json::from_value(json!({
+            "status": "failed", "retcode": 1400, "data": null, "wording": "bad", "echo": 7
+        })).unwrap();
+
+        assert!(!resp.is_ok());
+        assert_eq!(resp.error_message(), "bad");
+        assert_eq!(resp.echo_str(), Some("7".to_string()));
+    }
+
+    #[test]
+    fn test_message_to_segments() {
+        let segments = message_to_segments(&Message::Image {
+            url: "file:///a.png".to_string(),
+            caption: Some("cap".to_string()),
+        });
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0]["type"], "image");
+        assert_eq!(segments[1]["data"]["text"], "cap");
+
+        let chain = Message::builder().reply("9").at("10001").text(" hi").build();
+        let segments = message_to_segments(&chain);
+        assert_eq!(segments[0], json!({"type": "reply", "data": {"id": "9"}}));
+        assert_eq!(segments[1], json!({"type": "at", "data": {"qq": "10001"}}));
+        assert_eq!(segments[2]["data"]["text"], " hi");
+    }
+}
@@ -0,0 +1,344 @@
+//! OneBot v12 adapter - forward WebSocket client
+
+use async_trait::async_trait;
+use crate::adapters::converter::ConversionContext;
+use crate::adapters::onebot11::adapter::{connect_forward_ws, message_id_of};
+use crate::adapters::onebot11::session::OneBotSession;
+use crate::adapters::onebot11::types::text_segment;
+use crate::adapters::onebot12::converter::OneBot12Converter;
+use crate::adapters::onebot12::types::{file_name_of, file_segment, target_params};
+use crate::adapters::{
+    Adapter, AdapterConfig, AdapterStatus, Message, Segment, Target,
+    DEFAULT_EVENT_CHANNEL_CAPACITY,
+    types::AdapterStatistics,
+};
+use crate::errors::{AdapterError, LoquatError, Result};
+use crate::events::EventEnum;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+/// OneBot v12 adapter implementation
+#[derive(Debug)]
+pub struct OneBot12Adapter {
+    config: AdapterConfig,
+    converter: OneBot12Converter,
+    status: Arc<RwLock<AdapterStatus>>,
+    statistics: Arc<RwLock<AdapterStatistics>>,
+    session: Arc<OneBotSession>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl OneBot12Adapter {
+    /// Create a new OneBot v12 adapter
+    pub fn new(config: AdapterConfig) -> Self {
+        let (event_sender, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
+        let statistics = Arc::new(RwLock::new(AdapterStatistics::default()));
+        let self_id = config.platform["self_id"].as_str().unwrap_or_default().to_string();
+        let converter = OneBot12Converter::new(ConversionContext::new(
+            &config.adapter_id,
+            "onebot12",
+            &self_id,
+        ));
+        let session = OneBotSession::new(
+            converter.clone(),
+            event_sender,
+            Arc::clone(&statistics),
+            Duration::from_secs(config.connection.timeout.max(1)),
+        )
+        .with_default_account(&self_id);
+
+        Self {
+            config,
+            converter,
+            status: Arc::new(RwLock::new(AdapterStatus::Ready)),
+            statistics,
+            session: Arc::new(session),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get the access token from platform configuration
+    pub fn access_token(&self) -> Option<&str> {
+        self.config.platform["access_token"].as_str().filter(|t| !t.is_empty())
+    }
+
+    /// Get the underlying session
+    pub fn session(&self) -> Arc<OneBotSession> {
+        Arc::clone(&self.session)
+    }
+
+    /// Call a raw OneBot action and return its response data
+    pub async fn call_action(&self, action: &str, params: Value) -> Result<Value> {
+        self.session.call_action(action, params).await
+    }
+
+    /// Send segments to a target via `send_message`, returning the message ID
+    pub async fn send_message(&self, target: &Target, segments: Vec<Value>) -> Result<String> {
+        self.send_message_as(None, target, segments).await
+    }
+
+    /// Send segments from account `self_id` via `send_message`
+    pub async fn send_message_as(&self, self_id: Option<&str>, target: &Target, segments: Vec<Value>) -> Result<String> {
+        let guild_id = match target {
+            Target::Channel { channel_id } => Some(self.converter.guild_of(channel_id).ok_or_else(|| {
+                AdapterError::SendFailed(format!("unknown guild for channel {}", channel_id))
+            })?),
+            _ => None,
+        };
+        let mut params = target_params(target, guild_id.as_deref());
+        params["message"] = Value::Array(segments);
+
+        let data = self.session.call_action_as(self_id, "send_message", params).await?;
+        Ok(message_id_of(&data))
+    }
+
+    /// Upload a file by URL, returning its `file_id`
+    pub async fn upload_url(&self, url: &str) -> Result<String> {
+        self.upload_url_as(None, url).await
+    }
+
+    /// Upload a file by URL through account `self_id`, returning its `file_id`
+    async fn upload_url_as(&self, self_id: Option<&str>, url: &str) -> Result<String> {
+        let params = json!({"type": "url", "url": url, "name": file_name_of(url)});
+        let data = self.session.call_action_as(self_id, "upload_file", params).await?;
+        data["file_id"].as_str().map(|s| s.to_string()).ok_or_else(|| {
+            AdapterError::SendFailed("upload_file returned no file_id".to_string()).into()
+        })
+    }
+
+    /// Handle a friend request (the request ID comes from `metadata.extra["flag"]`)
+    pub async fn set_new_friend(&self, user_id: &str, request_id: &str, accept: bool) -> Result<()> {
+        self.call_action(
+            "set_new_friend",
+            json!({"user_id": user_id, "request_id": request_id, "accept": accept}),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Get the bot's own info
+    pub async fn get_self_info(&self) -> Result<Value> {
+        self.call_action("get_self_info", json!({})).await
+    }
+
+    /// Convert an outbound message into v12 segments, uploading media first
+    async fn message_to_segments(&self, self_id: Option<&str>, message: &Message) -> Result<Vec<Value>> {
+        let segments = match message {
+            Message::Text { content } => vec![text_segment(content)],
+            Message::Image { url, caption } => {
+                let mut segments = vec![file_segment("image", &self.upload_url_as(self_id, url).await?)];
+                if let Some(caption) = caption {
+                    segments.push(text_segment(caption));
+                }
+                segments
+            }
+            Message::Voice { url, .. } => vec![file_segment("voice", &self.upload_url_as(self_id, url).await?)],
+            Message::Video { url, .. } => vec![file_segment("video", &self.upload_url_as(self_id, url).await?)],
+            Message::Sticker { sticker_id } => vec![json!({"type": "face", "data": {"id": sticker_id}})],
+            Message::Chain { segments } => {
+                let mut values = Vec::with_capacity(segments.len());
+                for segment in segments {
+                    values.push(self.segment_to_v12(self_id, segment).await?);
+                }
+                values
+            }
+        };
+        Ok(segments)
+    }
+
+    /// Convert an outbound segment into a v12 segment, uploading media first
+    async fn segment_to_v12(&self, self_id: Option<&str>, segment: &Segment) -> Result<Value> {
+        let value = match segment {
+            Segment::Text { text } => text_segment(text),
+            Segment::At { user_id } if user_id == "all" => json!({"type": "mention_all", "data": {}}),
+            Segment::At { user_id } => json!({"type": "mention", "data": {"user_id": user_id}}),
+            Segment::Reply { message_id } => json!({"type": "reply", "data": {"message_id": message_id}}),
+            Segment::Image { url } => file_segment("image", &self.upload_url_as(self_id, url).await?),
+            Segment::Voice { url } => file_segment("voice", &self.upload_url_as(self_id, url).await?),
+            Segment::Video { url } => file_segment("video", &self.upload_url_as(self_id, url).await?),
+            Segment::File { url, .. } => file_segment("file", &self.upload_url_as(self_id, url).await?),
+            Segment::Markdown { content } => text_segment(content),
+            Segment::Face { id } => json!({"type": "face", "data": {"id": id}}),
+        };
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl Adapter for OneBot12Adapter {
+    fn name(&self) -> &str {
+        "OneBot12Adapter"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn adapter_id(&self) -> &str {
+        &self.config.adapter_id
+    }
+
+    fn config(&self) -> AdapterConfig {
+        self.config.clone()
+    }
+
+    fn status(&self) -> AdapterStatus {
+        // Use blocking read for synchronous method
+        tokio::task::block_in_place(|| {
+            let guard = tokio::runtime::Handle::current()
+                .block_on(self.status.read());
+            guard.clone()
+        })
+    }
+
+    fn statistics(&self) -> AdapterStatistics {
+        // Use blocking read for synchronous method
+        tokio::task::block_in_place(|| {
+            let guard = tokio::runtime::Handle::current()
+                .block_on(self.statistics.read());
+            guard.clone()
+        })
+    }
+
+    /// Connect to the forward WebSocket
+    async fn start(&self) -> Result<()> {
+        if *self.status.read().await == AdapterStatus::Running {
+            return Err(LoquatError::Adapter(AdapterError::LoadFailed(
+                "Adapter is already running".to_string()
+            )));
+        }
+        *self.status.write().await = AdapterStatus::Initializing;
+
+        let account = self.config.platform["self_id"].as_str().unwrap_or_default();
+        let tasks = connect_forward_ws(
+            &self.session,
+            &self.config.connection.url,
+            self.access_token(),
+            Duration::from_secs(self.config.connection.timeout.max(1)),
+            account,
+            Arc::clone(&self.status),
+        )
+        .await?;
+        self.tasks.lock().await.extend(tasks);
+
+        Ok(())
+    }
+
+    /// Close the connection
+    async fn stop(&self) -> Result<()> {
+        *self.status.write().await = AdapterStatus::Stopped;
+        self.session.detach_all().await;
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    /// Send a message via `send_message`
+    async fn send(&self, target: Target, message: Message) -> Result<String> {
+        self.send_as(None, target, message).await
+    }
+
+    /// Send a message from the connection of account `self_id`
+    async fn send_as(&self, self_id: Option<&str>, target: Target, message: Message) -> Result<String> {
+        let segments = self.message_to_segments(self_id, &message).await?;
+        let message_id = self.send_message_as(self_id, &target, segments).await?;
+
+        let mut stats = self.statistics.write().await;
+        stats.messages_sent += 1;
+        stats.last_activity = Some(chrono::Utc::now().timestamp());
+        drop(stats);
+
+        Ok(message_id)
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.session.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    /// Spawn a fake OneBot v12 implementation that pushes a channel message and answers actions
+    async fn spawn_fake_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+            let (mut sink, mut stream) = ws.split();
+
+            let event = json!({
+                "id": "e1", "time": 1700000000.0, "type": "message", "detail_type": "channel",
+                "sub_type": "", "message_id": "m1",
+                "self": {"platform": "qqguild", "user_id": "bot"},
+                "message": [{"type": "text", "data": {"text": "hello"}}],
+                "alt_message": "hello", "user_id": "u1", "guild_id": "g1", "channel_id": "c1"
+            });
+            sink.send(WsMessage::Text(event.to_string())).await.unwrap();
+
+            while let Some(Ok(WsMessage::Text(text))) = stream.next().await {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                let params = &request["params"];
+                let data = match request["action"].as_str() {
+                    Some("upload_file") => json!({"file_id": format!("file-{}", params["name"].as_str().unwrap())}),
+                    Some("send_message") if params["guild_id"] == "g1" => {
+                        json!({"message_id": params["message"][0]["data"]["file_id"].clone(), "time": 0})
+                    }
+                    _ => Value::Null,
+                };
+                let response = json!({
+                    "status": if data.is_null() { "failed" } else { "ok" },
+                    "retcode": if data.is_null() { 10002 } else { 0 },
+                    "data": data, "message": "", "echo": request["echo"]
+                });
+                sink.send(WsMessage::Text(response.to_string())).await.unwrap();
+            }
+        });
+
+        format!("ws://{}", addr)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_onebot12_forward_ws() {
+        let url = spawn_fake_server().await;
+        let adapter = OneBot12Adapter::new(AdapterConfig::new("onebot12", "onebot12-001", &url));
+        let mut events = adapter.subscribe();
+
+        let target = Target::Channel { channel_id: "c1".to_string() };
+        let message = Message::Image { url: "http://x/cat.png".to_string(), caption: None };
+        assert!(adapter.send(target.clone(), message.clone()).await.is_err());
+
+        adapter.start().await.unwrap();
+        assert!(adapter.is_running());
+
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type(), "message.text");
+        assert_eq!(event.self_id(), Some("bot"));
+
+        assert_eq!(adapter.send(target, message).await.unwrap(), "file-cat.png");
+
+        let target = Target::Group { group_id: "g2".to_string() };
+        let message = Message::Text { content: "pong".to_string() };
+        assert!(adapter.send(target, message).await.is_err());
+
+        let stats = adapter.statistics();
+        assert_eq!(stats.events_received, 1);
+        assert_eq!(stats.messages_sent, 1);
+
+        adapter.stop().await.unwrap();
+        assert_eq!(adapter.status(), AdapterStatus::Stopped);
+    }
+}
@@ -0,0 +1,389 @@
+//! OneBot v12 event converter
+
+use crate::adapters::converter::{
+    ConversionContext, EventConverter, MessageConverter, MetaConverter, NoticeConverter,
+    RequestConverter,
+};
+use crate::adapters::onebot11::converter::segments_to_message_event;
+use crate::adapters::onebot11::types::value_to_id;
+use crate::adapters::onebot12::types::normalize_segments;
+use crate::errors::{AdapterError, Result};
+use crate::events::{
+    ConnectionStatus, EventEnum, EventMetadata, EventSource, LifecyclePhase, MessageEvent,
+    MetaEvent, NoticeEvent, RequestEvent,
+};
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Converts OneBot v12 events into Loquat events
+///
+/// Events are dispatched on `type` and `detail_type`. Guild channels set
+/// the `channel_id`/`guild_id` metadata fields, and the guild of every seen
+/// channel is remembered so replies to a channel can be addressed.
+#[derive(Debug, Clone)]
+pub struct OneBot12Converter {
+    context: ConversionContext,
+    channel_guilds: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl OneBot12Converter {
+    /// Create a new converter
+    pub fn new(context: ConversionContext) -> Self {
+        Self {
+            context,
+            channel_guilds: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Get the conversion context
+    pub fn context(&self) -> &ConversionContext {
+        &self.context
+    }
+
+    /// Get the guild a channel belongs to, if an event from it was seen
+    pub fn guild_of(&self, channel_id: &str) -> Option<String> {
+        self.channel_guilds.read().ok()?.get(channel_id).cloned()
+    }
+
+    /// Build metadata shared by all events
+    fn metadata(&self, event: &Value, source: EventSource) -> EventMetadata {
+        let event_type = event["type"].as_str().unwrap_or("unknown");
+        let mut metadata = EventMetadata::new(&format!("onebot12.{}", event_type))
+            .with_source(source)
+            .with_extra("platform", &self.context.platform_type)
+            .with_adapter_id(&self.context.adapter_id)
+            .with_extra("detail_type", event["detail_type"].clone())
+            .with_extra("sub_type", event["sub_type"].clone());
+
+        if let Some(time) = event["time"].as_f64()
+            && let Some(ts) = Utc.timestamp_millis_opt((time * 1000.0) as i64).single()
+        {
+            metadata.timestamp = ts;
+        }
+
+        match value_to_id(&event["self"]["user_id"]) {
+            Some(self_id) => metadata = metadata.with_self_id(&self_id),
+            None if !self.context.self_id.is_empty() => {
+                metadata = metadata.with_self_id(&self.context.self_id)
+            }
+            None => {}
+        }
+        if let Some(platform) = event["self"]["platform"].as_str() {
+            metadata = metadata.with_extra("self_platform", platform);
+        }
+        if let Some(user_id) = value_to_id(&event["user_id"]) {
+            metadata = metadata.with_user_id(&user_id);
+        }
+        if let Some(group_id) = value_to_id(&event["group_id"]) {
+            metadata = metadata.with_group_id(&group_id);
+        }
+
+        let guild_id = value_to_id(&event["guild_id"]);
+        if let Some(guild_id) = &guild_id {
+            metadata = metadata.with_guild_id(guild_id);
+        }
+        if let Some(channel_id) = value_to_id(&event["channel_id"]) {
+            if let (Some(guild_id), Ok(mut guilds)) = (&guild_id, self.channel_guilds.write()) {
+                guilds.insert(channel_id.clone(), guild_id.clone());
+            }
+            metadata = metadata.with_channel_id(&channel_id);
+        }
+        if self.context.options.include_raw {
+            metadata = metadata.with_extra("raw", event);
+        }
+
+        metadata
+    }
+
+    fn require_id(event: &Value, key: &str) -> Result<String> {
+        value_to_id(&event[key]).ok_or_else(|| {
+            AdapterError::ConversionFailed(format!("missing field `{}`", key)).into()
+        })
+    }
+
+    /// Get the group a member event refers to (`group_id`, or `guild_id` for guilds)
+    fn require_group_or_guild(event: &Value) -> Result<String> {
+        Self::require_id(event, "group_id").or_else(|_| Self::require_id(event, "guild_id"))
+    }
+}
+
+impl EventConverter<Value> for OneBot12Converter {
+    fn convert(&self, event: Value) -> Result<EventEnum> {
+        match event["type"].as_str() {
+            Some("message") => self.convert_message(event).map(EventEnum::Message),
+            Some("notice") => self.convert_notice(event).map(EventEnum::Notice),
+            Some("request") => self.convert_request(event).map(EventEnum::Request),
+            Some("meta") => self.convert_meta(event).map(EventEnum::Meta),
+            other => Err(AdapterError::ConversionFailed(format!(
+                "unsupported event type: {:?}",
+                other
+            ))
+            .into()),
+        }
+    }
+
+    fn supported_types(&self) -> Vec<String> {
+        vec![
+            "message".to_string(),
+            "notice".to_string(),
+            "request".to_string(),
+            "meta".to_string(),
+        ]
+    }
+}
+
+impl MessageConverter<Value> for OneBot12Converter {
+    fn convert_message(&self, message: Value) -> Result<MessageEvent> {
+        let segments = normalize_segments(&message["message"]);
+        let mut metadata = self
+            .metadata(&message, EventSource::User)
+            .with_extra("message_type", message["detail_type"].clone());
+
+        if let Some(message_id) = value_to_id(&message["message_id"]) {
+            metadata = metadata.with_extra("message_id", message_id);
+        }
+        if let Some(alt_message) = message["alt_message"].as_str() {
+            metadata = metadata.with_extra("alt_message", alt_message);
+        }
+
+        Ok(segments_to_message_event(&segments, metadata))
+    }
+
+    fn supports_message(&self, message_type: &str) -> bool {
+        matches!(message_type, "private" | "group" | "channel")
+    }
+}
+
+impl NoticeConverter<Value> for OneBot12Converter {
+    fn convert_notice(&self, notice: Value) -> Result<NoticeEvent> {
+        let detail_type = notice["detail_type"].as_str().unwrap_or_default().to_string();
+        let sub_type = notice["sub_type"].as_str().unwrap_or_default();
+        let metadata = self
+            .metadata(&notice, EventSource::System)
+            .with_extra("notice_type", &detail_type);
+        let operator_id = value_to_id(&notice["operator_id"]).unwrap_or_default();
+
+        let event = match detail_type.as_str() {
+            "group_member_increase" | "guild_member_increase" => NoticeEvent::GroupMemberJoin {
+                user_id: Self::require_id(&notice, "user_id")?,
+                group_id: Self::require_group_or_guild(&notice)?,
+                user_info: None,
+                metadata,
+            },
+            "group_member_decrease" | "guild_member_decrease" if sub_type == "kick" => {
+                NoticeEvent::GroupMemberKick {
+                    user_id: Self::require_id(&notice, "user_id")?,
+                    group_id: Self::require_group_or_guild(&notice)?,
+                    operator_id,
+                    reason: None,
+                    metadata,
+                }
+            }
+            "group_member_decrease" | "guild_member_decrease" => NoticeEvent::GroupMemberLeave {
+                user_id: Self::require_id(&notice, "user_id")?,
+                group_id: Self::require_group_or_guild(&notice)?,
+                reason: None,
+                metadata,
+            },
+            "friend_increase" => NoticeEvent::FriendAdd {
+                user_id: Self::require_id(&notice, "user_id")?,
+                user_info: None,
+                metadata,
+            },
+            "friend_decrease" => NoticeEvent::FriendDelete {
+                user_id: Self::require_id(&notice, "user_id")?,
+                metadata,
+            },
+            _ => NoticeEvent::SystemNotice {
+                notice_type: detail_type,
+                content: notice.to_string(),
+                metadata,
+            },
+        };
+
+        Ok(event)
+    }
+
+    fn supports_notice(&self, notice_type: &str) -> bool {
+        matches!(
+            notice_type,
+            "group_member_increase"
+                | "group_member_decrease"
+                | "guild_member_increase"
+                | "guild_member_decrease"
+                | "friend_increase"
+                | "friend_decrease"
+        )
+    }
+}
+
+impl RequestConverter<Value> for OneBot12Converter {
+    fn convert_request(&self, request: Value) -> Result<RequestEvent> {
+        // OneBot v12 leaves requests to implementation extensions; accept the common names
+        let detail_type = request["detail_type"].as_str().unwrap_or_default();
+        let mut metadata = self
+            .metadata(&request, EventSource::User)
+            .with_extra("request_type", detail_type);
+        if let Some(request_id) = value_to_id(&request["request_id"]) {
+            metadata = metadata.with_extra("flag", request_id);
+        }
+        let comment = request["message"].as_str().map(|s| s.to_string());
+
+        match detail_type {
+            "new_friend" | "friend" => Ok(RequestEvent::FriendRequest {
+                from_user_id: Self::require_id(&request, "user_id")?,
+                comment,
+                metadata,
+            }),
+            "group_invite" | "invite_group" => Ok(RequestEvent::GroupInvite {
+                inviter_id: Self::require_id(&request, "user_id")?,
+                group_id: Self::require_group_or_guild(&request)?,
+                message: comment,
+                metadata,
+            }),
+            "join_group" | "group" => Ok(RequestEvent::GroupJoinRequest {
+                user_id: Self::require_id(&request, "user_id")?,
+                group_id: Self::require_group_or_guild(&request)?,
+                reason: comment,
+                metadata,
+            }),
+            _ => Err(AdapterError::ConversionFailed(format!(
+                "unsupported request detail_type: {}",
+                detail_type
+            ))
+            .into()),
+        }
+    }
+
+    fn supports_request(&self, request_type: &str) -> bool {
+        matches!(
+            request_type,
+            "new_friend" | "friend" | "group_invite" | "invite_group" | "join_group" | "group"
+        )
+    }
+}
+
+impl MetaConverter<Value> for OneBot12Converter {
+    fn convert_meta(&self, meta: Value) -> Result<MetaEvent> {
+        let metadata = self.metadata(&meta, EventSource::System);
+
+        match meta["detail_type"].as_str() {
+            Some("heartbeat") => Ok(MetaEvent::Heartbeat {
+                interval: meta["interval"].as_u64().unwrap_or(0) as u32,
+                metadata,
+            }),
+            Some("connect") => Ok(MetaEvent::Lifecycle {
+                phase: LifecyclePhase::Started,
+                metadata: metadata.with_extra("version", meta["version"].clone()),
+            }),
+            Some("status_update") => {
+                let good = meta["status"]["good"].as_bool().unwrap_or(false);
+                Ok(MetaEvent::ConnectionChange {
+                    status: if good { ConnectionStatus::Connected } else { ConnectionStatus::Disconnected },
+                    conn_type: None,
+                    reconnect_count: None,
+                    error: None,
+                    metadata: metadata.with_extra("status", meta["status"].clone()),
+                })
+            }
+            other => Err(AdapterError::ConversionFailed(format!(
+                "unsupported meta detail_type: {:?}",
+                other
+            ))
+            .into()),
+        }
+    }
+
+    fn supports_meta(&self, meta_type: &str) -> bool {
+        matches!(meta_type, "heartbeat" | "connect" | "status_update")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::ChannelType;
+    use crate::events::SiteType;
+    use serde_json::json;
+
+    fn converter() -> OneBot12Converter {
+        OneBot12Converter::new(ConversionContext::new("onebot12-001", "onebot12", ""))
+    }
+
+    #[test]
+    fn test_convert_channel_message() {
+        let converter = converter();
+        let event = json!({
+            "id": "e1", "time": 1700000000.5, "type": "message", "detail_type": "channel",
+            "sub_type": "", "message_id": "m1",
+            "self": {"platform": "qqguild", "user_id": "bot"},
+            "message": [
+                {"type": "mention", "data": {"user_id": "bot"}},
+                {"type": "text", "data": {"text": " hi"}}
+            ],
+            "alt_message": "@bot hi", "user_id": "u1", "guild_id": "g1", "channel_id": "c1"
+        });
+
+        let event = converter.convert(event).unwrap();
+        let EventEnum::Message(MessageEvent::At { text, at_list, metadata }) = event else {
+            panic!("expected at message");
+        };
+        assert_eq!(text, " hi");
+        assert_eq!(at_list, vec!["bot".to_string()]);
+        assert_eq!(metadata.self_id.as_deref(), Some("bot"));
+        assert_eq!(metadata.guild_id.as_deref(), Some("g1"));
+        assert_eq!(metadata.timestamp.timestamp_millis(), 1700000000500);
+
+        let channel = ChannelType::from_metadata(&metadata).unwrap();
+        assert_eq!(channel, ChannelType::channel("c1"));
+        assert_eq!(channel.target_site().site_type, SiteType::Channel("c1".to_string()));
+        assert_eq!(converter.guild_of("c1"), Some("g1".to_string()));
+    }
+
+    #[test]
+    fn test_convert_group_image() {
+        let event = json!({
+            "type": "message", "detail_type": "group", "user_id": "u1", "group_id": "g2",
+            "message": [{"type": "image", "data": {"file_id": "f1", "url": "http://x/a.png"}}]
+        });
+
+        let event = converter().convert_message(event).unwrap();
+        assert!(matches!(event, MessageEvent::Image { ref url, .. } if url == "http://x/a.png"));
+    }
+
+    #[test]
+    fn test_convert_notice_and_request() {
+        let event = json!({
+            "type": "notice", "detail_type": "guild_member_increase",
+            "guild_id": "g1", "user_id": "u1", "operator_id": "u2"
+        });
+        let event = converter().convert(event).unwrap();
+        assert!(matches!(
+            event,
+            EventEnum::Notice(NoticeEvent::GroupMemberJoin { ref group_id, .. }) if group_id == "g1"
+        ));
+
+        let event = json!({
+            "type": "request", "detail_type": "new_friend", "user_id": "u1",
+            "request_id": 7, "message": "hello"
+        });
+        let EventEnum::Request(RequestEvent::FriendRequest { metadata, comment, .. }) =
+            converter().convert(event).unwrap()
+        else {
+            panic!("expected friend request");
+        };
+        assert_eq!(comment.as_deref(), Some("hello"));
+        assert_eq!(metadata.extra["flag"], "7");
+    }
+
+    #[test]
+    fn test_convert_meta() {
+        let event = json!({"type": "meta", "detail_type": "heartbeat", "interval": 5000});
+        let event = converter().convert(event).unwrap();
+        assert!(matches!(event, EventEnum::Meta(MetaEvent::Heartbeat { interval: 5000, .. })));
+
+        assert!(converter().convert(json!({"post_type": "message"})).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! OneBot v12 Adapter Factory
+
+use crate::adapters::{
+    Adapter, AdapterConfig, AdapterFactory,
+};
+use crate::errors::{AdapterError, Result};
+use super::adapter::OneBot12Adapter;
+
+/// Factory for creating OneBot12Adapter instances
+pub struct OneBot12AdapterFactory;
+
+impl AdapterFactory for OneBot12AdapterFactory {
+    fn adapter_type(&self) -> &str {
+        "onebot12"
+    }
+
+    fn create(&self, config: AdapterConfig) -> Result<Box<dyn Adapter>> {
+        let url = &config.connection.url;
+        if !url.starts_with("ws://") && !url.starts_with("wss://") {
+            return Err(AdapterError::InvalidConfig(format!(
+                "OneBot v12 forward WebSocket URL must start with ws:// or wss://, got {}",
+                url
+            ))
+            .into());
+        }
+        Ok(Box::new(OneBot12Adapter::new(config)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::AdapterFactoryRegistry;
+
+    #[test]
+    fn test_onebot12_factory_create() {
+        let registry = AdapterFactoryRegistry::new();
+        registry.register(Box::new(OneBot12AdapterFactory)).unwrap();
+
+        let config = AdapterConfig::new("onebot12", "onebot12-001", "ws://127.0.0.1:6700");
+        let adapter = registry.create(config).unwrap();
+        assert_eq!(adapter.name(), "OneBot12Adapter");
+
+        let config = AdapterConfig::new("onebot12", "onebot12-002", "http://127.0.0.1:6700");
+        assert!(registry.create(config).is_err());
+    }
+}
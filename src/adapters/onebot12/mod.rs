@@ -0,0 +1,14 @@
+//! OneBot v12 adapter
+//!
+//! Connects to OneBot v12 implementations over a forward WebSocket. The
+//! action/response transport is shared with the OneBot v11 adapter.
+
+pub mod types;
+pub mod converter;
+pub mod adapter;
+pub mod factory;
+
+pub use types::*;
+pub use converter::*;
+pub use adapter::*;
+pub use factory::*;
@@ -0,0 +1,107 @@
+//! OneBot v12 message segment helpers
+//!
+//! Inbound v12 segments are normalized into the v11 segment shape so both
+//! protocols fold into `MessageEvent` through `segments_to_message_event`.
+
+use crate::adapters::onebot11::types::{text_segment, value_to_id};
+use crate::adapters::Target;
+use serde_json::{json, Value};
+
+/// Normalize a OneBot v12 segment into the equivalent v11 segment
+pub fn to_v11_segment(segment: &Value) -> Value {
+    let data = &segment["data"];
+    let id = |key: &str| value_to_id(&data[key]).unwrap_or_default();
+    let media = |seg_type: &str| {
+        json!({
+            "type": seg_type,
+            "data": {"file": id("file_id"), "url": data["url"].clone(), "name": data["name"].clone()},
+        })
+    };
+
+    match segment["type"].as_str().unwrap_or_default() {
+        "mention" => json!({"type": "at", "data": {"qq": id("user_id")}}),
+        "mention_all" => json!({"type": "at", "data": {"qq": "all"}}),
+        "reply" => json!({"type": "reply", "data": {"id": id("message_id")}}),
+        "image" => media("image"),
+        "voice" | "audio" => media("record"),
+        "video" => media("video"),
+        "file" => media("file"),
+        "location" => json!({
+            "type": "location",
+            "data": {"lat": data["latitude"].clone(), "lon": data["longitude"].clone(), "title": data["title"].clone()},
+        }),
+        _ => segment.clone(),
+    }
+}
+
+/// Normalize a v12 `message` array into v11 segments
+pub fn normalize_segments(message: &Value) -> Vec<Value> {
+    match message {
+        Value::Array(segments) => segments.iter().map(to_v11_segment).collect(),
+        Value::String(s) => vec![text_segment(s)],
+        _ => Vec::new(),
+    }
+}
+
+/// Build a segment referencing an uploaded file (`image`, `voice`, `video`, `file`)
+pub fn file_segment(seg_type: &str, file_id: &str) -> Value {
+    json!({"type": seg_type, "data": {"file_id": file_id}})
+}
+
+/// Build `send_message` parameters addressing a target
+///
+/// OneBot v12 channels live inside a guild, so channel targets need the
+/// guild ID of the channel (`None` if it is not known).
+pub fn target_params(target: &Target, guild_id: Option<&str>) -> Value {
+    match target {
+        Target::User { user_id } => json!({"detail_type": "private", "user_id": user_id}),
+        Target::Group { group_id } => json!({"detail_type": "group", "group_id": group_id}),
+        Target::Channel { channel_id } => json!({
+            "detail_type": "channel",
+            "guild_id": guild_id,
+            "channel_id": channel_id,
+        }),
+    }
+}
+
+/// Derive an upload file name from a URL (`http://x/a/b.png?s=1` -> `b.png`)
+pub fn file_name_of(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/')
+        .find(|s| !s.is_empty())
+        .unwrap_or("file")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_v11_segment() {
+        let segment = to_v11_segment(&json!({"type": "mention", "data": {"user_id": "10000"}}));
+        assert_eq!(segment, json!({"type": "at", "data": {"qq": "10000"}}));
+
+        let segment = to_v11_segment(&json!({"type": "reply", "data": {"message_id": "m1", "user_id": "1"}}));
+        assert_eq!(segment["data"]["id"], "m1");
+
+        let segment = to_v11_segment(&json!({"type": "voice", "data": {"file_id": "f1"}}));
+        assert_eq!(segment["type"], "record");
+        assert_eq!(segment["data"]["file"], "f1");
+    }
+
+    #[test]
+    fn test_target_params() {
+        let target = Target::Channel { channel_id: "c1".to_string() };
+        let params = target_params(&target, Some("g1"));
+        assert_eq!(params["detail_type"], "channel");
+        assert_eq!(params["guild_id"], "g1");
+        assert_eq!(params["channel_id"], "c1");
+    }
+
+    #[test]
+    fn test_file_name_of() {
+        assert_eq!(file_name_of("http://x/a/b.png?s=1"), "b.png");
+        assert_eq!(file_name_of("http://x/"), "x");
+    }
+}
@@ -0,0 +1,488 @@
+//! Satori adapter - event WebSocket plus HTTP API
+
+use async_trait::async_trait;
+use crate::adapters::converter::{ConversionContext, EventConverter};
+use crate::adapters::onebot11::types::value_to_id;
+use crate::adapters::satori::converter::SatoriConverter;
+use crate::adapters::satori::types::{
+    message_to_content, Signal, OP_EVENT, OP_IDENTIFY, OP_PING, OP_READY,
+};
+use crate::adapters::{
+    Adapter, AdapterConfig, AdapterStatus, Message, Target,
+    DEFAULT_EVENT_CHANNEL_CAPACITY,
+    types::AdapterStatistics,
+};
+use crate::errors::{AdapterError, LoquatError, Result};
+use crate::events::EventEnum;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+/// Interval of the `PING` signal required by the Satori event WebSocket
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Bot account used for API calls (`Satori-Platform` / `Satori-User-ID` headers)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatoriLogin {
+    /// Platform name (e.g. `discord`, `qq`)
+    pub platform: String,
+    /// Bot user ID on the platform
+    pub self_id: String,
+}
+
+/// Satori adapter implementation
+///
+/// Events arrive on the WebSocket at `connection.url` (`.../v1/events`);
+/// actions are HTTP calls to `platform.api_url`, which defaults to the same
+/// host and version prefix.
+#[derive(Debug)]
+pub struct SatoriAdapter {
+    config: AdapterConfig,
+    converter: Arc<SatoriConverter>,
+    status: Arc<RwLock<AdapterStatus>>,
+    statistics: Arc<RwLock<AdapterStatistics>>,
+    event_sender: broadcast::Sender<EventEnum>,
+    client: reqwest::Client,
+    api_base: String,
+    login: Arc<RwLock<Option<SatoriLogin>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl SatoriAdapter {
+    /// Create a new Satori adapter
+    pub fn new(config: AdapterConfig) -> Self {
+        let (event_sender, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
+        let self_id = config.platform["self_id"].as_str().unwrap_or_default().to_string();
+        let converter = SatoriConverter::new(ConversionContext::new(
+            &config.adapter_id,
+            "satori",
+            &self_id,
+        ));
+        let api_base = config.platform["api_url"]
+            .as_str()
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| api_base_of(&config.connection.url));
+        let login = config.platform["platform"].as_str().map(|platform| SatoriLogin {
+            platform: platform.to_string(),
+            self_id,
+        });
+
+        Self {
+            config,
+            converter: Arc::new(converter),
+            status: Arc::new(RwLock::new(AdapterStatus::Ready)),
+            statistics: Arc::new(RwLock::new(AdapterStatistics::default())),
+            event_sender,
+            client: reqwest::Client::new(),
+            api_base,
+            login: Arc::new(RwLock::new(login)),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get the token from platform configuration
+    pub fn token(&self) -> Option<&str> {
+        self.config.platform["token"]
+            .as_str()
+            .or_else(|| self.config.platform["access_token"].as_str())
+            .filter(|t| !t.is_empty())
+    }
+
+    /// Get the HTTP API base URL
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    /// Get the login used for API calls (configured, or the first from `READY`)
+    pub async fn login(&self) -> Option<SatoriLogin> {
+        self.login.read().await.clone()
+    }
+
+    /// Call an API method (`POST {api_base}/{method}`) and return the response body
+    pub async fn call_api(&self, method: &str, body: Value) -> Result<Value> {
+        let mut request = self.client
+            .post(format!("{}/{}", self.api_base, method))
+            .timeout(Duration::from_secs(self.config.connection.timeout.max(1)))
+            .json(&body);
+        if let Some(token) = self.token() {
+            request = request.bearer_auth(token);
+        }
+        if let Some(login) = self.login().await {
+            request = request
+                .header("Satori-Platform", &login.platform)
+                .header("Satori-User-ID", &login.self_id)
+                .header("X-Platform", &login.platform)
+                .header("X-Self-ID", &login.self_id);
+        }
+
+        let response = match request.send().await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                self.record_error().await;
+                return Err(AdapterError::SendFailed(format!(
+                    "{} returned {}",
+                    method,
+                    response.status()
+                ))
+                .into());
+            }
+            Err(e) => {
+                self.record_error().await;
+                return Err(AdapterError::ConnectionFailed(format!("{} request failed: {}", method, e)).into());
+            }
+        };
+
+        let text = response.text().await
+            .map_err(|e| AdapterError::ConnectionFailed(format!("{} response failed: {}", method, e)))?;
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).map_err(|e| LoquatError::Serialization(e.to_string()))
+    }
+
+    /// Create a message in a channel, returning the ID of the first created message
+    pub async fn create_message(&self, channel_id: &str, content: &str) -> Result<String> {
+        let data = self
+            .call_api("message.create", json!({"channel_id": channel_id, "content": content}))
+            .await?;
+        Ok(value_to_id(&data[0]["id"]).unwrap_or_default())
+    }
+
+    /// Get (or create) the direct channel with a user
+    pub async fn direct_channel(&self, user_id: &str) -> Result<String> {
+        let data = self.call_api("user.channel.create", json!({"user_id": user_id})).await?;
+        value_to_id(&data["id"]).ok_or_else(|| {
+            AdapterError::SendFailed(format!("no direct channel for user {}", user_id)).into()
+        })
+    }
+
+    /// Approve or reject a friend request (the ID comes from `metadata.extra["flag"]`)
+    pub async fn approve_friend(&self, message_id: &str, approve: bool, comment: Option<&str>) -> Result<()> {
+        self.call_api(
+            "friend.approve",
+            json!({"message_id": message_id, "approve": approve, "comment": comment}),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn record_error(&self) {
+        self.statistics.write().await.errors += 1;
+    }
+}
+
+/// Derive the API base from the event WebSocket URL (`ws://h/v1/events` -> `http://h/v1`)
+pub(crate) fn api_base_of(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix("/events").unwrap_or(url);
+    if let Some(rest) = url.strip_prefix("wss://") {
+        format!("https://{}", rest)
+    } else if let Some(rest) = url.strip_prefix("ws://") {
+        format!("http://{}", rest)
+    } else {
+        url.to_string()
+    }
+}
+
+/// Handle one signal from the event WebSocket
+async fn handle_signal(
+    signal: Signal,
+    converter: &SatoriConverter,
+    event_sender: &broadcast::Sender<EventEnum>,
+    statistics: &RwLock<AdapterStatistics>,
+    login: &RwLock<Option<SatoriLogin>>,
+) {
+    match signal.op {
+        OP_EVENT => match converter.convert(signal.body) {
+            Ok(event) => {
+                let mut stats = statistics.write().await;
+                stats.events_received += 1;
+                stats.last_activity = Some(chrono::Utc::now().timestamp());
+                drop(stats);
+                let _ = event_sender.send(event);
+            }
+            Err(_) => statistics.write().await.errors += 1,
+        },
+        OP_READY => {
+            let first = &signal.body["logins"][0];
+            let self_id = value_to_id(&first["self_id"]).or_else(|| value_to_id(&first["user"]["id"]));
+            if let (Some(platform), Some(self_id)) = (first["platform"].as_str(), self_id) {
+                let mut login = login.write().await;
+                if login.is_none() {
+                    *login = Some(SatoriLogin { platform: platform.to_string(), self_id });
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[async_trait]
+impl Adapter for SatoriAdapter {
+    fn name(&self) -> &str {
+        "SatoriAdapter"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn adapter_id(&self) -> &str {
+        &self.config.adapter_id
+    }
+
+    fn config(&self) -> AdapterConfig {
+        self.config.clone()
+    }
+
+    fn status(&self) -> AdapterStatus {
+        // Use blocking read for synchronous method
+        tokio::task::block_in_place(|| {
+            let guard = tokio::runtime::Handle::current()
+                .block_on(self.status.read());
+            guard.clone()
+        })
+    }
+
+    fn statistics(&self) -> AdapterStatistics {
+        // Use blocking read for synchronous method
+        tokio::task::block_in_place(|| {
+            let guard = tokio::runtime::Handle::current()
+                .block_on(self.statistics.read());
+            guard.clone()
+        })
+    }
+
+    /// Connect to the event WebSocket and identify
+    async fn start(&self) -> Result<()> {
+        if *self.status.read().await == AdapterStatus::Running {
+            return Err(LoquatError::Adapter(AdapterError::LoadFailed(
+                "Adapter is already running".to_string()
+            )));
+        }
+        *self.status.write().await = AdapterStatus::Initializing;
+
+        let url = &self.config.connection.url;
+        let connect_timeout = Duration::from_secs(self.config.connection.timeout.max(1));
+        let connected = tokio::time::timeout(connect_timeout, tokio_tungstenite::connect_async(url.as_str())).await;
+        let ws_stream = match connected {
+            Ok(Ok((ws_stream, _))) => ws_stream,
+            Ok(Err(e)) => {
+                let reason = format!("Failed to connect to {}: {}", url, e);
+                *self.status.write().await = AdapterStatus::Error(reason.clone());
+                return Err(AdapterError::ConnectionFailed(reason).into());
+            }
+            Err(_) => {
+                let reason = format!("Timed out connecting to {}", url);
+                *self.status.write().await = AdapterStatus::Error(reason.clone());
+                return Err(AdapterError::ConnectionFailed(reason).into());
+            }
+        };
+
+        let (mut sink, mut stream) = ws_stream.split();
+        let identify = Signal::new(OP_IDENTIFY, json!({"token": self.token()}));
+        let frame = serde_json::to_string(&identify).map_err(|e| LoquatError::Serialization(e.to_string()))?;
+        if let Err(e) = sink.send(WsMessage::Text(frame)).await {
+            let reason = format!("Failed to identify: {}", e);
+            *self.status.write().await = AdapterStatus::Error(reason.clone());
+            return Err(AdapterError::ConnectionFailed(reason).into());
+        }
+        *self.status.write().await = AdapterStatus::Running;
+
+        let converter = Arc::clone(&self.converter);
+        let event_sender = self.event_sender.clone();
+        let statistics = Arc::clone(&self.statistics);
+        let login = Arc::clone(&self.login);
+        let status = Arc::clone(&self.status);
+        let task = tokio::spawn(async move {
+            let ping = Signal::new(OP_PING, Value::Null);
+            let ping = serde_json::to_string(&ping).unwrap_or_default();
+            let mut ticker = tokio::time::interval(PING_INTERVAL);
+            ticker.tick().await;
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        if sink.send(WsMessage::Text(ping.clone())).await.is_err() {
+                            break;
+                        }
+                    }
+                    message = stream.next() => match message {
+                        Some(Ok(WsMessage::Text(text))) => {
+                            if let Ok(signal) = serde_json::from_str::<Signal>(&text) {
+                                handle_signal(signal, &converter, &event_sender, &statistics, &login).await;
+                            }
+                        }
+                        Some(Ok(WsMessage::Close(_))) | None => break,
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            *status.write().await = AdapterStatus::Error(e.to_string());
+                            return;
+                        }
+                    }
+                }
+            }
+
+            let mut status = status.write().await;
+            if *status == AdapterStatus::Running {
+                *status = AdapterStatus::Error("Connection closed by remote".to_string());
+            }
+        });
+        self.tasks.lock().await.push(task);
+
+        Ok(())
+    }
+
+    /// Close the connection
+    async fn stop(&self) -> Result<()> {
+        *self.status.write().await = AdapterStatus::Stopped;
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    /// Send a message via `message.create`
+    ///
+    /// Group targets are sent to the channel of the same ID; user targets
+    /// go through their direct channel.
+    async fn send(&self, target: Target, message: Message) -> Result<String> {
+        let channel_id = match target {
+            Target::User { user_id } => self.direct_channel(&user_id).await?,
+            Target::Group { group_id } => group_id,
+            Target::Channel { channel_id } => channel_id,
+        };
+        let message_id = self.create_message(&channel_id, &message_to_content(&message)).await?;
+
+        let mut stats = self.statistics.write().await;
+        stats.messages_sent += 1;
+        stats.last_activity = Some(chrono::Utc::now().timestamp());
+        drop(stats);
+
+        Ok(message_id)
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.event_sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        Json, Router,
+        extract::ws::{Message as AxumWsMessage, WebSocketUpgrade},
+        http::{HeaderMap, StatusCode},
+        response::IntoResponse,
+        routing::{get, post},
+    };
+    use tokio::net::TcpListener;
+
+    fn authorized(headers: &HeaderMap) -> bool {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        header("Authorization") == Some("Bearer secret")
+            && header("Satori-Platform") == Some("discord")
+            && header("Satori-User-ID") == Some("bot")
+    }
+
+    async fn events(ws: WebSocketUpgrade) -> impl IntoResponse {
+        ws.on_upgrade(|mut socket| async move {
+            let Some(Ok(AxumWsMessage::Text(identify))) = socket.recv().await else {
+                return;
+            };
+            let identify: Signal = serde_json::from_str(&identify).unwrap();
+            if identify.op != OP_IDENTIFY || identify.body["token"] != "secret" {
+                return;
+            }
+
+            let ready = json!({"op": OP_READY, "body": {"logins": [
+                {"platform": "discord", "user": {"id": "bot"}, "status": 1}
+            ]}});
+            socket.send(AxumWsMessage::Text(ready.to_string())).await.unwrap();
+            let event = json!({"op": OP_EVENT, "body": {
+                "id": 1, "type": "message-created", "platform": "discord", "self_id": "bot",
+                "timestamp": 1700000000000i64,
+                "channel": {"id": "c1", "type": 0}, "guild": {"id": "g1"},
+                "user": {"id": "u1"}, "message": {"id": "m1", "content": "hello"}
+            }});
+            socket.send(AxumWsMessage::Text(event.to_string())).await.unwrap();
+
+            while let Some(Ok(_)) = socket.recv().await {}
+        })
+    }
+
+    /// Spawn a fake Satori server, returning the event WebSocket URL
+    async fn spawn_fake_server() -> String {
+        let router = Router::new()
+            .route("/v1/events", get(events))
+            .route(
+                "/v1/message.create",
+                post(|headers: HeaderMap, Json(body): Json<Value>| async move {
+                    if !authorized(&headers) {
+                        return Err(StatusCode::UNAUTHORIZED);
+                    }
+                    let id = format!("sent-to-{}", body["channel_id"].as_str().unwrap_or_default());
+                    Ok(Json(json!([{"id": id, "content": body["content"]}])))
+                }),
+            )
+            .route(
+                "/v1/user.channel.create",
+                post(|Json(body): Json<Value>| async move {
+                    Json(json!({"id": format!("dm-{}", body["user_id"].as_str().unwrap_or_default()), "type": 1}))
+                }),
+            );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        format!("ws://{}/v1/events", addr)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_satori_adapter() {
+        let url = spawn_fake_server().await;
+        let config = AdapterConfig::new("satori", "satori-001", &url)
+            .with_platform_config("token", "secret")
+            .unwrap();
+        let adapter = SatoriAdapter::new(config);
+        assert!(adapter.api_base().ends_with("/v1"));
+        let mut events = adapter.subscribe();
+
+        adapter.start().await.unwrap();
+        assert!(adapter.is_running());
+
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type(), "message.text");
+        let expected = SatoriLogin { platform: "discord".to_string(), self_id: "bot".to_string() };
+        assert_eq!(adapter.login().await, Some(expected));
+
+        let target = Target::Channel { channel_id: "c1".to_string() };
+        let message = Message::Text { content: "pong".to_string() };
+        assert_eq!(adapter.send(target, message).await.unwrap(), "sent-to-c1");
+
+        let target = Target::User { user_id: "u1".to_string() };
+        let message = Message::Text { content: "hi".to_string() };
+        assert_eq!(adapter.send(target, message).await.unwrap(), "sent-to-dm-u1");
+
+        let stats = adapter.statistics();
+        assert_eq!(stats.events_received, 1);
+        assert_eq!(stats.messages_sent, 2);
+
+        adapter.stop().await.unwrap();
+        assert_eq!(adapter.status(), AdapterStatus::Stopped);
+    }
+
+    #[test]
+    fn test_api_base_of() {
+        assert_eq!(api_base_of("ws://127.0.0.1:5140/satori/v1/events"), "http://127.0.0.1:5140/satori/v1");
+        assert_eq!(api_base_of("wss://example.com/v1/events/"), "https://example.com/v1");
+    }
+}
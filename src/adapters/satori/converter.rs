@@ -0,0 +1,344 @@
+//! Satori event converter
+
+use crate::adapters::converter::{
+    ConversionContext, EventConverter, MessageConverter, MetaConverter, NoticeConverter,
+    RequestConverter,
+};
+use crate::adapters::onebot11::converter::segments_to_message_event;
+use crate::adapters::onebot11::types::value_to_id;
+use crate::adapters::satori::types::{parse_content, CHANNEL_TYPE_DIRECT};
+use crate::errors::{AdapterError, Result};
+use crate::events::{
+    EventEnum, EventMetadata, EventSource, LifecyclePhase, MessageEvent, MetaEvent, NoticeEvent,
+    RequestEvent,
+};
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+
+/// Converts Satori event bodies into Loquat events
+///
+/// Direct channels become private conversations; every other channel is
+/// exposed as `channel_id` (with its `guild_id`) so it resolves to
+/// `ChannelType::Channel`.
+#[derive(Debug, Clone)]
+pub struct SatoriConverter {
+    context: ConversionContext,
+}
+
+impl SatoriConverter {
+    /// Create a new converter
+    pub fn new(context: ConversionContext) -> Self {
+        Self { context }
+    }
+
+    /// Get the conversion context
+    pub fn context(&self) -> &ConversionContext {
+        &self.context
+    }
+
+    /// Build metadata shared by all events
+    fn metadata(&self, event: &Value, source: EventSource) -> EventMetadata {
+        let event_type = event["type"].as_str().unwrap_or("unknown");
+        let mut metadata = EventMetadata::new(&format!("satori.{}", event_type))
+            .with_source(source)
+            .with_extra("platform", &self.context.platform_type)
+            .with_adapter_id(&self.context.adapter_id)
+            .with_extra("satori_type", event_type);
+
+        if let Some(millis) = event["timestamp"].as_i64()
+            && let Some(ts) = Utc.timestamp_millis_opt(millis).single()
+        {
+            metadata.timestamp = ts;
+        }
+
+        let self_id = value_to_id(&event["self_id"]).or_else(|| value_to_id(&event["login"]["user"]["id"]));
+        match self_id {
+            Some(self_id) => metadata = metadata.with_self_id(&self_id),
+            None if !self.context.self_id.is_empty() => {
+                metadata = metadata.with_self_id(&self.context.self_id)
+            }
+            None => {}
+        }
+        if let Some(platform) = event["platform"].as_str().or_else(|| event["login"]["platform"].as_str()) {
+            metadata = metadata.with_extra("self_platform", platform);
+        }
+        if let Some(user_id) = value_to_id(&event["user"]["id"]) {
+            metadata = metadata.with_user_id(&user_id);
+        }
+        if let Some(name) = event["member"]["nick"].as_str().or_else(|| event["user"]["name"].as_str()) {
+            metadata = metadata.with_extra("sender_nickname", name);
+        }
+
+        let direct = event["channel"]["type"].as_u64() == Some(CHANNEL_TYPE_DIRECT);
+        if !direct {
+            if let Some(guild_id) = value_to_id(&event["guild"]["id"]) {
+                metadata = metadata.with_guild_id(&guild_id);
+            }
+            if let Some(channel_id) = value_to_id(&event["channel"]["id"]) {
+                metadata = metadata.with_channel_id(&channel_id);
+            }
+        }
+        if self.context.options.include_raw {
+            metadata = metadata.with_extra("raw", event);
+        }
+
+        metadata
+    }
+
+    fn require(event: &Value, object: &str) -> Result<String> {
+        value_to_id(&event[object]["id"]).ok_or_else(|| {
+            AdapterError::ConversionFailed(format!("missing field `{}.id`", object)).into()
+        })
+    }
+}
+
+impl EventConverter<Value> for SatoriConverter {
+    fn convert(&self, event: Value) -> Result<EventEnum> {
+        match event["type"].as_str() {
+            Some("message-created") => self.convert_message(event).map(EventEnum::Message),
+            Some("friend-request") | Some("guild-request") | Some("guild-member-request") => {
+                self.convert_request(event).map(EventEnum::Request)
+            }
+            Some(t) if t.starts_with("login-") => self.convert_meta(event).map(EventEnum::Meta),
+            Some(_) => self.convert_notice(event).map(EventEnum::Notice),
+            None => Err(AdapterError::ConversionFailed("missing event type".to_string()).into()),
+        }
+    }
+
+    fn supported_types(&self) -> Vec<String> {
+        vec![
+            "message-created".to_string(),
+            "guild-member-added".to_string(),
+            "guild-member-removed".to_string(),
+            "friend-request".to_string(),
+            "guild-request".to_string(),
+            "guild-member-request".to_string(),
+            "login-added".to_string(),
+            "login-removed".to_string(),
+            "login-updated".to_string(),
+        ]
+    }
+}
+
+impl MessageConverter<Value> for SatoriConverter {
+    fn convert_message(&self, message: Value) -> Result<MessageEvent> {
+        let content = message["message"]["content"].as_str().unwrap_or_default();
+        let segments = parse_content(content);
+        let direct = message["channel"]["type"].as_u64() == Some(CHANNEL_TYPE_DIRECT);
+        let mut metadata = self
+            .metadata(&message, EventSource::User)
+            .with_extra("message_type", if direct { "private" } else { "channel" });
+
+        if let Some(message_id) = value_to_id(&message["message"]["id"]) {
+            metadata = metadata.with_extra("message_id", message_id);
+        }
+
+        Ok(segments_to_message_event(&segments, metadata))
+    }
+
+    fn supports_message(&self, message_type: &str) -> bool {
+        matches!(message_type, "private" | "channel")
+    }
+}
+
+impl NoticeConverter<Value> for SatoriConverter {
+    fn convert_notice(&self, notice: Value) -> Result<NoticeEvent> {
+        let notice_type = notice["type"].as_str().unwrap_or_default().to_string();
+        let metadata = self
+            .metadata(&notice, EventSource::System)
+            .with_extra("notice_type", &notice_type);
+
+        let event = match notice_type.as_str() {
+            "guild-member-added" => NoticeEvent::GroupMemberJoin {
+                user_id: Self::require(&notice, "user")?,
+                group_id: Self::require(&notice, "guild")?,
+                user_info: None,
+                metadata,
+            },
+            "guild-member-removed" => match value_to_id(&notice["operator"]["id"]) {
+                Some(operator_id) if Some(&operator_id) != value_to_id(&notice["user"]["id"]).as_ref() => {
+                    NoticeEvent::GroupMemberKick {
+                        user_id: Self::require(&notice, "user")?,
+                        group_id: Self::require(&notice, "guild")?,
+                        operator_id,
+                        reason: None,
+                        metadata,
+                    }
+                }
+                _ => NoticeEvent::GroupMemberLeave {
+                    user_id: Self::require(&notice, "user")?,
+                    group_id: Self::require(&notice, "guild")?,
+                    reason: None,
+                    metadata,
+                },
+            },
+            _ => NoticeEvent::SystemNotice {
+                notice_type,
+                content: notice.to_string(),
+                metadata,
+            },
+        };
+
+        Ok(event)
+    }
+
+    fn supports_notice(&self, notice_type: &str) -> bool {
+        matches!(notice_type, "guild-member-added" | "guild-member-removed")
+    }
+}
+
+impl RequestConverter<Value> for SatoriConverter {
+    fn convert_request(&self, request: Value) -> Result<RequestEvent> {
+        let request_type = request["type"].as_str().unwrap_or_default();
+        let mut metadata = self
+            .metadata(&request, EventSource::User)
+            .with_extra("request_type", request_type);
+        // Satori identifies requests by `message.id`, used with `*.approve` APIs
+        if let Some(message_id) = value_to_id(&request["message"]["id"]) {
+            metadata = metadata.with_extra("flag", message_id);
+        }
+        let comment = request["message"]["content"].as_str().map(|s| s.to_string());
+
+        match request_type {
+            "friend-request" => Ok(RequestEvent::FriendRequest {
+                from_user_id: Self::require(&request, "user")?,
+                comment,
+                metadata,
+            }),
+            "guild-request" => Ok(RequestEvent::GroupInvite {
+                inviter_id: Self::require(&request, "user")?,
+                group_id: Self::require(&request, "guild")?,
+                message: comment,
+                metadata,
+            }),
+            "guild-member-request" => Ok(RequestEvent::GroupJoinRequest {
+                user_id: Self::require(&request, "user")?,
+                group_id: Self::require(&request, "guild")?,
+                reason: comment,
+                metadata,
+            }),
+            _ => Err(AdapterError::ConversionFailed(format!(
+                "unsupported request type: {}",
+                request_type
+            ))
+            .into()),
+        }
+    }
+
+    fn supports_request(&self, request_type: &str) -> bool {
+        matches!(request_type, "friend-request" | "guild-request" | "guild-member-request")
+    }
+}
+
+impl MetaConverter<Value> for SatoriConverter {
+    fn convert_meta(&self, meta: Value) -> Result<MetaEvent> {
+        let metadata = self.metadata(&meta, EventSource::System);
+
+        let phase = match meta["type"].as_str() {
+            Some("login-added") => LifecyclePhase::Started,
+            Some("login-removed") => LifecyclePhase::Stopped,
+            Some("login-updated") => LifecyclePhase::Resumed,
+            other => {
+                return Err(AdapterError::ConversionFailed(format!(
+                    "unsupported login event: {:?}",
+                    other
+                ))
+                .into());
+            }
+        };
+
+        Ok(MetaEvent::Lifecycle {
+            phase,
+            metadata: metadata.with_extra("login_status", meta["login"]["status"].clone()),
+        })
+    }
+
+    fn supports_meta(&self, meta_type: &str) -> bool {
+        matches!(meta_type, "login-added" | "login-removed" | "login-updated")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::ChannelType;
+    use crate::events::SiteType;
+    use serde_json::json;
+
+    fn converter() -> SatoriConverter {
+        SatoriConverter::new(ConversionContext::new("satori-001", "satori", ""))
+    }
+
+    #[test]
+    fn test_convert_channel_message() {
+        let event = json!({
+            "id": 1, "type": "message-created", "platform": "discord", "self_id": "bot",
+            "timestamp": 1700000000123i64,
+            "channel": {"id": "c1", "type": 0}, "guild": {"id": "g1"},
+            "user": {"id": "u1", "name": "alice"},
+            "message": {"id": "m1", "content": "<at id=\"bot\"/> hi"}
+        });
+
+        let EventEnum::Message(MessageEvent::At { text, at_list, metadata }) =
+            converter().convert(event).unwrap()
+        else {
+            panic!("expected at message");
+        };
+        assert_eq!(text, " hi");
+        assert_eq!(at_list, vec!["bot".to_string()]);
+        assert_eq!(metadata.self_id.as_deref(), Some("bot"));
+        assert_eq!(metadata.extra["message_id"], "m1");
+        assert_eq!(metadata.guild_id.as_deref(), Some("g1"));
+        assert_eq!(metadata.timestamp.timestamp_millis(), 1700000000123);
+
+        let channel = ChannelType::from_metadata(&metadata).unwrap();
+        assert_eq!(channel, ChannelType::channel("c1"));
+        assert_eq!(channel.target_site().site_type, SiteType::Channel("c1".to_string()));
+    }
+
+    #[test]
+    fn test_convert_direct_message() {
+        let event = json!({
+            "type": "message-created", "login": {"platform": "qq", "user": {"id": "bot"}},
+            "channel": {"id": "private:u1", "type": 1}, "user": {"id": "u1"},
+            "message": {"id": "m2", "content": "hello"}
+        });
+
+        let EventEnum::Message(MessageEvent::Text { text, metadata }) = converter().convert(event).unwrap() else {
+            panic!("expected text message");
+        };
+        assert_eq!(text, "hello");
+        assert_eq!(metadata.self_id.as_deref(), Some("bot"));
+        assert_eq!(ChannelType::from_metadata(&metadata), Some(ChannelType::private("u1")));
+    }
+
+    #[test]
+    fn test_convert_notice_request_and_login() {
+        let event = json!({
+            "type": "guild-member-removed", "guild": {"id": "g1"},
+            "user": {"id": "u1"}, "operator": {"id": "u2"}
+        });
+        assert!(matches!(
+            converter().convert(event).unwrap(),
+            EventEnum::Notice(NoticeEvent::GroupMemberKick { ref operator_id, .. }) if operator_id == "u2"
+        ));
+
+        let event = json!({
+            "type": "guild-member-request", "guild": {"id": "g1"}, "user": {"id": "u1"},
+            "message": {"id": "req-1", "content": "let me in"}
+        });
+        let EventEnum::Request(RequestEvent::GroupJoinRequest { reason, metadata, .. }) =
+            converter().convert(event).unwrap()
+        else {
+            panic!("expected join request");
+        };
+        assert_eq!(reason.as_deref(), Some("let me in"));
+        assert_eq!(metadata.extra["flag"], "req-1");
+
+        let event = json!({"type": "login-removed", "login": {"status": 0}});
+        assert!(matches!(
+            converter().convert(event).unwrap(),
+            EventEnum::Meta(MetaEvent::Lifecycle { phase: LifecyclePhase::Stopped, .. })
+        ));
+    }
+}
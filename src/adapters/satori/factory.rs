@@ -0,0 +1,47 @@
+//! Satori Adapter Factory
+
+use crate::adapters::{
+    Adapter, AdapterConfig, AdapterFactory,
+};
+use crate::errors::{AdapterError, Result};
+use super::adapter::SatoriAdapter;
+
+/// Factory for creating SatoriAdapter instances
+pub struct SatoriAdapterFactory;
+
+impl AdapterFactory for SatoriAdapterFactory {
+    fn adapter_type(&self) -> &str {
+        "satori"
+    }
+
+    fn create(&self, config: AdapterConfig) -> Result<Box<dyn Adapter>> {
+        let url = &config.connection.url;
+        if !url.starts_with("ws://") && !url.starts_with("wss://") {
+            return Err(AdapterError::InvalidConfig(format!(
+                "Satori event URL must start with ws:// or wss://, got {}",
+                url
+            ))
+            .into());
+        }
+        Ok(Box::new(SatoriAdapter::new(config)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::AdapterFactoryRegistry;
+
+    #[test]
+    fn test_satori_factory_create() {
+        let registry = AdapterFactoryRegistry::new();
+        registry.register(Box::new(SatoriAdapterFactory)).unwrap();
+
+        let config = AdapterConfig::new("satori", "satori-001", "ws://127.0.0.1:5140/satori/v1/events");
+        let adapter = registry.create(config).unwrap();
+        assert_eq!(adapter.name(), "SatoriAdapter");
+
+        let config = AdapterConfig::new("satori", "satori-002", "127.0.0.1:5140");
+        assert!(registry.create(config).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+//! Satori adapter
+//!
+//! Connects to Satori gateways (Koishi, Chronocat, ...): events over the
+//! `/v1/events` WebSocket, actions over the HTTP API.
+
+pub mod types;
+pub mod converter;
+pub mod adapter;
+pub mod factory;
+
+pub use types::*;
+pub use converter::*;
+pub use adapter::*;
+pub use factory::*;
@@ -0,0 +1,226 @@
+//! Satori protocol types and message element helpers
+//!
+//! Satori messages are XHTML-like element strings (`hi <at id="1"/>`). Inbound
+//! content is parsed into OneBot v11 shaped segments so it folds into
+//! `MessageEvent` through `segments_to_message_event`.
+
+use crate::adapters::{Message, Segment};
+use crate::adapters::onebot11::types::text_segment;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Event dispatch
+pub const OP_EVENT: u8 = 0;
+/// Ping sent by the client
+pub const OP_PING: u8 = 1;
+/// Pong answered by the server
+pub const OP_PONG: u8 = 2;
+/// Authentication sent by the client
+pub const OP_IDENTIFY: u8 = 3;
+/// Authentication succeeded, carries the logins
+pub const OP_READY: u8 = 4;
+
+/// Channel type of a direct (private) conversation
+pub const CHANNEL_TYPE_DIRECT: u64 = 1;
+
+/// WebSocket signal frame
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Signal {
+    /// Opcode
+    pub op: u8,
+    /// Signal body
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub body: Value,
+}
+
+impl Signal {
+    /// Create a new signal
+    pub fn new(op: u8, body: Value) -> Self {
+        Self { op, body }
+    }
+}
+
+/// Parse Satori message content into message segments
+pub fn parse_content(content: &str) -> Vec<Value> {
+    let mut segments = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            segments.push(text_segment(&unescape(&rest[..start])));
+        }
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        if tag.starts_with('/') {
+            continue;
+        }
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let attrs = parse_attrs(attrs);
+        let attr = |key: &str| attrs.get(key).cloned().unwrap_or(Value::Null);
+
+        let segment = match name {
+            "at" if attrs.get("type").and_then(Value::as_str) == Some("all") => {
+                json!({"type": "at", "data": {"qq": "all"}})
+            }
+            "at" => json!({"type": "at", "data": {"qq": attr("id"), "name": attr("name")}}),
+            "img" | "image" => json!({"type": "image", "data": {"url": attr("src")}}),
+            "audio" => json!({"type": "record", "data": {"url": attr("src")}}),
+            "video" => json!({"type": "video", "data": {"url": attr("src"), "thumb": attr("poster")}}),
+            "file" => json!({"type": "file", "data": {"url": attr("src"), "name": attr("title")}}),
+            "face" => json!({"type": "face", "data": {"id": attr("id")}}),
+            "br" => text_segment("\n"),
+            "quote" => {
+                // The quoted message is rendered inside the element; drop it
+                if !self_closing {
+                    rest = rest.find("</quote>").map(|i| &rest[i + "</quote>".len()..]).unwrap_or("");
+                }
+                json!({"type": "reply", "data": {"id": attr("id")}})
+            }
+            // Styling elements (b, i, p, a, ...) only wrap text
+            _ => continue,
+        };
+        segments.push(segment);
+    }
+
+    if !rest.is_empty() {
+        segments.push(text_segment(&unescape(rest)));
+    }
+
+    segments
+}
+
+fn parse_attrs(input: &str) -> Map<String, Value> {
+    let mut attrs = Map::new();
+    let mut rest = input.trim();
+
+    while !rest.is_empty() {
+        let key_end = rest.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        rest = rest[key_end..].trim_start();
+
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let quote = after_eq.chars().next().filter(|c| *c == '"' || *c == '\'');
+            let (value, remaining) = match quote {
+                Some(q) => {
+                    let body = &after_eq[1..];
+                    let end = body.find(q).unwrap_or(body.len());
+                    (&body[..end], body.get(end + 1..).unwrap_or(""))
+                }
+                None => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            attrs.insert(key.to_string(), Value::String(unescape(value)));
+            rest = remaining.trim_start();
+        } else if !key.is_empty() {
+            attrs.insert(key.to_string(), Value::Bool(true));
+        } else {
+            break;
+        }
+    }
+
+    attrs
+}
+
+/// Escape text for use in Satori content
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+/// Convert an outbound message into Satori content
+pub fn message_to_content(message: &Message) -> String {
+    match message {
+        Message::Text { content } => escape(content),
+        Message::Image { url, caption } => {
+            let mut content = format!("<img src=\"{}\"/>", escape(url));
+            if let Some(caption) = caption {
+                content.push_str(&escape(caption));
+            }
+            content
+        }
+        Message::Voice { url, .. } => format!("<audio src=\"{}\"/>", escape(url)),
+        Message::Video { url, cover_url, .. } => match cover_url {
+            Some(cover) => format!("<video src=\"{}\" poster=\"{}\"/>", escape(url), escape(cover)),
+            None => format!("<video src=\"{}\"/>", escape(url)),
+        },
+        Message::Sticker { sticker_id } => format!("<face id=\"{}\"/>", escape(sticker_id)),
+        Message::Chain { segments } => segments.iter().map(segment_to_content).collect(),
+    }
+}
+
+/// Convert an outbound segment into a Satori message element
+pub fn segment_to_content(segment: &Segment) -> String {
+    match segment {
+        Segment::Text { text } => escape(text),
+        Segment::At { user_id } if user_id == "all" => "<at type=\"all\"/>".to_string(),
+        Segment::At { user_id } => format!("<at id=\"{}\"/>", escape(user_id)),
+        Segment::Reply { message_id } => format!("<quote id=\"{}\"/>", escape(message_id)),
+        Segment::Image { url } => format!("<img src=\"{}\"/>", escape(url)),
+        Segment::Voice { url } => format!("<audio src=\"{}\"/>", escape(url)),
+        Segment::Video { url } => format!("<video src=\"{}\"/>", escape(url)),
+        Segment::File { url, name } => format!("<file src=\"{}\" title=\"{}\"/>", escape(url), escape(name)),
+        Segment::Markdown { content } => escape(content),
+        Segment::Face { id } => format!("<face id=\"{}\"/>", escape(id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_content() {
+        let segments = parse_content(
+            "<quote id=\"m0\"><author id=\"2\"/>old</quote>hi <at id=\"1\" name=\"bob\"/> &lt;3<b>!</b><img src='http://x/a.png?a=1&amp;b=2'/>",
+        );
+
+        assert_eq!(segments.len(), 6);
+        assert_eq!(segments[0], json!({"type": "reply", "data": {"id": "m0"}}));
+        assert_eq!(segments[1]["data"]["text"], "hi ");
+        assert_eq!(segments[2]["data"]["qq"], "1");
+        assert_eq!(segments[3]["data"]["text"], " <3");
+        assert_eq!(segments[4]["data"]["text"], "!");
+        assert_eq!(segments[5]["data"]["url"], "http://x/a.png?a=1&b=2");
+    }
+
+    #[test]
+    fn test_parse_attrs() {
+        let attrs = parse_attrs("type=\"all\" silent id=42");
+        assert_eq!(attrs["type"], "all");
+        assert_eq!(attrs["silent"], true);
+        assert_eq!(attrs["id"], "42");
+    }
+
+    #[test]
+    fn test_message_to_content() {
+        let content = message_to_content(&Message::Image {
+            url: "http://x/a.png".to_string(),
+            caption: Some("a<b".to_string()),
+        });
+        assert_eq!(content, "<img src=\"http://x/a.png\"/>a&lt;b");
+
+        let chain = Message::builder().reply("m1").at("1").text(" ok").file("http://x/f.txt", "f.txt").build();
+        assert_eq!(
+            message_to_content(&chain),
+            "<quote id=\"m1\"/><at id=\"1\"/> ok<file src=\"http://x/f.txt\" title=\"f.txt\"/>"
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! Segment-based outbound messages
+//!
+//! A `Message::Chain` carries an ordered list of segments, so one outbound
+//! message can quote a message, mention users and mix text with media.
+//! Adapters render each segment into their platform's message format.
+
+use crate::adapters::Message;
+use crate::events::{Event, MessageEvent, MessageSegment};
+use serde::{Deserialize, Serialize};
+
+/// A single part of an outbound message chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Segment {
+    /// Plain text
+    Text {
+        /// Text content
+        text: String,
+    },
+    /// Mention (@) a user
+    At {
+        /// Mentioned user ID
+        user_id: String,
+    },
+    /// Quote a message
+    Reply {
+        /// Quoted message ID
+        message_id: String,
+    },
+    /// Image
+    Image {
+        /// Image URL or data
+        url: String,
+    },
+    /// Voice
+    Voice {
+        /// Voice URL or data
+        url: String,
+    },
+    /// Video
+    Video {
+        /// Video URL or data
+        url: String,
+    },
+    /// File
+    File {
+        /// File URL or data
+        url: String,
+        /// File name
+        name: String,
+    },
+    /// Markdown content
+    Markdown {
+        /// Markdown source
+        content: String,
+    },
+    /// Platform face / emoji
+    Face {
+        /// Face ID
+        id: String,
+    },
+}
+
+impl Segment {
+    /// Create a text segment
+    pub fn text(text: impl Into<String>) -> Self {
+        Segment::Text { text: text.into() }
+    }
+
+    /// Get the text of a text segment
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Segment::Text { text } => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Convert the content of an inbound message into segments
+    ///
+    /// Content without an outbound counterpart (forwards, locations) is
+    /// kept as text.
+    pub fn from_event(event: &MessageEvent) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let push_text = |segments: &mut Vec<Segment>, text: &str| {
+            if !text.is_empty() {
+                segments.push(Segment::text(text));
+            }
+        };
+
+        match event {
+            MessageEvent::Text { text, .. } => push_text(&mut segments, text),
+            MessageEvent::Image { url, caption, .. } => {
+                segments.push(Segment::Image { url: url.clone() });
+                push_text(&mut segments, caption.as_deref().unwrap_or_default());
+            }
+            MessageEvent::Voice { url, .. } => segments.push(Segment::Voice { url: url.clone() }),
+            MessageEvent::Video { url, .. } => segments.push(Segment::Video { url: url.clone() }),
+            MessageEvent::At { text, at_list, .. } => {
+                segments.extend(at_list.iter().map(|user_id| Segment::At { user_id: user_id.clone() }));
+                push_text(&mut segments, text);
+            }
+            MessageEvent::Reply { reply_to, text, .. } => {
+                segments.push(Segment::Reply { message_id: reply_to.clone() });
+                push_text(&mut segments, text);
+            }
+            MessageEvent::Forward { text, .. } => push_text(&mut segments, text.as_deref().unwrap_or_default()),
+            MessageEvent::File { url, name, .. } => segments.push(Segment::File {
+                url: url.clone(),
+                name: name.clone(),
+            }),
+            MessageEvent::Location { latitude, longitude, address, .. } => {
+                push_text(&mut segments, &location_text(*latitude, *longitude, address.as_deref()));
+            }
+            MessageEvent::Sticker { sticker_id, .. } => segments.push(Segment::Face { id: sticker_id.clone() }),
+            MessageEvent::Markdown { content, .. } => segments.push(Segment::Markdown { content: content.clone() }),
+            MessageEvent::Chain { segments: chain, .. } => segments.extend(chain.iter().filter_map(Segment::from_segment)),
+        }
+        segments
+    }
+
+    /// Convert an inbound message segment
+    ///
+    /// Returns `None` for empty text.
+    pub fn from_segment(segment: &MessageSegment) -> Option<Segment> {
+        let segment = match segment {
+            MessageSegment::Text { text } if text.is_empty() => return None,
+            MessageSegment::Text { text } => Segment::text(text.as_str()),
+            MessageSegment::At { user_id } => Segment::At { user_id: user_id.clone() },
+            MessageSegment::Reply { message_id } => Segment::Reply { message_id: message_id.clone() },
+            MessageSegment::Image { url } => Segment::Image { url: url.clone() },
+            MessageSegment::Voice { url, .. } => Segment::Voice { url: url.clone() },
+            MessageSegment::Video { url, .. } => Segment::Video { url: url.clone() },
+            MessageSegment::File { url, name, .. } => Segment::File {
+                url: url.clone(),
+                name: name.clone(),
+            },
+            MessageSegment::Location { latitude, longitude, address } => {
+                Segment::text(location_text(*latitude, *longitude, address.as_deref()))
+            }
+            MessageSegment::Forward { .. } => return None,
+            MessageSegment::Sticker { sticker_id } => Segment::Face { id: sticker_id.clone() },
+            MessageSegment::Markdown { content } => Segment::Markdown { content: content.clone() },
+        };
+        Some(segment)
+    }
+}
+
+/// Render a location as text
+fn location_text(latitude: f64, longitude: f64, address: Option<&str>) -> String {
+    match address {
+        Some(address) => format!("{} ({}, {})", address, latitude, longitude),
+        None => format!("({}, {})", latitude, longitude),
+    }
+}
+
+/// Builder for `Message::Chain`
+///
+/// ```ignore
+/// let reply = MessageBuilder::new().reply_to(&event).text("pong").build();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageBuilder {
+    segments: Vec<Segment>,
+}
+
+impl MessageBuilder {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a segment
+    pub fn segment(mut self, segment: Segment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Append text, merging with a preceding text segment
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        let text = text.into();
+        match self.segments.last_mut() {
+            Some(Segment::Text { text: last }) => last.push_str(&text),
+            _ => self.segments.push(Segment::Text { text }),
+        }
+        self
+    }
+
+    /// Mention a user
+    pub fn at(self, user_id: impl Into<String>) -> Self {
+        self.segment(Segment::At { user_id: user_id.into() })
+    }
+
+    /// Quote a message
+    pub fn reply(self, message_id: impl Into<String>) -> Self {
+        self.segment(Segment::Reply { message_id: message_id.into() })
+    }
+
+    /// Append an image
+    pub fn image(self, url: impl Into<String>) -> Self {
+        self.segment(Segment::Image { url: url.into() })
+    }
+
+    /// Append a file
+    pub fn file(self, url: impl Into<String>, name: impl Into<String>) -> Self {
+        self.segment(Segment::File {
+            url: url.into(),
+            name: name.into(),
+        })
+    }
+
+    /// Append markdown content
+    pub fn markdown(self, content: impl Into<String>) -> Self {
+        self.segment(Segment::Markdown { content: content.into() })
+    }
+
+    /// Append a face / emoji
+    pub fn face(self, id: impl Into<String>) -> Self {
+        self.segment(Segment::Face { id: id.into() })
+    }
+
+    /// Quote an inbound message and mention its sender
+    ///
+    /// The quote is only added if the event carries a platform message ID.
+    pub fn reply_to(mut self, event: &MessageEvent) -> Self {
+        if let Some(message_id) = event.message_id() {
+            self = self.reply(message_id);
+        }
+        if let Some(user_id) = event.user_id() {
+            self = self.at(user_id).text(" ");
+        }
+        self
+    }
+
+    /// Append the content of an inbound message
+    pub fn quote_content(mut self, event: &MessageEvent) -> Self {
+        self.segments.extend(Segment::from_event(event));
+        self
+    }
+
+    /// Get the segments built so far
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Build the message
+    pub fn build(self) -> Message {
+        Message::Chain { segments: self.segments }
+    }
+}
+
+impl From<Vec<Segment>> for Message {
+    fn from(segments: Vec<Segment>) -> Self {
+        Message::Chain { segments }
+    }
+}
+
+impl From<&MessageEvent> for Message {
+    fn from(event: &MessageEvent) -> Self {
+        Message::Chain {
+            segments: Segment::from_event(event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventMetadata;
+
+    #[test]
+    fn test_reply_and_mention_sender() {
+        let event = MessageEvent::Text {
+            text: "ping".to_string(),
+            metadata: EventMetadata::new("message.text")
+                .with_user_id("u1")
+                .with_extra("message_id", "m42"),
+        };
+
+        let message = MessageBuilder::new().reply_to(&event).text("pong").image("http://x/a.png").build();
+        assert_eq!(
+            message.segments(),
+            vec![
+                Segment::Reply { message_id: "m42".to_string() },
+                Segment::At { user_id: "u1".to_string() },
+                Segment::text(" pong"),
+                Segment::Image { url: "http://x/a.png".to_string() },
+            ]
+        );
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["type"], "chain");
+        assert_eq!(json["segments"][1], serde_json::json!({"type": "at", "user_id": "u1"}));
+        assert_eq!(serde_json::from_value::<Message>(json).unwrap(), message);
+    }
+
+    #[test]
+    fn test_segments_from_event() {
+        let metadata = EventMetadata::new("message.at");
+        let event = MessageEvent::At {
+            text: "hi".to_string(),
+            at_list: vec!["u1".to_string(), "u2".to_string()],
+            metadata,
+        };
+        let segments = Segment::from_event(&event);
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[2].as_text(), Some("hi"));
+
+        let event = MessageEvent::Image {
+            url: "http://x/a.png".to_string(),
+            caption: None,
+            metadata: EventMetadata::new("message.image"),
+        };
+        assert_eq!(Message::from(&event).segments(), vec![Segment::Image { url: "http://x/a.png".to_string() }]);
+
+        let image = Message::Image { url: "u".to_string(), caption: Some("c".to_string()) };
+        assert_eq!(image.segments(), vec![Segment::Image { url: "u".to_string() }, Segment::text("c")]);
+    }
+}
@@ -0,0 +1,473 @@
+//! Adapter connection supervisor
+//!
+//! Watches every adapter of an `AdapterManager`, reconnects adapters that
+//! report an error or miss their heartbeats with exponential backoff, and
+//! publishes `MetaEvent::ConnectionChange` events for each step.
+
+use crate::adapters::config::{ConnectionConfig, HeartbeatConfig, RetryConfig};
+use crate::adapters::state_manager::{AdapterStateManager, StateTransition};
+use crate::adapters::{Adapter, AdapterManager, AdapterStatus, DEFAULT_EVENT_CHANNEL_CAPACITY};
+use crate::events::{ConnectionStatus, EventEnum, EventMetadata, EventSource, MetaEvent};
+use crate::logging::traits::{LogContext, LogLevel, Logger};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+/// Default interval between adapter checks
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Reconnection policy of a single adapter
+#[derive(Debug, Clone)]
+struct ReconnectPolicy {
+    retry: RetryConfig,
+    max_attempts: u32,
+    heartbeat: Option<HeartbeatConfig>,
+    conn_type: String,
+}
+
+impl ReconnectPolicy {
+    /// Build the policy from adapter config
+    ///
+    /// Attempts are bounded by both `retry.max_attempts` and
+    /// `connection.max_reconnect`.
+    fn new(connection: &ConnectionConfig, retry: Option<RetryConfig>, heartbeat: Option<HeartbeatConfig>) -> Self {
+        let max_attempts = match &retry {
+            Some(retry) => retry.max_attempts.min(connection.max_reconnect),
+            None => connection.max_reconnect,
+        };
+        Self {
+            retry: retry.unwrap_or_default(),
+            max_attempts,
+            heartbeat: heartbeat.filter(|h| h.enabled && h.interval > 0),
+            conn_type: connection.conn_type.clone(),
+        }
+    }
+}
+
+/// Supervises adapter connections
+pub struct AdapterSupervisor {
+    manager: Arc<AdapterManager>,
+    states: Arc<RwLock<HashMap<String, AdapterStateManager>>>,
+    event_sender: broadcast::Sender<EventEnum>,
+    check_interval: Duration,
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+    logger: Arc<dyn Logger>,
+}
+
+impl std::fmt::Debug for AdapterSupervisor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdapterSupervisor")
+            .field("check_interval", &self.check_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AdapterSupervisor {
+    /// Create a new supervisor
+    pub fn new(manager: Arc<AdapterManager>, logger: Arc<dyn Logger>) -> Self {
+        let (event_sender, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
+        Self {
+            manager,
+            states: Arc::new(RwLock::new(HashMap::new())),
+            event_sender,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+            tasks: Mutex::new(HashMap::new()),
+            logger,
+        }
+    }
+
+    /// Set the interval between adapter checks
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Subscribe to connection change events
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.event_sender.subscribe()
+    }
+
+    /// Get the state manager of a supervised adapter
+    pub async fn state_manager(&self, adapter_id: &str) -> Option<AdapterStateManager> {
+        self.states.read().await.get(adapter_id).cloned()
+    }
+
+    /// Get the state transition history of a supervised adapter
+    pub async fn get_history(&self, adapter_id: &str) -> Vec<StateTransition> {
+        match self.state_manager(adapter_id).await {
+            Some(state) => state.get_history().await,
+            None => Vec::new(),
+        }
+    }
+
+    /// Start supervising every adapter of the manager
+    ///
+    /// Adapters that are already supervised are skipped; returns the number
+    /// of newly supervised adapters.
+    pub async fn supervise_all(&self) -> usize {
+        let mut count = 0;
+        for adapter in self.manager.list_adapters().await {
+            if self.supervise(adapter).await {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Start supervising an adapter
+    ///
+    /// Returns false if the adapter is already supervised.
+    pub async fn supervise(&self, adapter: Arc<dyn Adapter>) -> bool {
+        let adapter_id = adapter.adapter_id().to_string();
+        let mut tasks = self.tasks.lock().await;
+        if tasks.get(&adapter_id).is_some_and(|task| !task.is_finished()) {
+            return false;
+        }
+
+        let state = AdapterStateManager::new(adapter_id.clone(), self.logger.clone());
+        state.set_state(adapter.status(), "Supervision started").await;
+        self.states.write().await.insert(adapter_id.clone(), state.clone());
+
+        let config = adapter.config();
+        let watcher = AdapterWatcher {
+            policy: ReconnectPolicy::new(&config.connection, config.retry, config.heartbeat),
+            adapter,
+            manager: self.manager.clone(),
+            state,
+            event_sender: self.event_sender.clone(),
+            logger: self.logger.clone(),
+        };
+        tasks.insert(adapter_id, tokio::spawn(watcher.run(self.check_interval)));
+        true
+    }
+
+    /// Stop supervising all adapters
+    pub async fn stop(&self) -> crate::errors::Result<()> {
+        for (_, task) in self.tasks.lock().await.drain() {
+            task.abort();
+        }
+        Ok(())
+    }
+}
+
+/// Watch loop of a single adapter
+struct AdapterWatcher {
+    adapter: Arc<dyn Adapter>,
+    manager: Arc<AdapterManager>,
+    state: AdapterStateManager,
+    policy: ReconnectPolicy,
+    event_sender: broadcast::Sender<EventEnum>,
+    logger: Arc<dyn Logger>,
+}
+
+impl AdapterWatcher {
+    async fn run(self, check_interval: Duration) {
+        let mut events = Some(self.adapter.subscribe());
+        let mut ticker = tokio::time::interval(check_interval);
+        let mut last_seen = Instant::now();
+        let mut gave_up = false;
+
+        loop {
+            tokio::select! {
+                received = next_event(&mut events) => match received {
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => last_seen = Instant::now(),
+                    Err(broadcast::error::RecvError::Closed) => events = None,
+                },
+                _ = ticker.tick() => {
+                    // The adapter was unloaded or replaced by a reload
+                    let current = self.manager.get_adapter(self.adapter.adapter_id()).await;
+                    if !current.is_some_and(|current| Arc::ptr_eq(&current, &self.adapter)) {
+                        break;
+                    }
+
+                    let status = self.adapter.status();
+                    if status.is_active() {
+                        gave_up = false;
+                    }
+                    self.state.set_state(status.clone(), "Observed adapter status").await;
+
+                    let failure = match &status {
+                        AdapterStatus::Error(e) if !gave_up => Some(e.clone()),
+                        AdapterStatus::Running => self.policy.heartbeat.as_ref()
+                            .filter(|heartbeat| last_seen.elapsed() > heartbeat.timeout_duration())
+                            .map(|_| "Missed heartbeats".to_string()),
+                        _ => None,
+                    };
+
+                    if let Some(reason) = failure {
+                        self.state.set_state(AdapterStatus::Error(reason.clone()), &reason).await;
+                        gave_up = !self.reconnect(&reason).await;
+                        last_seen = Instant::now();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Restart the adapter with exponential backoff
+    ///
+    /// Returns false once the attempts are exhausted.
+    async fn reconnect(&self, reason: &str) -> bool {
+        self.log(LogLevel::Warn, &format!("Connection lost: {}", reason));
+        self.emit(ConnectionStatus::Disconnected, None, Some(reason.to_string()));
+
+        let mut last_error = reason.to_string();
+        for attempt in 1..=self.policy.max_attempts {
+            self.emit(ConnectionStatus::Reconnecting, Some(attempt), None);
+            tokio::time::sleep(self.policy.retry.delay(attempt)).await;
+            self.state.set_state(AdapterStatus::Initializing, &format!("Reconnect attempt {}", attempt)).await;
+
+            let _ = self.adapter.stop().await;
+            let result = self.adapter.start().await;
+            let status = self.adapter.status();
+            match result {
+                Ok(()) if !status.is_error() => {
+                    self.state.set_state(status, &format!("Reconnected after {} attempts", attempt)).await;
+                    self.emit(ConnectionStatus::Connected, Some(attempt), None);
+                    self.log(LogLevel::Info, &format!("Reconnected after {} attempts", attempt));
+                    return true;
+                }
+                Ok(()) => last_error = status.error_message().unwrap_or_default().to_string(),
+                Err(e) => last_error = e.to_string(),
+            }
+        }
+
+        let message = format!("Reconnect failed after {} attempts: {}", self.policy.max_attempts, last_error);
+        self.state.set_state(AdapterStatus::Error(message.clone()), "Reconnect attempts exhausted").await;
+        self.emit(ConnectionStatus::Failed, Some(self.policy.max_attempts), Some(last_error));
+        self.log(LogLevel::Error, &message);
+        false
+    }
+
+    fn emit(&self, status: ConnectionStatus, reconnect_count: Option<u32>, error: Option<String>) {
+        let metadata = EventMetadata::new("adapter.connection")
+            .with_source(EventSource::System)
+            .with_adapter_id(self.adapter.adapter_id());
+        let _ = self.event_sender.send(EventEnum::Meta(MetaEvent::ConnectionChange {
+            status,
+            conn_type: Some(self.policy.conn_type.clone()),
+            reconnect_count,
+            error,
+            metadata,
+        }));
+    }
+
+    fn log(&self, level: LogLevel, message: &str) {
+        let mut context = LogContext::new().with_component("AdapterSupervisor");
+        context.add("adapter_id", self.adapter.adapter_id().to_string());
+        self.logger.log(level, &format!("Adapter {}: {}", self.adapter.adapter_id(), message), &context);
+    }
+}
+
+/// Receive the next adapter event, pending forever once the channel closed
+async fn next_event(
+    events: &mut Option<broadcast::Receiver<EventEnum>>,
+) -> Result<EventEnum, broadcast::error::RecvError> {
+    match events {
+        Some(events) => events.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::types::AdapterStatistics;
+    use crate::adapters::{AdapterConfig, AdapterManagerConfig, Message, Target};
+    use crate::errors::{AdapterError, Result};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn create_test_logger() -> Arc<dyn crate::logging::Logger> {
+        let formatter = Arc::new(crate::logging::formatters::JsonFormatter::new());
+        let writer = Arc::new(crate::logging::writers::ConsoleWriter::new());
+        Arc::new(crate::logging::StructuredLogger::new(formatter, writer))
+    }
+
+    /// Adapter whose first `failures` starts fail
+    #[derive(Debug)]
+    struct FlakyAdapter {
+        config: AdapterConfig,
+        status: std::sync::RwLock<AdapterStatus>,
+        failures: AtomicU32,
+        starts: AtomicU32,
+        events: broadcast::Sender<EventEnum>,
+    }
+
+    impl FlakyAdapter {
+        fn new(config: AdapterConfig, failures: u32) -> Self {
+            let (events, _) = broadcast::channel(16);
+            Self {
+                config,
+                status: std::sync::RwLock::new(AdapterStatus::Running),
+                failures: AtomicU32::new(failures),
+                starts: AtomicU32::new(0),
+                events,
+            }
+        }
+
+        fn set_status(&self, status: AdapterStatus) {
+            *self.status.write().unwrap() = status;
+        }
+    }
+
+    #[async_trait]
+    impl Adapter for FlakyAdapter {
+        fn name(&self) -> &str {
+            "FlakyAdapter"
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn adapter_id(&self) -> &str {
+            &self.config.adapter_id
+        }
+
+        fn config(&self) -> AdapterConfig {
+            self.config.clone()
+        }
+
+        fn status(&self) -> AdapterStatus {
+            self.status.read().unwrap().clone()
+        }
+
+        fn statistics(&self) -> AdapterStatistics {
+            AdapterStatistics::default()
+        }
+
+        async fn start(&self) -> Result<()> {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                self.set_status(AdapterStatus::Error("connection refused".to_string()));
+                return Err(AdapterError::ConnectionFailed("connection refused".to_string()).into());
+            }
+            self.set_status(AdapterStatus::Running);
+            Ok(())
+        }
+
+        async fn stop(&self) -> Result<()> {
+            self.set_status(AdapterStatus::Stopped);
+            Ok(())
+        }
+
+        async fn send(&self, _target: Target, _message: Message) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+            self.events.subscribe()
+        }
+    }
+
+    fn fast_retry(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            initial_delay: 1,
+            max_delay: 5,
+            backoff_multiplier: 2.0,
+        }
+    }
+
+    async fn next_connection_change(events: &mut broadcast::Receiver<EventEnum>) -> (ConnectionStatus, Option<u32>) {
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap();
+        match event {
+            EventEnum::Meta(MetaEvent::ConnectionChange { status, reconnect_count, .. }) => (status, reconnect_count),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    async fn setup(adapter: FlakyAdapter) -> (Arc<AdapterManager>, Arc<dyn Adapter>, AdapterSupervisor) {
+        let manager = Arc::new(AdapterManager::new(AdapterManagerConfig::default(), create_test_logger()));
+        let adapter_id = adapter.adapter_id().to_string();
+        manager.add_adapter(Box::new(adapter)).await.unwrap();
+        let adapter = manager.get_adapter(&adapter_id).await.unwrap();
+        let supervisor = AdapterSupervisor::new(manager.clone(), create_test_logger())
+            .with_check_interval(Duration::from_millis(10));
+        (manager, adapter, supervisor)
+    }
+
+    #[test]
+    fn test_reconnect_policy() {
+        let connection = AdapterConfig::new("flaky", "flaky-0", "ws://x").connection;
+        assert_eq!(ReconnectPolicy::new(&connection, None, None).max_attempts, connection.max_reconnect);
+        assert_eq!(ReconnectPolicy::new(&connection, Some(fast_retry(2)), None).max_attempts, 2);
+
+        let heartbeat = HeartbeatConfig { interval: 1, timeout: None, enabled: false };
+        assert!(ReconnectPolicy::new(&connection, None, Some(heartbeat)).heartbeat.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_supervisor_reconnects_with_backoff() {
+        let config = AdapterConfig::new("flaky", "flaky-1", "ws://x").with_retry(fast_retry(5));
+        let (_manager, adapter, supervisor) = setup(FlakyAdapter::new(config, 2)).await;
+        let mut events = supervisor.subscribe();
+
+        assert_eq!(supervisor.supervise_all().await, 1);
+        assert_eq!(supervisor.supervise_all().await, 0);
+
+        let _ = adapter.stop().await;
+        let _ = adapter.start().await; // first failure puts the adapter in Error
+
+        assert_eq!(next_connection_change(&mut events).await, (ConnectionStatus::Disconnected, None));
+        assert_eq!(next_connection_change(&mut events).await, (ConnectionStatus::Reconnecting, Some(1)));
+        assert_eq!(next_connection_change(&mut events).await, (ConnectionStatus::Reconnecting, Some(2)));
+        assert_eq!(next_connection_change(&mut events).await, (ConnectionStatus::Connected, Some(2)));
+        assert_eq!(adapter.status(), AdapterStatus::Running);
+
+        let history = supervisor.get_history("flaky-1").await;
+        assert!(history.iter().any(|t| t.to.is_error()));
+        assert_eq!(history.last().unwrap().to, AdapterStatus::Running);
+        assert!(supervisor.get_history("missing").await.is_empty());
+
+        supervisor.stop().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_supervisor_gives_up() {
+        let config = AdapterConfig::new("flaky", "flaky-2", "ws://x").with_retry(fast_retry(2));
+        let (_manager, adapter, supervisor) = setup(FlakyAdapter::new(config, 10)).await;
+        let mut events = supervisor.subscribe();
+        supervisor.supervise_all().await;
+
+        let _ = adapter.start().await;
+
+        assert_eq!(next_connection_change(&mut events).await.0, ConnectionStatus::Disconnected);
+        assert_eq!(next_connection_change(&mut events).await.0, ConnectionStatus::Reconnecting);
+        assert_eq!(next_connection_change(&mut events).await.0, ConnectionStatus::Reconnecting);
+        assert_eq!(next_connection_change(&mut events).await, (ConnectionStatus::Failed, Some(2)));
+
+        // No further attempts until the adapter recovers
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(events.try_recv().is_err());
+
+        let state = supervisor.state_manager("flaky-2").await.unwrap();
+        assert!(state.get_state().await.is_error());
+        supervisor.stop().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_supervisor_detects_missed_heartbeats() {
+        let heartbeat = HeartbeatConfig { interval: 1, timeout: Some(0), enabled: true };
+        let config = AdapterConfig::new("flaky", "flaky-3", "ws://x")
+            .with_retry(fast_retry(3))
+            .with_heartbeat(heartbeat);
+        let (_manager, _adapter, supervisor) = setup(FlakyAdapter::new(config, 0)).await;
+        let mut events = supervisor.subscribe();
+        supervisor.supervise_all().await;
+
+        let (status, _) = next_connection_change(&mut events).await;
+        assert_eq!(status, ConnectionStatus::Disconnected);
+        assert_eq!(next_connection_change(&mut events).await, (ConnectionStatus::Reconnecting, Some(1)));
+        assert_eq!(next_connection_change(&mut events).await, (ConnectionStatus::Connected, Some(1)));
+
+        let history = supervisor.get_history("flaky-3").await;
+        assert!(history.iter().any(|t| t.reason == "Missed heartbeats"));
+        supervisor.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,460 @@
+//! Telegram adapter - long polling and webhook
+
+use async_trait::async_trait;
+use crate::adapters::converter::ConversionContext;
+use crate::adapters::onebot11::adapter::bind_address;
+use crate::adapters::onebot11::types::value_to_id;
+use crate::adapters::telegram::client::BotApiClient;
+use crate::adapters::telegram::converter::TelegramConverter;
+use crate::adapters::telegram::server::{webhook_router, UpdateDispatcher, WebhookState, WEBHOOK_PATH};
+use crate::adapters::telegram::types::{
+    bot_id_of, chat_id_of, send_request, ALLOWED_UPDATES, DEFAULT_API_URL,
+};
+use crate::adapters::{
+    Adapter, AdapterConfig, AdapterStatus, Message, Target,
+    DEFAULT_EVENT_CHANNEL_CAPACITY,
+    types::AdapterStatistics,
+};
+use crate::errors::{AdapterError, LoquatError, Result};
+use crate::events::EventEnum;
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+/// Default `getUpdates` long polling timeout in seconds
+const DEFAULT_POLL_TIMEOUT: u64 = 30;
+
+/// Telegram update delivery mode, selected by `connection.conn_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelegramMode {
+    /// Poll `getUpdates` (`polling`)
+    LongPolling,
+    /// Receive updates on a webhook listener bound to `connection.url` (`webhook`)
+    Webhook,
+}
+
+impl TelegramMode {
+    /// Parse a connection type
+    pub fn from_conn_type(conn_type: &str) -> Option<Self> {
+        match conn_type.to_lowercase().as_str() {
+            "polling" | "long_polling" | "getupdates" => Some(Self::LongPolling),
+            "webhook" | "http" => Some(Self::Webhook),
+            _ => None,
+        }
+    }
+}
+
+/// Telegram Bot API adapter implementation
+///
+/// Platform configuration: `token` (required), `api_url` (defaults to
+/// `https://api.telegram.org`), `poll_timeout`, and for webhook mode
+/// `webhook_path`, `secret_token` and `webhook_url` (registered with
+/// `setWebhook` on start when set).
+#[derive(Debug)]
+pub struct TelegramAdapter {
+    config: AdapterConfig,
+    mode: TelegramMode,
+    client: BotApiClient,
+    dispatcher: UpdateDispatcher,
+    status: Arc<RwLock<AdapterStatus>>,
+    statistics: Arc<RwLock<AdapterStatistics>>,
+    bot_info: RwLock<Option<Value>>,
+    local_addr: RwLock<Option<SocketAddr>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl TelegramAdapter {
+    /// Create a new Telegram adapter
+    pub fn new(config: AdapterConfig) -> Self {
+        let (event_sender, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
+        let statistics = Arc::new(RwLock::new(AdapterStatistics::default()));
+        let token = config.platform["token"].as_str().unwrap_or_default();
+        let self_id = config.platform["self_id"]
+            .as_str()
+            .or_else(|| bot_id_of(token))
+            .unwrap_or_default();
+        let converter = TelegramConverter::new(ConversionContext::new(
+            &config.adapter_id,
+            "telegram",
+            self_id,
+        ));
+        let api_url = config.platform["api_url"].as_str().unwrap_or(DEFAULT_API_URL);
+        let client = BotApiClient::new(api_url, token);
+        let dispatcher = UpdateDispatcher::new(converter, event_sender, Arc::clone(&statistics));
+        let mode = TelegramMode::from_conn_type(&config.connection.conn_type)
+            .unwrap_or(TelegramMode::LongPolling);
+
+        Self {
+            config,
+            mode,
+            client,
+            dispatcher,
+            status: Arc::new(RwLock::new(AdapterStatus::Ready)),
+            statistics,
+            bot_info: RwLock::new(None),
+            local_addr: RwLock::new(None),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get the update delivery mode
+    pub fn mode(&self) -> TelegramMode {
+        self.mode
+    }
+
+    /// Get the bot's `getMe` result (after start)
+    pub async fn bot_info(&self) -> Option<Value> {
+        self.bot_info.read().await.clone()
+    }
+
+    /// Get the bound webhook listener address (webhook mode, after start)
+    pub async fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.read().await
+    }
+
+    /// Call a raw Bot API method and return its result
+    pub async fn call_method(&self, method: &str, params: Value) -> Result<Value> {
+        match self.client.call(method, &params, self.request_timeout()).await {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                self.statistics.write().await.errors += 1;
+                Err(e)
+            }
+        }
+    }
+
+    /// Resolve a `file_id` into a download URL via `getFile`
+    pub async fn file_url(&self, file_id: &str) -> Result<String> {
+        let file = self.call_method("getFile", json!({"file_id": file_id})).await?;
+        let file_path = file["file_path"].as_str().ok_or_else(|| {
+            AdapterError::ConversionFailed(format!("file {} has no file_path", file_id))
+        })?;
+        Ok(self.client.file_url(file_path))
+    }
+
+    fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.config.connection.timeout.max(1))
+    }
+
+    fn poll_timeout(&self) -> u64 {
+        self.config.platform["poll_timeout"].as_u64().unwrap_or(DEFAULT_POLL_TIMEOUT)
+    }
+
+    /// Delay before polling again after a failed `getUpdates`
+    fn retry_delay(&self) -> Duration {
+        let millis = self.config.retry.as_ref().map(|r| r.initial_delay).unwrap_or(1000);
+        Duration::from_millis(millis)
+    }
+
+    /// Spawn the `getUpdates` loop
+    fn spawn_polling(&self) -> JoinHandle<()> {
+        let client = self.client.clone();
+        let dispatcher = self.dispatcher.clone();
+        let statistics = Arc::clone(&self.statistics);
+        let poll_timeout = self.poll_timeout();
+        let request_timeout = Duration::from_secs(poll_timeout) + self.request_timeout();
+        let retry_delay = self.retry_delay();
+
+        tokio::spawn(async move {
+            let mut offset: Option<i64> = None;
+            loop {
+                let params = json!({
+                    "offset": offset,
+                    "timeout": poll_timeout,
+                    "allowed_updates": ALLOWED_UPDATES,
+                });
+                let updates = match client.call("getUpdates", &params, request_timeout).await {
+                    Ok(Value::Array(updates)) => updates,
+                    Ok(_) => Vec::new(),
+                    Err(_) => {
+                        statistics.write().await.errors += 1;
+                        tokio::time::sleep(retry_delay).await;
+                        continue;
+                    }
+                };
+
+                for update in updates {
+                    if let Some(update_id) = update["update_id"].as_i64() {
+                        offset = Some(update_id + 1);
+                    }
+                    let _ = dispatcher.dispatch(update).await;
+                }
+            }
+        })
+    }
+
+    /// Bind the webhook listener and register it when `webhook_url` is set
+    async fn start_webhook(&self) -> Result<()> {
+        let addr = bind_address(&self.config.connection.url);
+        let listener = TcpListener::bind(&addr).await
+            .map_err(|e| AdapterError::ConnectionFailed(format!("Failed to bind to {}: {}", addr, e)))?;
+        *self.local_addr.write().await = listener.local_addr().ok();
+
+        let platform = &self.config.platform;
+        let secret_token = platform["secret_token"].as_str().filter(|t| !t.is_empty());
+        let path = platform["webhook_path"].as_str().unwrap_or(WEBHOOK_PATH);
+        let router = webhook_router(path, WebhookState::new(self.dispatcher.clone(), secret_token));
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        self.tasks.lock().await.push(server);
+
+        if let Some(webhook_url) = platform["webhook_url"].as_str() {
+            let params = json!({
+                "url": webhook_url,
+                "secret_token": secret_token,
+                "allowed_updates": ALLOWED_UPDATES,
+            });
+            self.call_method("setWebhook", params).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Adapter for TelegramAdapter {
+    fn name(&self) -> &str {
+        "TelegramAdapter"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn adapter_id(&self) -> &str {
+        &self.config.adapter_id
+    }
+
+    fn config(&self) -> AdapterConfig {
+        self.config.clone()
+    }
+
+    fn status(&self) -> AdapterStatus {
+        // Use blocking read for synchronous method
+        tokio::task::block_in_place(|| {
+            let guard = tokio::runtime::Handle::current()
+                .block_on(self.status.read());
+            guard.clone()
+        })
+    }
+
+    fn statistics(&self) -> AdapterStatistics {
+        // Use blocking read for synchronous method
+        tokio::task::block_in_place(|| {
+            let guard = tokio::runtime::Handle::current()
+                .block_on(self.statistics.read());
+            guard.clone()
+        })
+    }
+
+    /// Verify the token with `getMe`, then start polling or the webhook listener
+    async fn start(&self) -> Result<()> {
+        if *self.status.read().await == AdapterStatus::Running {
+            return Err(LoquatError::Adapter(AdapterError::LoadFailed(
+                "Adapter is already running".to_string()
+            )));
+        }
+        *self.status.write().await = AdapterStatus::Initializing;
+
+        let started = async {
+            let me = self.call_method("getMe", json!({})).await?;
+            *self.bot_info.write().await = Some(me);
+            match self.mode {
+                TelegramMode::LongPolling => {
+                    let poller = self.spawn_polling();
+                    self.tasks.lock().await.push(poller);
+                    Ok(())
+                }
+                TelegramMode::Webhook => self.start_webhook().await,
+            }
+        };
+
+        match started.await {
+            Ok(()) => {
+                *self.status.write().await = AdapterStatus::Running;
+                Ok(())
+            }
+            Err(e) => {
+                for task in self.tasks.lock().await.drain(..) {
+                    task.abort();
+                }
+                *self.status.write().await = AdapterStatus::Error(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// Stop polling / close the webhook listener
+    async fn stop(&self) -> Result<()> {
+        *self.status.write().await = AdapterStatus::Stopped;
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    /// Send a message via `sendMessage` / `sendPhoto` / `sendVoice` / `sendVideo` / `sendSticker`
+    async fn send(&self, target: Target, message: Message) -> Result<String> {
+        let (method, params) = send_request(chat_id_of(&target), &message);
+        let sent = self.call_method(method, params).await?;
+        let message_id = value_to_id(&sent["message_id"]).unwrap_or_default();
+
+        let mut stats = self.statistics.write().await;
+        stats.messages_sent += 1;
+        stats.last_activity = Some(chrono::Utc::now().timestamp());
+        drop(stats);
+
+        Ok(message_id)
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.dispatcher.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::telegram::server::SECRET_TOKEN_HEADER;
+    use axum::{Json, Router, extract::{Path, State}, routing::post};
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    const TOKEN: &str = "123456:test-token";
+
+    /// Stub Bot API: one batch of updates, then empty polls; records the last offset
+    async fn spawn_stub_api(last_offset: Arc<AtomicI64>) -> String {
+        async fn handle(
+            State(last_offset): State<Arc<AtomicI64>>,
+            Path((bot, method)): Path<(String, String)>,
+            Json(params): Json<Value>,
+        ) -> Json<Value> {
+            if bot != format!("bot{}", TOKEN) {
+                return Json(json!({"ok": false, "error_code": 401, "description": "Unauthorized"}));
+            }
+            let result = match method.as_str() {
+                "getMe" => json!({"id": 123456, "is_bot": true, "username": "loquat_bot"}),
+                "getUpdates" => match params["offset"].as_i64() {
+                    None => json!([
+                        {"update_id": 100, "message": {
+                            "message_id": 1, "from": {"id": 42}, "chat": {"id": 42, "type": "private"},
+                            "date": 1700000000, "text": "hello"
+                        }},
+                        {"update_id": 101, "message": {
+                            "message_id": 2, "from": {"id": 42}, "chat": {"id": -1001, "type": "supergroup"},
+                            "photo": [{"file_id": "p-small"}, {"file_id": "p-large"}], "caption": "look"
+                        }}
+                    ]),
+                    Some(offset) => {
+                        last_offset.store(offset, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        json!([])
+                    }
+                },
+                "sendMessage" | "sendPhoto" => json!({"message_id": 900, "chat": {"id": params["chat_id"]}}),
+                _ => return Json(json!({"ok": false, "error_code": 404, "description": "Not Found"})),
+            };
+            Json(json!({"ok": true, "result": result}))
+        }
+
+        let router = Router::new().route("/:bot/:method", post(handle)).with_state(last_offset);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        format!("http://{}", addr)
+    }
+
+    fn telegram_config(adapter_id: &str, conn_type: &str, api_url: &str) -> AdapterConfig {
+        let mut config = AdapterConfig::new("telegram", adapter_id, "127.0.0.1:0")
+            .with_platform_config("token", TOKEN)
+            .unwrap()
+            .with_platform_config("api_url", api_url)
+            .unwrap()
+            .with_platform_config("poll_timeout", 0)
+            .unwrap();
+        config.connection.conn_type = conn_type.to_string();
+        config
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_telegram_long_polling() {
+        let last_offset = Arc::new(AtomicI64::new(0));
+        let api_url = spawn_stub_api(Arc::clone(&last_offset)).await;
+        let adapter = TelegramAdapter::new(telegram_config("telegram-001", "polling", &api_url));
+        let mut events = adapter.subscribe();
+
+        adapter.start().await.unwrap();
+        assert!(adapter.is_running());
+        assert_eq!(adapter.bot_info().await.unwrap()["username"], "loquat_bot");
+
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.event_type(), "message.text");
+        assert_eq!(event.self_id(), Some("123456"));
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.event_type(), "message.image");
+        assert_eq!(event.group_id(), Some("-1001"));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(last_offset.load(Ordering::SeqCst), 102);
+
+        let target = Target::Group { group_id: "-1001".to_string() };
+        let message = Message::Image { url: "http://x/a.png".to_string(), caption: None };
+        assert_eq!(adapter.send(target, message).await.unwrap(), "900");
+
+        let target = Target::User { user_id: "42".to_string() };
+        let message = Message::Sticker { sticker_id: "s1".to_string() };
+        assert!(adapter.send(target, message).await.is_err());
+
+        let stats = adapter.statistics();
+        assert_eq!(stats.events_received, 2);
+        assert_eq!(stats.messages_sent, 1);
+
+        adapter.stop().await.unwrap();
+        assert_eq!(adapter.status(), AdapterStatus::Stopped);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_telegram_webhook() {
+        let api_url = spawn_stub_api(Arc::new(AtomicI64::new(0))).await;
+        let config = telegram_config("telegram-002", "webhook", &api_url)
+            .with_platform_config("secret_token", "s3cret")
+            .unwrap();
+        let adapter = TelegramAdapter::new(config);
+        let mut events = adapter.subscribe();
+        adapter.start().await.unwrap();
+        let url = format!("http://{}{}", adapter.local_addr().await.unwrap(), WEBHOOK_PATH);
+
+        let update = json!({"update_id": 1, "channel_post": {
+            "message_id": 3, "chat": {"id": -1002, "type": "channel"}, "text": "news"
+        }});
+        let client = reqwest::Client::new();
+        let response = client.post(&url).header(SECRET_TOKEN_HEADER, "wrong").json(&update).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+        let response = client.post(&url).header(SECRET_TOKEN_HEADER, "s3cret").json(&update).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.event_type(), "message.text");
+
+        adapter.stop().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_telegram_invalid_token() {
+        let api_url = spawn_stub_api(Arc::new(AtomicI64::new(0))).await;
+        let config = telegram_config("telegram-003", "polling", &api_url)
+            .with_platform_config("token", "999:wrong")
+            .unwrap();
+        let adapter = TelegramAdapter::new(config);
+
+        assert!(adapter.start().await.is_err());
+        assert!(adapter.status().is_error());
+    }
+}
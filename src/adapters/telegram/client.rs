@@ -0,0 +1,63 @@
+//! Telegram Bot API client
+
+use crate::adapters::telegram::types::ApiResponse;
+use crate::errors::{AdapterError, LoquatError, Result};
+use serde_json::Value;
+use std::time::Duration;
+
+/// Client for `{api_url}/bot{token}/{method}` calls
+#[derive(Clone)]
+pub struct BotApiClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: String,
+}
+
+impl std::fmt::Debug for BotApiClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the bot token
+        f.debug_struct("BotApiClient")
+            .field("api_url", &self.api_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BotApiClient {
+    /// Create a new client
+    pub fn new(api_url: &str, token: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    /// Get the API base URL
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    /// Call a method and return its `result`
+    pub async fn call(&self, method: &str, params: &Value, timeout: Duration) -> Result<Value> {
+        let response = self.client
+            .post(format!("{}/bot{}/{}", self.api_url, self.token, method))
+            .timeout(timeout)
+            .json(params)
+            .send()
+            .await
+            .map_err(|e| AdapterError::ConnectionFailed(format!("{} request failed: {}", method, e.without_url())))?;
+        let response: ApiResponse = response.json().await
+            .map_err(|e| LoquatError::Serialization(e.to_string()))?;
+
+        if response.ok {
+            Ok(response.result)
+        } else {
+            Err(AdapterError::SendFailed(format!("{} failed: {}", method, response.error_message())).into())
+        }
+    }
+
+    /// Build the download URL of a file path returned by `getFile`
+    pub fn file_url(&self, file_path: &str) -> String {
+        format!("{}/file/bot{}/{}", self.api_url, self.token, file_path)
+    }
+}
@@ -0,0 +1,484 @@
+//! Telegram update converter
+
+use crate::adapters::converter::{
+    ConversionContext, EventConverter, MessageConverter, NoticeConverter, RequestConverter,
+};
+use crate::adapters::onebot11::types::value_to_id;
+use crate::adapters::telegram::types::utf16_slice;
+use crate::errors::{AdapterError, Result};
+use crate::events::{
+    EventEnum, EventMetadata, EventSource, MessageEvent, MessageSegment, NoticeEvent, RequestEvent,
+};
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+
+/// Update fields carrying a `Message` object
+const MESSAGE_KINDS: &[&str] = &["message", "edited_message", "channel_post", "edited_channel_post"];
+
+/// Converts Telegram `Update` objects into Loquat events
+///
+/// Media is referenced by `file_id` (stored as the event URL); resolve it
+/// with `getFile` to download. Group and supergroup chats set `group_id`,
+/// channel posts set `channel_id`.
+#[derive(Debug, Clone)]
+pub struct TelegramConverter {
+    context: ConversionContext,
+}
+
+impl TelegramConverter {
+    /// Create a new converter
+    pub fn new(context: ConversionContext) -> Self {
+        Self { context }
+    }
+
+    /// Get the conversion context
+    pub fn context(&self) -> &ConversionContext {
+        &self.context
+    }
+
+    /// Get the kind of an update (the name of its payload field)
+    pub fn update_kind(update: &Value) -> Option<&str> {
+        update
+            .as_object()?
+            .keys()
+            .map(|k| k.as_str())
+            .find(|k| *k != "update_id")
+    }
+
+    /// Build metadata from an update payload (`chat`, `from`, `date`, ...)
+    ///
+    /// `convert` copies `update_kind` and `update_id` into the payload.
+    fn metadata(&self, object: &Value, kind: &str, source: EventSource) -> EventMetadata {
+        let mut metadata = EventMetadata::new(&format!("telegram.{}", kind))
+            .with_source(source)
+            .with_extra("platform", &self.context.platform_type)
+            .with_adapter_id(&self.context.adapter_id)
+            .with_extra("update_kind", kind);
+
+        if let Some(date) = object["date"].as_i64()
+            && let Some(ts) = Utc.timestamp_opt(date, 0).single()
+        {
+            metadata.timestamp = ts;
+        }
+        if !self.context.self_id.is_empty() {
+            metadata = metadata.with_self_id(&self.context.self_id);
+        }
+
+        let from = &object["from"];
+        if let Some(user_id) = value_to_id(&from["id"]) {
+            metadata = metadata.with_user_id(&user_id);
+        }
+        if let Some(name) = from["username"].as_str().or_else(|| from["first_name"].as_str()) {
+            metadata = metadata.with_extra("sender_nickname", name);
+        }
+
+        let chat = &object["chat"];
+        if let Some(chat_id) = value_to_id(&chat["id"]) {
+            match chat["type"].as_str() {
+                Some("group") | Some("supergroup") => metadata = metadata.with_group_id(&chat_id),
+                Some("channel") => metadata = metadata.with_channel_id(&chat_id),
+                _ => {}
+            }
+            metadata = metadata
+                .with_extra("chat_id", chat_id)
+                .with_extra("chat_type", chat["type"].clone());
+        }
+        if let Some(update_id) = value_to_id(&object["update_id"]) {
+            metadata = metadata.with_extra("update_id", update_id);
+        }
+        if let Some(thread_id) = value_to_id(&object["message_thread_id"]) {
+            metadata = metadata.with_extra("message_thread_id", thread_id);
+        }
+        if self.context.options.include_raw {
+            metadata = metadata.with_extra("raw", object);
+        }
+
+        metadata
+    }
+
+    fn require_id(value: &Value, what: &str) -> Result<String> {
+        value_to_id(value).ok_or_else(|| {
+            AdapterError::ConversionFailed(format!("missing field `{}`", what)).into()
+        })
+    }
+
+    /// Convert an update payload whose kind is known
+    fn convert_kind(&self, kind: &str, payload: Value) -> Result<EventEnum> {
+        match kind {
+            k if MESSAGE_KINDS.contains(&k) => {
+                let is_service = payload.get("new_chat_members").is_some()
+                    || payload.get("left_chat_member").is_some();
+                if is_service {
+                    self.convert_notice(payload).map(EventEnum::Notice)
+                } else {
+                    self.convert_message(payload).map(EventEnum::Message)
+                }
+            }
+            "chat_member" | "my_chat_member" => self.convert_notice(payload).map(EventEnum::Notice),
+            "chat_join_request" => self.convert_request(payload).map(EventEnum::Request),
+            other => Err(AdapterError::ConversionFailed(format!(
+                "unsupported update kind: {}",
+                other
+            ))
+            .into()),
+        }
+    }
+}
+
+impl EventConverter<Value> for TelegramConverter {
+    fn convert(&self, mut update: Value) -> Result<EventEnum> {
+        let kind = Self::update_kind(&update)
+            .ok_or_else(|| AdapterError::ConversionFailed("empty update".to_string()))?
+            .to_string();
+        let mut payload = update[&kind].take();
+        if let Some(object) = payload.as_object_mut() {
+            object.insert("update_kind".to_string(), Value::from(kind.as_str()));
+            object.insert("update_id".to_string(), update["update_id"].take());
+        }
+        self.convert_kind(&kind, payload)
+    }
+
+    fn supported_types(&self) -> Vec<String> {
+        let mut types: Vec<String> = MESSAGE_KINDS.iter().map(|k| k.to_string()).collect();
+        types.extend(["chat_member", "my_chat_member", "chat_join_request"].map(String::from));
+        types
+    }
+}
+
+/// Get the users mentioned in a message text or caption
+fn mentions_of(message: &Value, text: &str) -> Vec<String> {
+    message
+        .get("entities")
+        .or_else(|| message.get("caption_entities"))
+        .and_then(|entities| entities.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entity| match entity["type"].as_str() {
+            Some("mention") => {
+                let offset = entity["offset"].as_u64()? as usize;
+                let length = entity["length"].as_u64()? as usize;
+                utf16_slice(text, offset, length).map(|m| m.trim_start_matches('@').to_string())
+            }
+            Some("text_mention") => value_to_id(&entity["user"]["id"]),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Get the media attached to a message as a segment
+///
+/// The largest photo size comes last.
+fn media_segment(message: &Value) -> Option<MessageSegment> {
+    let file_id = |object: &Value| object["file_id"].as_str().unwrap_or_default().to_string();
+    let duration = |object: &Value| object["duration"].as_u64().unwrap_or(0) as u32;
+
+    if let Some(photo) = message["photo"].as_array().and_then(|sizes| sizes.last()) {
+        return Some(MessageSegment::Image { url: file_id(photo) });
+    }
+    if let Some(voice) = message.get("voice").or_else(|| message.get("audio")) {
+        return Some(MessageSegment::Voice { url: file_id(voice), duration: duration(voice) });
+    }
+    if let Some(video) = ["video", "animation", "video_note"].iter().find_map(|k| message.get(*k)) {
+        return Some(MessageSegment::Video {
+            url: file_id(video),
+            cover_url: video["thumbnail"]["file_id"].as_str().map(|s| s.to_string()),
+        });
+    }
+    if let Some(document) = message.get("document") {
+        return Some(MessageSegment::File {
+            url: file_id(document),
+            name: document["file_name"].as_str().unwrap_or_default().to_string(),
+            size: document["file_size"].as_u64().unwrap_or(0),
+        });
+    }
+    if let Some(location) = message.get("location") {
+        let venue = &message["venue"];
+        return Some(MessageSegment::Location {
+            latitude: location["latitude"].as_f64().unwrap_or(0.0),
+            longitude: location["longitude"].as_f64().unwrap_or(0.0),
+            address: venue["title"].as_str().or_else(|| venue["address"].as_str()).map(|s| s.to_string()),
+        });
+    }
+    message.get("sticker").map(|sticker| MessageSegment::Sticker { sticker_id: file_id(sticker) })
+}
+
+impl MessageConverter<Value> for TelegramConverter {
+    fn convert_message(&self, message: Value) -> Result<MessageEvent> {
+        let kind = message["update_kind"].as_str().unwrap_or("message");
+        let mut metadata = self.metadata(&message, kind, EventSource::User);
+        if let Some(message_id) = value_to_id(&message["message_id"]) {
+            metadata = metadata.with_extra("message_id", message_id);
+        }
+
+        let text = message["text"].as_str().or_else(|| message["caption"].as_str());
+        let reply_to = value_to_id(&message["reply_to_message"]["message_id"]);
+        let at_list = mentions_of(&message, text.unwrap_or_default());
+
+        // Quotes combined with media or mentions, mentions in media captions
+        // and captions of non-photo media need a message chain
+        let media = media_segment(&message);
+        let has_text = text.is_some_and(|t| !t.is_empty());
+        let captioned = has_text && media.as_ref().is_some_and(|m| !matches!(m, MessageSegment::Image { .. }));
+        let parts = [reply_to.is_some(), !at_list.is_empty(), media.is_some(), captioned];
+        if parts.iter().filter(|part| **part).count() > 1 {
+            let mut segments: Vec<MessageSegment> = reply_to
+                .map(|message_id| MessageSegment::Reply { message_id })
+                .into_iter()
+                .chain(media)
+                .chain(at_list.into_iter().map(|user_id| MessageSegment::At { user_id }))
+                .collect();
+            if let Some(text) = text.filter(|_| has_text) {
+                segments.push(MessageSegment::Text { text: text.to_string() });
+            }
+            let message_id = metadata.extra["message_id"]
+                .as_str()
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| metadata.event_id.clone());
+            return Ok(MessageEvent::Chain { message_id, segments, metadata });
+        }
+
+        match media {
+            Some(MessageSegment::Image { url }) => {
+                return Ok(MessageEvent::Image { url, caption: text.map(|s| s.to_string()), metadata });
+            }
+            Some(MessageSegment::Voice { url, duration }) => {
+                return Ok(MessageEvent::Voice { url, duration, metadata });
+            }
+            Some(MessageSegment::Video { url, cover_url }) => {
+                let duration = ["video", "animation", "video_note"]
+                    .iter()
+                    .find_map(|k| message[*k]["duration"].as_u64())
+                    .unwrap_or(0) as u32;
+                return Ok(MessageEvent::Video { url, duration, cover_url, metadata });
+            }
+            Some(MessageSegment::File { url, name, size }) => {
+                return Ok(MessageEvent::File { url, name, size, metadata });
+            }
+            Some(MessageSegment::Location { latitude, longitude, address }) => {
+                return Ok(MessageEvent::Location { latitude, longitude, address, metadata });
+            }
+            Some(MessageSegment::Sticker { sticker_id }) => {
+                let metadata = metadata.with_extra("emoji", message["sticker"]["emoji"].clone());
+                return Ok(MessageEvent::Sticker { sticker_id, metadata });
+            }
+            _ => {}
+        }
+
+        let text = text.unwrap_or_default().to_string();
+        if let Some(reply_to) = reply_to {
+            return Ok(MessageEvent::Reply { reply_to, text, metadata });
+        }
+        if !at_list.is_empty() {
+            return Ok(MessageEvent::At { text, at_list, metadata });
+        }
+
+        Ok(MessageEvent::Text { text, metadata })
+    }
+
+    fn supports_message(&self, message_type: &str) -> bool {
+        matches!(message_type, "private" | "group" | "supergroup" | "channel")
+    }
+}
+
+impl NoticeConverter<Value> for TelegramConverter {
+    fn convert_notice(&self, notice: Value) -> Result<NoticeEvent> {
+        let kind = notice["update_kind"].as_str().unwrap_or("message");
+        let metadata = self.metadata(&notice, kind, EventSource::System);
+        let group_id = Self::require_id(&notice["chat"]["id"], "chat.id")?;
+
+        if let Some(member) = notice["new_chat_members"].as_array().and_then(|m| m.first()) {
+            return Ok(NoticeEvent::GroupMemberJoin {
+                user_id: Self::require_id(&member["id"], "new_chat_members.id")?,
+                group_id,
+                user_info: None,
+                metadata: metadata.with_extra("notice_type", "new_chat_members"),
+            });
+        }
+        if let Some(member) = notice.get("left_chat_member") {
+            return Ok(NoticeEvent::GroupMemberLeave {
+                user_id: Self::require_id(&member["id"], "left_chat_member.id")?,
+                group_id,
+                reason: None,
+                metadata: metadata.with_extra("notice_type", "left_chat_member"),
+            });
+        }
+
+        // chat_member updates: compare the old and new membership status
+        let new_member = &notice["new_chat_member"];
+        let user_id = Self::require_id(&new_member["user"]["id"], "new_chat_member.user.id")?;
+        let was_member = matches!(
+            notice["old_chat_member"]["status"].as_str(),
+            Some("member" | "administrator" | "creator" | "restricted")
+        );
+        let metadata = metadata.with_extra("notice_type", kind);
+
+        let event = match new_member["status"].as_str() {
+            Some("member") if !was_member => NoticeEvent::GroupMemberJoin {
+                user_id,
+                group_id,
+                user_info: None,
+                metadata,
+            },
+            Some("left") => NoticeEvent::GroupMemberLeave {
+                user_id,
+                group_id,
+                reason: None,
+                metadata,
+            },
+            Some("kicked") => NoticeEvent::GroupMemberKick {
+                user_id,
+                group_id,
+                operator_id: value_to_id(&notice["from"]["id"]).unwrap_or_default(),
+                reason: None,
+                metadata,
+            },
+            _ => NoticeEvent::SystemNotice {
+                notice_type: kind.to_string(),
+                content: notice.to_string(),
+                metadata,
+            },
+        };
+
+        Ok(event)
+    }
+
+    fn supports_notice(&self, notice_type: &str) -> bool {
+        matches!(
+            notice_type,
+            "new_chat_members" | "left_chat_member" | "chat_member" | "my_chat_member"
+        )
+    }
+}
+
+impl RequestConverter<Value> for TelegramConverter {
+    fn convert_request(&self, request: Value) -> Result<RequestEvent> {
+        let metadata = self
+            .metadata(&request, "chat_join_request", EventSource::User)
+            .with_extra("request_type", "chat_join_request");
+
+        Ok(RequestEvent::GroupJoinRequest {
+            user_id: Self::require_id(&request["from"]["id"], "from.id")?,
+            group_id: Self::require_id(&request["chat"]["id"], "chat.id")?,
+            reason: request["bio"].as_str().map(|s| s.to_string()),
+            metadata,
+        })
+    }
+
+    fn supports_request(&self, request_type: &str) -> bool {
+        request_type == "chat_join_request"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::ChannelType;
+    use serde_json::json;
+
+    fn converter() -> TelegramConverter {
+        TelegramConverter::new(ConversionContext::new("telegram-001", "telegram", "123456"))
+    }
+
+    fn update(kind: &str, payload: Value) -> Value {
+        json!({"update_id": 10, kind: payload})
+    }
+
+    #[test]
+    fn test_convert_text_and_mentions() {
+        let event = converter().convert(update("message", json!({
+            "message_id": 5, "date": 1700000000,
+            "from": {"id": 42, "first_name": "Alice", "username": "alice"},
+            "chat": {"id": -1001, "type": "supergroup", "title": "G"},
+            "text": "hi @loquat_bot",
+            "entities": [{"type": "mention", "offset": 3, "length": 11}]
+        }))).unwrap();
+
+        let EventEnum::Message(MessageEvent::At { text, at_list, metadata }) = event else {
+            panic!("expected at message");
+        };
+        assert_eq!(text, "hi @loquat_bot");
+        assert_eq!(at_list, vec!["loquat_bot".to_string()]);
+        assert_eq!(metadata.user_id.as_deref(), Some("42"));
+        assert_eq!(metadata.group_id.as_deref(), Some("-1001"));
+        assert_eq!(metadata.self_id.as_deref(), Some("123456"));
+        assert_eq!(metadata.extra["message_id"], "5");
+        assert_eq!(metadata.extra["update_id"], "10");
+    }
+
+    #[test]
+    fn test_convert_media() {
+        let base = json!({"message_id": 1, "from": {"id": 42}, "chat": {"id": 42, "type": "private"}});
+        let with = |key: &str, value: Value| {
+            let mut message = base.clone();
+            message[key] = value;
+            converter().convert(update("message", message)).unwrap()
+        };
+
+        let event = with("photo", json!([{"file_id": "small"}, {"file_id": "large"}]));
+        assert!(matches!(event, EventEnum::Message(MessageEvent::Image { ref url, .. }) if url == "large"));
+
+        let event = with("voice", json!({"file_id": "v1", "duration": 3}));
+        assert!(matches!(event, EventEnum::Message(MessageEvent::Voice { duration: 3, .. })));
+
+        let event = with("video", json!({"file_id": "vd", "duration": 9, "thumbnail": {"file_id": "th"}}));
+        assert!(matches!(event, EventEnum::Message(MessageEvent::Video { ref cover_url, .. }) if cover_url.as_deref() == Some("th")));
+
+        let event = with("document", json!({"file_id": "d1", "file_name": "a.pdf", "file_size": 100}));
+        assert!(matches!(event, EventEnum::Message(MessageEvent::File { size: 100, ref name, .. }) if name == "a.pdf"));
+
+        let event = with("location", json!({"latitude": 1.5, "longitude": 2.5}));
+        assert!(matches!(event, EventEnum::Message(MessageEvent::Location { latitude, .. }) if latitude == 1.5));
+
+        let event = with("sticker", json!({"file_id": "s1", "emoji": "😀"}));
+        assert!(matches!(event, EventEnum::Message(MessageEvent::Sticker { ref sticker_id, .. }) if sticker_id == "s1"));
+
+        let mut message = base.clone();
+        message["photo"] = json!([{"file_id": "p1"}]);
+        message["caption"] = json!("@bob see");
+        message["caption_entities"] = json!([{"type": "mention", "offset": 0, "length": 4}]);
+        message["reply_to_message"] = json!({"message_id": 0});
+        let EventEnum::Message(event) = converter().convert(update("message", message)).unwrap() else {
+            panic!("expected message event");
+        };
+        assert!(matches!(event, MessageEvent::Chain { ref message_id, .. } if message_id == "1"));
+        assert_eq!(event.reply_to(), Some("0"));
+        assert_eq!(event.images(), vec!["p1"]);
+        assert_eq!(event.mentions(), vec!["bob"]);
+        assert_eq!(event.plain_text(), "@bob see");
+    }
+
+    #[test]
+    fn test_convert_channel_post() {
+        let event = converter().convert(update("channel_post", json!({
+            "message_id": 7, "chat": {"id": -1002, "type": "channel", "title": "News"}, "text": "news"
+        }))).unwrap();
+
+        let EventEnum::Message(MessageEvent::Text { metadata, .. }) = event else {
+            panic!("expected text message");
+        };
+        assert_eq!(ChannelType::from_metadata(&metadata), Some(ChannelType::channel("-1002")));
+    }
+
+    #[test]
+    fn test_convert_member_and_join_request() {
+        let event = converter().convert(update("chat_member", json!({
+            "chat": {"id": -1001, "type": "supergroup"}, "from": {"id": 1},
+            "old_chat_member": {"status": "member", "user": {"id": 42}},
+            "new_chat_member": {"status": "kicked", "user": {"id": 42}}
+        }))).unwrap();
+        assert!(matches!(
+            event,
+            EventEnum::Notice(NoticeEvent::GroupMemberKick { ref operator_id, .. }) if operator_id == "1"
+        ));
+
+        let event = converter().convert(update("chat_join_request", json!({
+            "chat": {"id": -1001, "type": "supergroup"}, "from": {"id": 42}, "bio": "hello"
+        }))).unwrap();
+        assert!(matches!(
+            event,
+            EventEnum::Request(RequestEvent::GroupJoinRequest { ref reason, .. }) if reason.as_deref() == Some("hello")
+        ));
+
+        assert!(converter().convert(update("poll", json!({}))).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! Telegram Adapter Factory
+
+use crate::adapters::{
+    Adapter, AdapterConfig, AdapterFactory,
+};
+use crate::errors::{AdapterError, Result};
+use super::adapter::{TelegramAdapter, TelegramMode};
+
+/// Factory for creating TelegramAdapter instances
+pub struct TelegramAdapterFactory;
+
+impl AdapterFactory for TelegramAdapterFactory {
+    fn adapter_type(&self) -> &str {
+        "telegram"
+    }
+
+    fn create(&self, config: AdapterConfig) -> Result<Box<dyn Adapter>> {
+        let mode = TelegramMode::from_conn_type(&config.connection.conn_type).ok_or_else(|| {
+            AdapterError::InvalidConfig(format!(
+                "Unsupported Telegram connection type: {}",
+                config.connection.conn_type
+            ))
+        })?;
+        if config.platform["token"].as_str().is_none_or(|t| t.is_empty()) {
+            return Err(AdapterError::InvalidConfig(
+                "Telegram adapter requires platform.token".to_string(),
+            )
+            .into());
+        }
+        if mode == TelegramMode::Webhook && config.connection.url.is_empty() {
+            return Err(AdapterError::InvalidConfig(
+                "Telegram webhook mode requires a bind address".to_string(),
+            )
+            .into());
+        }
+        Ok(Box::new(TelegramAdapter::new(config)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::AdapterFactoryRegistry;
+
+    #[test]
+    fn test_telegram_factory_create() {
+        let registry = AdapterFactoryRegistry::new();
+        registry.register(Box::new(TelegramAdapterFactory)).unwrap();
+
+        let mut config = AdapterConfig::new("telegram", "telegram-001", "")
+            .with_platform_config("token", "123:abc")
+            .unwrap();
+        config.connection.conn_type = "polling".to_string();
+        let adapter = registry.create(config.clone()).unwrap();
+        assert_eq!(adapter.name(), "TelegramAdapter");
+
+        config.connection.conn_type = "webhook".to_string();
+        assert!(registry.create(config.clone()).is_err());
+
+        config.connection.conn_type = "ws".to_string();
+        assert!(registry.create(config).is_err());
+
+        let mut config = AdapterConfig::new("telegram", "telegram-002", "");
+        config.connection.conn_type = "polling".to_string();
+        assert!(registry.create(config).is_err());
+    }
+}
@@ -0,0 +1,18 @@
+//! Telegram adapter
+//!
+//! Talks to the Telegram Bot API, receiving updates by `getUpdates` long
+//! polling or on a webhook listener.
+
+pub mod types;
+pub mod converter;
+pub mod client;
+pub mod server;
+pub mod adapter;
+pub mod factory;
+
+pub use types::*;
+pub use converter::*;
+pub use client::*;
+pub use server::*;
+pub use adapter::*;
+pub use factory::*;
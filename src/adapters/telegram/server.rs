@@ -0,0 +1,124 @@
+//! Telegram webhook endpoint
+
+use crate::adapters::converter::EventConverter;
+use crate::adapters::telegram::converter::TelegramConverter;
+use crate::adapters::types::AdapterStatistics;
+use crate::events::EventEnum;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+
+/// Default webhook route
+pub const WEBHOOK_PATH: &str = "/telegram/webhook";
+
+/// Header carrying the `secret_token` passed to `setWebhook`
+pub const SECRET_TOKEN_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+/// Converts updates and publishes them to subscribers
+///
+/// Shared by long polling and the webhook route.
+#[derive(Debug, Clone)]
+pub struct UpdateDispatcher {
+    converter: Arc<TelegramConverter>,
+    event_sender: broadcast::Sender<EventEnum>,
+    statistics: Arc<RwLock<AdapterStatistics>>,
+}
+
+impl UpdateDispatcher {
+    /// Create a new dispatcher
+    pub fn new(
+        converter: TelegramConverter,
+        event_sender: broadcast::Sender<EventEnum>,
+        statistics: Arc<RwLock<AdapterStatistics>>,
+    ) -> Self {
+        Self {
+            converter: Arc::new(converter),
+            event_sender,
+            statistics,
+        }
+    }
+
+    /// Subscribe to converted events
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.event_sender.subscribe()
+    }
+
+    /// Convert an update and publish it
+    ///
+    /// Unsupported update kinds are counted as errors but not fatal.
+    pub async fn dispatch(&self, update: Value) -> crate::errors::Result<EventEnum> {
+        let event = match self.converter.convert(update) {
+            Ok(event) => event,
+            Err(e) => {
+                self.statistics.write().await.errors += 1;
+                return Err(e);
+            }
+        };
+
+        let mut stats = self.statistics.write().await;
+        stats.events_received += 1;
+        stats.last_activity = Some(chrono::Utc::now().timestamp());
+        drop(stats);
+
+        let _ = self.event_sender.send(event.clone());
+        Ok(event)
+    }
+}
+
+/// Shared state of the webhook route
+#[derive(Debug, Clone)]
+pub struct WebhookState {
+    dispatcher: UpdateDispatcher,
+    secret_token: Option<String>,
+}
+
+impl WebhookState {
+    /// Create a new webhook state
+    pub fn new(dispatcher: UpdateDispatcher, secret_token: Option<&str>) -> Self {
+        Self {
+            dispatcher,
+            secret_token: secret_token.map(|t| t.to_string()),
+        }
+    }
+
+    /// Verify the secret token header
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), StatusCode> {
+        let Some(expected) = &self.secret_token else {
+            return Ok(());
+        };
+
+        match headers.get(SECRET_TOKEN_HEADER).and_then(|v| v.to_str().ok()) {
+            None => Err(StatusCode::UNAUTHORIZED),
+            Some(token) if token == expected => Ok(()),
+            Some(_) => Err(StatusCode::FORBIDDEN),
+        }
+    }
+}
+
+/// Build the router for the webhook route
+pub fn webhook_router(path: &str, state: WebhookState) -> Router {
+    Router::new()
+        .route(path, post(webhook_handler))
+        .with_state(state)
+}
+
+async fn webhook_handler(
+    State(state): State<WebhookState>,
+    headers: HeaderMap,
+    Json(update): Json<Value>,
+) -> Response {
+    if let Err(status) = state.authorize(&headers) {
+        return status.into_response();
+    }
+
+    // Telegram retries non-2xx responses, so unsupported updates are still acknowledged
+    let _ = state.dispatcher.dispatch(update).await;
+    StatusCode::OK.into_response()
+}
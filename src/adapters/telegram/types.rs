@@ -0,0 +1,167 @@
+//! Telegram Bot API types and request helpers
+
+use crate::adapters::{Message, Segment, Target};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Default Telegram Bot API base URL
+pub const DEFAULT_API_URL: &str = "https://api.telegram.org";
+
+/// Update kinds requested from `getUpdates` / `setWebhook`
+pub const ALLOWED_UPDATES: &[&str] = &[
+    "message",
+    "edited_message",
+    "channel_post",
+    "edited_channel_post",
+    "chat_member",
+    "chat_join_request",
+];
+
+/// Response envelope of every Bot API method
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiResponse {
+    /// Whether the call succeeded
+    pub ok: bool,
+    /// Result on success
+    #[serde(default)]
+    pub result: Value,
+    /// Error description on failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Error code on failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<i64>,
+}
+
+impl ApiResponse {
+    /// Get a human readable error description
+    pub fn error_message(&self) -> String {
+        match (&self.description, self.error_code) {
+            (Some(description), _) => description.clone(),
+            (None, Some(code)) => format!("error code {}", code),
+            (None, None) => "unknown error".to_string(),
+        }
+    }
+}
+
+/// Get the `chat_id` addressed by a target
+///
+/// Telegram has a single chat ID space, so users, groups and channels (IDs
+/// or `@channel` usernames) are all addressed the same way.
+pub fn chat_id_of(target: &Target) -> Value {
+    let id = match target {
+        Target::User { user_id } => user_id,
+        Target::Group { group_id } => group_id,
+        Target::Channel { channel_id } => channel_id,
+    };
+    id.parse::<i64>().map(Value::from).unwrap_or_else(|_| Value::from(id.as_str()))
+}
+
+/// Build the send method and parameters for an outbound message
+pub fn send_request(chat_id: Value, message: &Message) -> (&'static str, Value) {
+    match message {
+        Message::Text { content } => ("sendMessage", json!({"chat_id": chat_id, "text": content})),
+        Message::Image { url, caption } => (
+            "sendPhoto",
+            json!({"chat_id": chat_id, "photo": url, "caption": caption}),
+        ),
+        Message::Voice { url, duration } => (
+            "sendVoice",
+            json!({"chat_id": chat_id, "voice": url, "duration": duration}),
+        ),
+        Message::Video { url, duration, .. } => (
+            "sendVideo",
+            json!({"chat_id": chat_id, "video": url, "duration": duration}),
+        ),
+        Message::Sticker { sticker_id } => (
+            "sendSticker",
+            json!({"chat_id": chat_id, "sticker": sticker_id}),
+        ),
+        Message::Chain { segments } => chain_request(chat_id, segments),
+    }
+}
+
+/// Build the send request for a segment chain
+///
+/// A Telegram message carries at most one media item: the first media
+/// segment selects the method, the text segments become its caption and
+/// any further media is dropped. Mentions are rendered as `@user_id`.
+fn chain_request(chat_id: Value, segments: &[Segment]) -> (&'static str, Value) {
+    let mut text = String::new();
+    let mut media = None;
+    let mut reply_to = None;
+    for segment in segments {
+        match segment {
+            Segment::Text { text: content } | Segment::Markdown { content } => text.push_str(content),
+            Segment::At { user_id } => text.push_str(&format!("@{}", user_id)),
+            Segment::Reply { message_id } => reply_to = Some(message_id.clone()),
+            Segment::Image { url } => media = media.or(Some(("sendPhoto", "photo", url))),
+            Segment::Voice { url } => media = media.or(Some(("sendVoice", "voice", url))),
+            Segment::Video { url } => media = media.or(Some(("sendVideo", "video", url))),
+            Segment::File { url, .. } => media = media.or(Some(("sendDocument", "document", url))),
+            Segment::Face { id } => media = media.or(Some(("sendSticker", "sticker", id))),
+        }
+    }
+
+    let (method, mut params) = match media {
+        Some((method, field, url)) => {
+            let mut params = json!({"chat_id": chat_id, field: url});
+            if !text.is_empty() && method != "sendSticker" {
+                params["caption"] = Value::from(text);
+            }
+            (method, params)
+        }
+        None => ("sendMessage", json!({"chat_id": chat_id, "text": text})),
+    };
+    if let Some(reply_to) = reply_to {
+        let message_id = reply_to.parse::<i64>().map(Value::from).unwrap_or_else(|_| Value::from(reply_to));
+        params["reply_parameters"] = json!({"message_id": message_id});
+    }
+    (method, params)
+}
+
+/// Get the bot ID encoded in a bot token (`123456:ABC...` -> `123456`)
+pub fn bot_id_of(token: &str) -> Option<&str> {
+    token.split_once(':').map(|(id, _)| id).filter(|id| !id.is_empty())
+}
+
+/// Slice a string by UTF-16 offset and length, as used by message entities
+pub fn utf16_slice(text: &str, offset: usize, length: usize) -> Option<String> {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    let slice = units.get(offset..offset.checked_add(length)?)?;
+    String::from_utf16(slice).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_request() {
+        let message = Message::Image { url: "http://x/a.png".to_string(), caption: None };
+        let (method, params) = send_request(json!(-100), &message);
+        assert_eq!(method, "sendPhoto");
+        assert_eq!(params["photo"], "http://x/a.png");
+
+        let target = Target::Channel { channel_id: "@news".to_string() };
+        assert_eq!(chat_id_of(&target), json!("@news"));
+
+        let chain = Message::builder().reply("7").at("alice").text(" look").image("http://x/b.png").build();
+        let (method, params) = send_request(json!(-100), &chain);
+        assert_eq!(method, "sendPhoto");
+        assert_eq!(params["caption"], "@alice look");
+        assert_eq!(params["reply_parameters"]["message_id"], 7);
+    }
+
+    #[test]
+    fn test_utf16_slice() {
+        assert_eq!(utf16_slice("😀 @bob hi", 3, 4).as_deref(), Some("@bob"));
+        assert_eq!(utf16_slice("hi", 1, 5), None);
+    }
+
+    #[test]
+    fn test_bot_id_of() {
+        assert_eq!(bot_id_of("123456:ABC-DEF"), Some("123456"));
+        assert_eq!(bot_id_of("invalid"), None);
+    }
+}
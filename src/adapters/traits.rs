@@ -1,12 +1,19 @@
 //! Core adapter traits
 
-use crate::adapters::{AdapterConfig, AdapterStatus};
+use async_trait::async_trait;
+use crate::adapters::{AdapterConfig, AdapterStatus, MessageBuilder, Segment};
 use crate::events::EventEnum;
-use crate::errors::{LoquatError, Result};
+use crate::errors::Result;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use tokio::sync::broadcast;
+
+/// Default capacity of an adapter's event broadcast channel
+pub const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 1024;
 
 /// Message target for sending messages
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Target {
     /// Private message target
     User {
@@ -26,7 +33,8 @@ pub enum Target {
 }
 
 /// Message for sending through adapter
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum Message {
     /// Text message
     Text {
@@ -61,11 +69,45 @@ pub enum Message {
         /// Sticker ID
         sticker_id: String,
     },
+    /// Ordered chain of segments (text, mentions, quotes, media)
+    Chain {
+        /// Message segments
+        segments: Vec<Segment>,
+    },
+}
+
+impl Message {
+    /// Create a builder for a segment chain
+    pub fn builder() -> MessageBuilder {
+        MessageBuilder::new()
+    }
+
+    /// Get the message as a list of segments
+    pub fn segments(&self) -> Vec<Segment> {
+        match self {
+            Message::Text { content } => vec![Segment::text(content.as_str())],
+            Message::Image { url, caption } => {
+                let mut segments = vec![Segment::Image { url: url.clone() }];
+                if let Some(caption) = caption {
+                    segments.push(Segment::text(caption.as_str()));
+                }
+                segments
+            }
+            Message::Voice { url, .. } => vec![Segment::Voice { url: url.clone() }],
+            Message::Video { url, .. } => vec![Segment::Video { url: url.clone() }],
+            Message::Sticker { sticker_id } => vec![Segment::Face { id: sticker_id.clone() }],
+            Message::Chain { segments } => segments.clone(),
+        }
+    }
 }
 
 /// Core adapter trait - all platform adapters must implement this
 ///
+/// Adapters are bidirectional: inbound platform events are published to
+/// subscribers as `EventEnum`, outbound messages are delivered with `send`.
+///
 /// Note: This trait is object-safe and can be used as `dyn Adapter`.
+#[async_trait]
 pub trait Adapter: Send + Sync + Debug {
     /// Get adapter name
     fn name(&self) -> &str;
@@ -94,6 +136,26 @@ pub trait Adapter: Send + Sync + Debug {
     
     /// Get statistics about adapter
     fn statistics(&self) -> crate::adapters::types::AdapterStatistics;
+
+    /// Start the adapter (connect to the platform and begin receiving events)
+    async fn start(&self) -> Result<()>;
+
+    /// Stop the adapter
+    async fn stop(&self) -> Result<()>;
+
+    /// Send a message to a target, returning the platform message ID
+    async fn send(&self, target: Target, message: Message) -> Result<String>;
+
+    /// Send a message from a bot account (`self_id`), returning the platform message ID
+    ///
+    /// Adapters serving a single account ignore `self_id`.
+    async fn send_as(&self, self_id: Option<&str>, target: Target, message: Message) -> Result<String> {
+        let _ = self_id;
+        self.send(target, message).await
+    }
+
+    /// Subscribe to events received by this adapter
+    fn subscribe(&self) -> broadcast::Receiver<EventEnum>;
 }
 
 #[cfg(test)]
@@ -102,8 +164,18 @@ mod tests {
 
     /// Mock adapter for testing
     #[derive(Debug)]
-    struct MockAdapter;
+    struct MockAdapter {
+        events: broadcast::Sender<EventEnum>,
+    }
 
+    impl MockAdapter {
+        fn new() -> Self {
+            let (events, _) = broadcast::channel(DEFAULT_EVENT_CHANNEL_CAPACITY);
+            Self { events }
+        }
+    }
+
+    #[async_trait]
     impl Adapter for MockAdapter {
         fn name(&self) -> &str {
             "MockAdapter"
@@ -136,11 +208,38 @@ mod tests {
         fn statistics(&self) -> crate::adapters::types::AdapterStatistics {
             crate::adapters::types::AdapterStatistics::default()
         }
+
+        async fn start(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send(&self, target: Target, message: Message) -> Result<String> {
+            let event = match (target, message) {
+                (Target::User { user_id }, Message::Text { content }) => {
+                    crate::events::MessageEvent::Text {
+                        text: content,
+                        metadata: crate::events::EventMetadata::new("message.text")
+                            .with_user_id(&user_id),
+                    }
+                }
+                _ => return Err(crate::errors::AdapterError::SendFailed("unsupported".to_string()).into()),
+            };
+            let _ = self.events.send(EventEnum::Message(event));
+            Ok("mock-msg-1".to_string())
+        }
+
+        fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+            self.events.subscribe()
+        }
     }
 
     #[test]
     fn test_adapter_trait() {
-        let adapter = MockAdapter;
+        let adapter = MockAdapter::new();
         
         assert_eq!(adapter.name(), "MockAdapter");
         assert_eq!(adapter.version(), "1.0.0");
@@ -157,4 +256,22 @@ mod tests {
         assert_eq!(stats.uptime_seconds, 0);
         assert!(stats.last_activity.is_none());
     }
+
+    #[tokio::test]
+    async fn test_adapter_send_and_subscribe() {
+        let adapter = MockAdapter::new();
+        let mut events = adapter.subscribe();
+
+        let target = Target::User { user_id: "10001".to_string() };
+        let message = Message::Text { content: "hello".to_string() };
+        let message_id = adapter.send(target, message).await.unwrap();
+        assert_eq!(message_id, "mock-msg-1");
+
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.user_id(), Some("10001"));
+
+        let target = Target::Group { group_id: "20002".to_string() };
+        let message = Message::Sticker { sticker_id: "s1".to_string() };
+        assert!(adapter.send(target, message).await.is_err());
+    }
 }
@@ -0,0 +1,304 @@
+//! Channel actors
+//!
+//! Every channel owns a task that takes work from a bounded inbox and runs
+//! it one submission at a time, so messages of one conversation are
+//! processed (and answered) in the order they arrived while other
+//! conversations run in parallel.
+//!
+//! Submitting never blocks the caller on a busy channel: work that does not
+//! fit in a full inbox waits in the channel's overflow queue, from which a
+//! forwarding task moves it into the inbox in order. The overflow queue is
+//! bounded too; once it is full, submissions fail with
+//! `ChannelError::InboxFull`.
+
+use crate::channels::types::ChannelType;
+use crate::errors::{ChannelError, Result};
+use crate::events::Package;
+use futures_util::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+
+/// Default number of submissions queued behind a full inbox
+pub const DEFAULT_OVERFLOW_LIMIT: usize = 256;
+
+/// What happens to a submission while a channel's inbox is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxOverflow {
+    /// Queue the submission until there is room in the inbox, failing it
+    /// with `ChannelError::InboxFull` once the overflow queue is full
+    #[default]
+    Wait,
+
+    /// Fail the submission with `ChannelError::InboxFull`
+    Reject,
+}
+
+/// Result of packages submitted to a channel, available once they are processed
+pub type ChannelTicket = oneshot::Receiver<Result<Vec<Package>>>;
+
+/// Work waiting in a channel's inbox
+type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Submissions that did not fit in a full inbox
+#[derive(Default)]
+struct Overflow {
+    queue: VecDeque<Task>,
+    /// A task is moving the queue into the inbox
+    forwarding: bool,
+}
+
+impl std::fmt::Debug for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Overflow")
+            .field("queue", &self.queue.len())
+            .field("forwarding", &self.forwarding)
+            .finish()
+    }
+}
+
+/// Task running the work of one channel in order
+///
+/// The task stops once every clone of the actor is dropped and its inbox
+/// is drained.
+#[derive(Debug, Clone)]
+pub struct ChannelActor {
+    channel_type: ChannelType,
+    inbox: mpsc::Sender<Task>,
+    overflow: Arc<Mutex<Overflow>>,
+    /// Submissions the overflow queue holds
+    overflow_limit: usize,
+    /// Submissions queued or running
+    pending: Arc<AtomicUsize>,
+}
+
+impl ChannelActor {
+    /// Spawn the task of a channel with an inbox of `capacity` submissions
+    pub fn spawn(channel_type: ChannelType, capacity: usize) -> Self {
+        let (inbox, mut receiver) = mpsc::channel::<Task>(capacity.max(1));
+        tokio::spawn(async move {
+            while let Some(task) = receiver.recv().await {
+                // A panicking submission fails alone, the channel keeps going
+                let _ = AssertUnwindSafe(task).catch_unwind().await;
+            }
+        });
+
+        Self {
+            channel_type,
+            inbox,
+            overflow: Arc::new(Mutex::new(Overflow::default())),
+            overflow_limit: DEFAULT_OVERFLOW_LIMIT,
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Set the number of submissions queued behind a full inbox
+    pub fn with_overflow_limit(mut self, limit: usize) -> Self {
+        self.overflow_limit = limit;
+        self
+    }
+
+    /// Get the channel of this actor
+    pub fn channel_type(&self) -> &ChannelType {
+        &self.channel_type
+    }
+
+    /// Put work in the inbox
+    ///
+    /// Returns a receiver for the work's output. Work submitted earlier
+    /// finishes first. With `InboxOverflow::Wait` the work is queued behind
+    /// a full inbox instead of waiting for room, up to the overflow limit.
+    pub async fn run<F, T>(&self, work: F, overflow: InboxOverflow) -> Result<oneshot::Receiver<T>>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (reply, output) = oneshot::channel();
+        let done = PendingGuard(self.pending.clone());
+        let task: Task = Box::pin(async move {
+            let output = work.await;
+            drop(done);
+            let _ = reply.send(output);
+        });
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        // A submission that never makes it into the inbox is dropped with its guard
+        match overflow {
+            InboxOverflow::Wait => self.submit(task)?,
+            InboxOverflow::Reject => self.inbox.try_send(task).map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => ChannelError::InboxFull(self.channel_type.to_string()),
+                mpsc::error::TrySendError::Closed(_) => self.stopped(),
+            })?,
+        }
+        Ok(output)
+    }
+
+    /// Put a task in the inbox, or in the overflow queue if the inbox is full
+    fn submit(&self, task: Task) -> Result<()> {
+        let mut overflow = self.overflow.lock().unwrap_or_else(|e| e.into_inner());
+        // Queued tasks go first, later ones line up behind them
+        if overflow.forwarding {
+            if overflow.queue.len() >= self.overflow_limit {
+                return Err(ChannelError::InboxFull(self.channel_type.to_string()).into());
+            }
+            overflow.queue.push_back(task);
+            return Ok(());
+        }
+        match self.inbox.try_send(task) {
+            Ok(()) => Ok(()),
+            Err(mpsc::error::TrySendError::Full(_)) if self.overflow_limit == 0 => {
+                Err(ChannelError::InboxFull(self.channel_type.to_string()).into())
+            }
+            Err(mpsc::error::TrySendError::Full(task)) => {
+                overflow.queue.push_back(task);
+                overflow.forwarding = true;
+                tokio::spawn(forward(self.inbox.clone(), self.overflow.clone()));
+                Ok(())
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => Err(self.stopped().into()),
+        }
+    }
+
+    /// Get the number of submissions waiting in the inbox or behind it
+    pub fn queue_depth(&self) -> usize {
+        let overflowed = self.overflow.lock().map(|overflow| overflow.queue.len()).unwrap_or(0);
+        self.inbox.max_capacity() - self.inbox.capacity() + overflowed
+    }
+
+    /// Check if nothing is queued or running
+    pub fn is_idle(&self) -> bool {
+        self.pending.load(Ordering::SeqCst) == 0
+    }
+
+    /// Check if the task is still taking submissions
+    pub fn is_running(&self) -> bool {
+        !self.inbox.is_closed()
+    }
+
+    fn stopped(&self) -> ChannelError {
+        ChannelError::Stopped(self.channel_type.to_string())
+    }
+}
+
+/// Move the overflow queue into the inbox as room frees up
+async fn forward(inbox: mpsc::Sender<Task>, overflow: Arc<Mutex<Overflow>>) {
+    loop {
+        let task = {
+            let mut overflow = overflow.lock().unwrap_or_else(|e| e.into_inner());
+            match overflow.queue.pop_front() {
+                Some(task) => task,
+                None => {
+                    overflow.forwarding = false;
+                    return;
+                }
+            }
+        };
+        if inbox.send(task).await.is_err() {
+            // The channel stopped; dropping the queue fails its submissions
+            let mut overflow = overflow.lock().unwrap_or_else(|e| e.into_inner());
+            overflow.queue.clear();
+            overflow.forwarding = false;
+            return;
+        }
+    }
+}
+
+/// Counts a submission as finished when dropped, even if it panicked
+struct PendingGuard(Arc<AtomicUsize>);
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn step(order: &Arc<Mutex<Vec<&'static str>>>, name: &'static str, delay: u64) -> impl Future<Output = &'static str> + use<> {
+        let order = order.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            order.lock().unwrap().push(name);
+            name
+        }
+    }
+
+    #[tokio::test]
+    async fn test_actor_keeps_order() {
+        let actor = ChannelActor::spawn(ChannelType::group("g1"), 8);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // The slow first submission still finishes first
+        let first = actor.run(step(&order, "first", 30), InboxOverflow::Wait).await.unwrap();
+        let second = actor.run(step(&order, "second", 0), InboxOverflow::Wait).await.unwrap();
+        assert_eq!(second.await.unwrap(), "second");
+        assert_eq!(first.await.unwrap(), "first");
+        assert_eq!(*order.lock().unwrap(), vec!["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn test_actor_rejects_when_full() {
+        let actor = ChannelActor::spawn(ChannelType::group("g1"), 1);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // The task holds the first submission, the second fills the inbox
+        let _running = actor.run(step(&order, "a", 50), InboxOverflow::Wait).await.unwrap();
+        tokio::task::yield_now().await;
+        let _queued = actor.run(step(&order, "b", 0), InboxOverflow::Wait).await.unwrap();
+        assert_eq!(actor.queue_depth(), 1);
+
+        let rejected = actor.run(step(&order, "c", 0), InboxOverflow::Reject).await;
+        assert!(matches!(rejected, Err(crate::errors::LoquatError::Channel(ChannelError::InboxFull(_)))));
+        assert!(!actor.is_idle());
+    }
+
+    #[tokio::test]
+    async fn test_actor_queues_behind_full_inbox() {
+        let actor = ChannelActor::spawn(ChannelType::group("g1"), 1).with_overflow_limit(2);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let first = actor.run(step(&order, "a", 50), InboxOverflow::Wait).await.unwrap();
+        tokio::task::yield_now().await;
+        let _second = actor.run(step(&order, "b", 0), InboxOverflow::Wait).await.unwrap();
+
+        // A full inbox does not hold up the caller
+        let submitted = tokio::time::timeout(Duration::from_millis(20), async {
+            let third = actor.run(step(&order, "c", 0), InboxOverflow::Wait).await.unwrap();
+            let fourth = actor.run(step(&order, "d", 0), InboxOverflow::Wait).await.unwrap();
+            (third, fourth)
+        })
+        .await;
+        let (_third, fourth) = submitted.expect("submitting to a full inbox does not wait");
+        assert_eq!(actor.queue_depth(), 3);
+
+        // The overflow queue is bounded as well
+        let rejected = actor.run(step(&order, "e", 0), InboxOverflow::Wait).await;
+        assert!(matches!(rejected, Err(crate::errors::LoquatError::Channel(ChannelError::InboxFull(_)))));
+
+        assert_eq!(fourth.await.unwrap(), "d");
+        assert_eq!(first.await.unwrap(), "a");
+        assert_eq!(*order.lock().unwrap(), vec!["a", "b", "c", "d"]);
+        assert!(actor.is_idle());
+    }
+
+    #[tokio::test]
+    async fn test_actor_survives_panics() {
+        let actor = ChannelActor::spawn(ChannelType::group("g1"), 8);
+
+        let failed = actor.run(async { panic!("task exploded") }, InboxOverflow::Wait).await.unwrap();
+        let next = actor.run(async { 1 }, InboxOverflow::Wait).await.unwrap();
+        assert!(failed.await.is_err());
+        assert_eq!(next.await.unwrap(), 1);
+        assert!(actor.is_running());
+        assert!(actor.is_idle());
+    }
+}
@@ -1,26 +1,57 @@
 //! Standard channel manager implementation
 
+use crate::channel_manager::actor::{ChannelActor, ChannelTicket};
 use crate::channel_manager::traits::ChannelManager;
-use crate::channel_manager::types::{ChannelInfo, ChannelManagerConfig, ChannelStats};
+use crate::channel_manager::types::{ChannelCounters, ChannelInfo, ChannelManagerConfig, ChannelStats};
 use crate::channels::types::ChannelType;
-use crate::errors::{ChannelError, Result};
+use crate::errors::{ChannelError, ConfigError, LoquatError, Result};
+use crate::events::{EventEnum, EventMetadata, EventSource, MetaEvent, Package, SystemEventType};
 use crate::logging::traits::{LogLevel, LogContext};
 use crate::streams::{Stream, StandardStream};
+use crate::pools::{PoolMonitor, PoolType, StageLayout};
+use crate::workers::{WorkerRegistration, WorkerRegistry, WorkerScope};
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
+
+/// A channel's stream together with the actor feeding it
+#[derive(Clone)]
+struct ChannelEntry {
+    stream: Arc<dyn Stream>,
+    actor: ChannelActor,
+    info: ChannelInfo,
+    /// Packages submitted to the channel
+    packages: Arc<AtomicU64>,
+}
 
 /// Standard channel manager - manages multiple channel instances
 pub struct StandardChannelManager {
-    /// Channel storage: (Stream, ChannelInfo)
-    channels: Arc<tokio::sync::RwLock<HashMap<ChannelType, (Arc<dyn Stream>, ChannelInfo)>>>,
+    /// Channel storage
+    channels: Arc<tokio::sync::RwLock<HashMap<ChannelType, ChannelEntry>>>,
     
     /// Manager configuration
     config: ChannelManagerConfig,
     
     /// Statistics
-    stats: Arc<tokio::sync::RwLock<ChannelStats>>,
+    stats: Arc<ChannelCounters>,
+    
+    /// Workers shared by all channel streams
+    workers: Arc<WorkerRegistry>,
+    
+    /// Pool metrics and quarantine shared by all channel streams
+    monitor: Arc<PoolMonitor>,
+    
+    /// Eviction events
+    event_sender: broadcast::Sender<EventEnum>,
+    
+    /// Background task evicting idle channels
+    sweeper: Mutex<Option<JoinHandle<()>>>,
     
     /// Logger
     logger: Arc<dyn crate::logging::Logger>,
@@ -29,32 +60,172 @@ pub struct StandardChannelManager {
 impl StandardChannelManager {
     /// Create a new channel manager
     pub fn new(logger: Arc<dyn crate::logging::Logger>) -> Self {
-        Self {
-            channels: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
-            config: ChannelManagerConfig::new(),
-            stats: Arc::new(tokio::sync::RwLock::new(ChannelStats::new())),
-            logger,
-        }
+        Self::with_config(ChannelManagerConfig::new(), logger)
     }
     
     /// Create a new channel manager with custom config
     pub fn with_config(config: ChannelManagerConfig, logger: Arc<dyn crate::logging::Logger>) -> Self {
+        let (event_sender, _) = broadcast::channel(64);
         Self {
             channels: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
             config,
-            stats: Arc::new(tokio::sync::RwLock::new(ChannelStats::new())),
+            stats: Arc::new(ChannelCounters::new()),
+            workers: Arc::new(WorkerRegistry::new()),
+            monitor: Arc::new(PoolMonitor::new()),
+            event_sender,
+            sweeper: Mutex::new(None),
             logger,
         }
     }
     
+    /// Share `registry` with the streams of this manager
+    pub fn with_worker_registry(mut self, registry: Arc<WorkerRegistry>) -> Self {
+        self.workers = registry;
+        self
+    }
+    
+    /// Get the worker registry shared by the streams of this manager
+    pub fn worker_registry(&self) -> &Arc<WorkerRegistry> {
+        &self.workers
+    }
+    
+    /// Report the pools of this manager's streams to `monitor`
+    pub fn with_pool_monitor(mut self, monitor: Arc<PoolMonitor>) -> Self {
+        self.monitor = monitor;
+        self
+    }
+    
+    /// Get the pool monitor shared by the streams of this manager
+    pub fn pool_monitor(&self) -> &Arc<PoolMonitor> {
+        &self.monitor
+    }
+    
+    /// Subscribe to the eviction events of this manager
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.event_sender.subscribe()
+    }
+    
+    /// Register a third-party worker
+    ///
+    /// Fails if the pool does not accept third-party workers: built-in pools
+    /// follow `PoolType::allows_third_party`, custom stages their definition.
+    /// Global workers are checked against the default stages, channel workers
+    /// against the stages of their channel.
+    pub fn register_third_party_worker(
+        &self,
+        pool_type: PoolType,
+        scope: WorkerScope,
+        registration: WorkerRegistration,
+    ) -> Result<()> {
+        let layout = match &scope {
+            WorkerScope::Global => StageLayout::new(&self.config.stages)?,
+            WorkerScope::Channel(channel_type) => self.config.stage_layout(channel_type)?,
+        };
+        if !layout.allows_third_party(&pool_type) {
+            return Err(LoquatError::Config(ConfigError::InvalidFormat(format!(
+                "Pool '{}' does not accept third-party workers",
+                pool_type
+            ))));
+        }
+        self.workers.register(pool_type, scope, registration)
+    }
+    
     /// Create a new stream for given channel type
-    fn create_stream(&self, channel_type: &ChannelType) -> Arc<dyn Stream> {
+    fn create_stream(&self, channel_type: &ChannelType) -> Result<Arc<dyn Stream>> {
         // Create StandardStream with channel_id derived from ChannelType
-        Arc::new(StandardStream::new(
+        Ok(Arc::new(StandardStream::with_registry(
             channel_type.id().to_string(),
             channel_type.clone(),
+            self.workers.clone(),
+            &self.config.pool_configs,
+            Arc::new(self.config.stage_layout(channel_type)?),
+            self.monitor.clone(),
             self.logger.clone(),
-        ))
+        )))
+    }
+    
+    /// Get a channel, creating it if needed
+    async fn open_channel(&self, channel_type: &ChannelType) -> Result<ChannelEntry> {
+        let mut channels = self.channels.write().await;
+        self.open_channel_in(&mut channels, channel_type)
+    }
+    
+    /// Get a channel from the locked channel map and touch it, creating it if needed
+    fn open_channel_in(
+        &self,
+        channels: &mut HashMap<ChannelType, ChannelEntry>,
+        channel_type: &ChannelType,
+    ) -> Result<ChannelEntry> {
+        if let Some(entry) = channels.get_mut(channel_type) {
+            entry.info.touch();
+            return Ok(entry.clone());
+        }
+        
+        // Check max channels
+        self.check_max_channels(channels.len())?;
+        
+        // Create new stream and the task feeding it
+        let stream = self.create_stream(channel_type)?;
+        let actor = ChannelActor::spawn(channel_type.clone(), self.config.inbox_capacity)
+            .with_overflow_limit(self.config.overflow_limit);
+        let entry = ChannelEntry {
+            stream,
+            actor,
+            info: ChannelInfo::new(channel_type.clone()),
+            packages: self.stats.record_created(channel_type),
+        };
+        channels.insert(channel_type.clone(), entry.clone());
+        
+        // Log channel creation
+        let message = format!(
+            "Created new channel for {} (total: {})",
+            channel_type, channels.len()
+        );
+        let context = LogContext::new().with_component("ChannelManager");
+        self.logger.log(LogLevel::Info, &message, &context);
+        
+        Ok(entry)
+    }
+    
+    /// Queue work on a channel's stream, creating the channel if needed
+    ///
+    /// `work` runs after everything queued on the channel before it; the
+    /// returned receiver yields its output. `packages` is the number of
+    /// packages the work carries, counted in the channel's stats.
+    pub async fn schedule<F, Fut, T>(
+        &self,
+        channel_type: &ChannelType,
+        packages: usize,
+        work: F,
+    ) -> Result<oneshot::Receiver<T>>
+    where
+        F: FnOnce(Arc<dyn Stream>) -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        // The submission is counted before the lock is released, so the
+        // sweeper cannot evict the channel in between and let a second actor
+        // take over the conversation
+        let mut channels = self.channels.write().await;
+        let entry = self.open_channel_in(&mut channels, channel_type)?;
+        let output = entry.actor.run(work(entry.stream), self.config.inbox_overflow).await?;
+        drop(channels);
+        entry.packages.fetch_add(packages as u64, Ordering::Relaxed);
+        Ok(output)
+    }
+    
+    /// Put packages in a channel's inbox, creating the channel if needed
+    ///
+    /// Returns a ticket for the processed packages.
+    pub async fn enqueue(&self, channel_type: &ChannelType, packages: Vec<Package>) -> Result<ChannelTicket> {
+        self.schedule(channel_type, packages.len(), |stream| async move { stream.process(packages).await })
+            .await
+    }
+    
+    /// Get the number of submissions waiting in a channel's inbox
+    pub async fn queue_depth(&self, channel_type: &ChannelType) -> Option<usize> {
+        let channels = self.channels.read().await;
+        channels.get(channel_type).map(|entry| entry.actor.queue_depth())
     }
     
     /// Check if max channels reached
@@ -65,28 +236,121 @@ impl StandardChannelManager {
         Ok(())
     }
     
-    /// Get channel info and touch it (update last_used)
-    async fn touch_channel(&self, channel_type: &ChannelType) -> Result<()> {
-        let mut channels = self.channels.write().await;
-        if let Some((_, info)) = channels.get_mut(channel_type) {
-            info.touch();
+    /// Remove channels idle for longer than `channel_timeout`
+    ///
+    /// A channel with submissions queued or running is never idle. Work
+    /// handed to an evicted channel's actor while it was being removed still
+    /// runs before the actor stops. Each eviction is announced as a
+    /// `SystemEventType::ChannelEvicted` event.
+    pub async fn evict_idle(&self) -> usize {
+        let timeout = self.config.channel_timeout;
+        
+        if timeout == 0 {
+            return 0; // No timeout configured
         }
-        Ok(())
+        
+        let evicted: Vec<ChannelEntry> = {
+            let mut channels = self.channels.write().await;
+            let idle: Vec<ChannelType> = channels
+                .iter()
+                .filter(|(_, entry)| entry.info.idle_seconds() as u64 > timeout && entry.actor.is_idle())
+                .map(|(channel_type, _)| channel_type.clone())
+                .collect();
+            idle.iter().filter_map(|channel_type| channels.remove(channel_type)).collect()
+        };
+        
+        for entry in &evicted {
+            self.stats.record_removed(&entry.info.channel_type, true);
+            
+            let message = format!(
+                "Evicted idle channel: {} (idle: {}s)",
+                entry.info.channel_type,
+                entry.info.idle_seconds()
+            );
+            let context = LogContext::new().with_component("ChannelManager");
+            self.logger.log(LogLevel::Debug, &message, &context);
+            
+            let _ = self.event_sender.send(evicted_event(entry));
+        }
+        
+        if !evicted.is_empty() {
+            let message = format!("Evicted {} idle channels", evicted.len());
+            let context = LogContext::new().with_component("ChannelManager");
+            self.logger.log(LogLevel::Info, &message, &context);
+        }
+        
+        evicted.len()
+    }
+    
+    /// Start evicting idle channels every `cleanup_interval` seconds
+    ///
+    /// Does nothing if the sweeper is already running or the interval or
+    /// `channel_timeout` is 0. The sweeper stops with `stop_sweeper` or once
+    /// the manager is dropped.
+    pub fn start_sweeper(self: &Arc<Self>) {
+        if self.config.cleanup_interval == 0 || self.config.channel_timeout == 0 {
+            return;
+        }
+        let mut sweeper = self.sweeper.lock().unwrap_or_else(|e| e.into_inner());
+        if sweeper.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+        
+        let manager = Arc::downgrade(self);
+        let period = Duration::from_secs(self.config.cleanup_interval);
+        *sweeper = Some(tokio::spawn(sweep(manager, period)));
     }
     
-    /// Update stats on channel creation
-    async fn stats_created(&self, count: usize) {
-        let mut stats = self.stats.write().await;
-        stats.record_created(count);
+    /// Stop the background sweeper
+    pub fn stop_sweeper(&self) {
+        if let Some(task) = self.sweeper.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            task.abort();
+        }
     }
     
-    /// Update stats on channel removal
-    async fn stats_removed(&self, count: usize) {
-        let mut stats = self.stats.write().await;
-        stats.record_removed(count);
+    /// Check if the background sweeper is running
+    pub fn is_sweeping(&self) -> bool {
+        self.sweeper
+            .lock()
+            .map(|sweeper| sweeper.as_ref().is_some_and(|task| !task.is_finished()))
+            .unwrap_or(false)
+    }
+}
+
+/// Sweeper loop, holding the manager only while sweeping
+async fn sweep(manager: Weak<StandardChannelManager>, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let Some(manager) = manager.upgrade() else {
+            return;
+        };
+        manager.evict_idle().await;
     }
 }
 
+/// Build the `MetaEvent::System` event announcing an eviction
+fn evicted_event(entry: &ChannelEntry) -> EventEnum {
+    let channel_type = &entry.info.channel_type;
+    let mut data = HashMap::new();
+    data.insert("channel".to_string(), serde_json::json!(channel_type.to_string()));
+    data.insert("idle_seconds".to_string(), serde_json::json!(entry.info.idle_seconds()));
+    data.insert("age_seconds".to_string(), serde_json::json!(entry.info.age_seconds()));
+    data.insert("packages".to_string(), serde_json::json!(entry.packages.load(Ordering::Relaxed)));
+    
+    EventEnum::Meta(MetaEvent::System {
+        event_type: SystemEventType::ChannelEvicted,
+        description: format!(
+            "Channel {} evicted after {}s idle",
+            channel_type,
+            entry.info.idle_seconds()
+        ),
+        data,
+        metadata: EventMetadata::new("channel.evicted").with_source(EventSource::System),
+    })
+}
+
 impl Debug for StandardChannelManager {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("StandardChannelManager")
@@ -98,52 +362,19 @@ impl Debug for StandardChannelManager {
 #[async_trait]
 impl ChannelManager for StandardChannelManager {
     async fn get_or_create_channel(&self, channel_type: &ChannelType) -> Result<Arc<dyn Stream>> {
-        // Check if channel already exists
-        let stream_opt = {
-            let channels = self.channels.read().await;
-            channels.get(channel_type).map(|(s, _)| s.clone())
-        };
-        
-        if let Some(stream) = stream_opt {
-            // Channel exists, touch and return
-            self.touch_channel(channel_type).await?;
-            return Ok(stream);
-        }
-        
-        // Channel doesn't exist, create new one
-        let mut channels = self.channels.write().await;
-        
-        // Double-check after acquiring write lock
-        if let Some((stream, _)) = channels.get(channel_type) {
-            return Ok(stream.clone());
-        }
-        
-        // Check max channels
-        self.check_max_channels(channels.len())?;
-        
-        // Create new stream
-        let stream = self.create_stream(channel_type);
-        let info = ChannelInfo::new(channel_type.clone());
-        
-        channels.insert(channel_type.clone(), (stream.clone(), info));
-        
-        // Log channel creation
-        let message = format!(
-            "Created new channel for {} (total: {})",
-            channel_type, channels.len()
-        );
-        let context = LogContext::new().with_component("ChannelManager");
-        self.logger.log(LogLevel::Info, &message, &context);
-        
-        // Update stats
-        self.stats_created(channels.len()).await;
-        
-        Ok(stream)
+        self.open_channel(channel_type).await.map(|entry| entry.stream)
+    }
+    
+    async fn submit(&self, channel_type: &ChannelType, packages: Vec<Package>) -> Result<Vec<Package>> {
+        let ticket = self.enqueue(channel_type, packages).await?;
+        ticket
+            .await
+            .map_err(|_| ChannelError::Stopped(channel_type.to_string()))?
     }
     
     async fn get_channel(&self, channel_type: &ChannelType) -> Result<Option<Arc<dyn Stream>>> {
         let channels = self.channels.read().await;
-        Ok(channels.get(channel_type).map(|(s, _)| s.clone()))
+        Ok(channels.get(channel_type).map(|entry| entry.stream.clone()))
     }
     
     async fn remove_channel(&self, channel_type: &ChannelType) -> Result<()> {
@@ -156,7 +387,7 @@ impl ChannelManager for StandardChannelManager {
             self.logger.log(LogLevel::Info, &message, &context);
             
             // Update stats
-            self.stats_removed(channels.len()).await;
+            self.stats.record_removed(channel_type, false);
             
             Ok(())
         } else {
@@ -182,6 +413,9 @@ impl ChannelManager for StandardChannelManager {
     async fn clear_all(&self) -> Result<()> {
         let mut channels = self.channels.write().await;
         let count = channels.len();
+        for channel_type in channels.keys() {
+            self.stats.record_removed(channel_type, false);
+        }
         channels.clear();
         
         // Log clear
@@ -197,56 +431,17 @@ impl ChannelManager for StandardChannelManager {
     }
     
     async fn set_config(&mut self, config: ChannelManagerConfig) -> Result<()> {
+        config.validate_stages()?;
         self.config = config;
         Ok(())
     }
     
-    fn stats(&self) -> crate::channel_manager::types::ChannelStats {
-        // Simplified implementation: return empty stats
-        // In a real production implementation, this would need a different approach
-        // such as using atomic counters or a separate stats struct
-        ChannelStats::new()
+    fn stats(&self) -> ChannelStats {
+        self.stats.snapshot()
     }
     
     async fn cleanup(&self) -> Result<usize> {
-        let timeout = self.config.channel_timeout;
-        
-        if timeout == 0 {
-            return Ok(0); // No timeout configured
-        }
-        
-        let mut channels = self.channels.write().await;
-        let mut to_remove = Vec::new();
-        
-        for (channel_type, (_, info)) in channels.iter() {
-            if info.idle_seconds() as u64 > timeout {
-                to_remove.push(channel_type.clone());
-            }
-        }
-        
-        let removed_count = to_remove.len();
-        
-        for channel_type in to_remove {
-            channels.remove(&channel_type);
-            
-            let message = format!(
-                "Cleaned up idle channel: {} (idle: {}s)",
-                channel_type,
-                timeout
-            );
-            let context = LogContext::new().with_component("ChannelManager");
-            self.logger.log(LogLevel::Debug, &message, &context);
-        }
-        
-        if removed_count > 0 {
-            let message = format!("Cleaned up {} idle channels", removed_count);
-            let context = LogContext::new().with_component("ChannelManager");
-            self.logger.log(LogLevel::Info, &message, &context);
-            
-            self.stats_removed(channels.len()).await;
-        }
-        
-        Ok(removed_count)
+        Ok(self.evict_idle().await)
     }
 }
 
@@ -405,6 +600,122 @@ mod tests {
         assert_eq!(manager.channel_count().await.unwrap(), 1);
     }
 
+    async fn backdate(manager: &StandardChannelManager, channel_type: &ChannelType, seconds: i64) {
+        let mut channels = manager.channels.write().await;
+        channels.get_mut(channel_type).unwrap().info.last_used -= chrono::Duration::seconds(seconds);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_evicts_idle_channels() {
+        let config = ChannelManagerConfig::new().with_channel_timeout(10);
+        let manager = StandardChannelManager::with_config(config, create_test_logger());
+        let mut events = manager.subscribe();
+        let (idle, active) = (ChannelType::group("idle"), ChannelType::group("active"));
+
+        manager.submit(&idle, vec![Package::new(), Package::new()]).await.unwrap();
+        manager.submit(&active, vec![Package::new()]).await.unwrap();
+        assert_eq!(manager.stats().channel_packages.get(&idle), Some(&2));
+
+        backdate(&manager, &idle, 60).await;
+        assert_eq!(manager.cleanup().await.unwrap(), 1);
+        assert!(!manager.has_channel(&idle).await.unwrap());
+
+        let EventEnum::Meta(MetaEvent::System { event_type, data, .. }) = events.try_recv().unwrap() else {
+            panic!("expected a system event");
+        };
+        assert_eq!(event_type, SystemEventType::ChannelEvicted);
+        assert_eq!(data["channel"], serde_json::json!(idle.to_string()));
+        assert_eq!(data["packages"], serde_json::json!(2));
+
+        let stats = manager.stats();
+        assert_eq!(stats.total_created, 2);
+        assert_eq!(stats.total_evicted, 1);
+        assert_eq!(stats.active_channels, 1);
+        assert_eq!(stats.peak_channels, 2);
+        assert_eq!(stats.channel_packages, HashMap::from([(active, 1)]));
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_keeps_busy_channels() {
+        let config = ChannelManagerConfig::new().with_channel_timeout(10);
+        let manager = StandardChannelManager::with_config(config, create_test_logger());
+        let channel_type = ChannelType::group("busy");
+
+        let (release, released) = oneshot::channel::<()>();
+        let done = manager
+            .schedule(&channel_type, 1, |_| async move {
+                let _ = released.await;
+            })
+            .await
+            .unwrap();
+        backdate(&manager, &channel_type, 60).await;
+        assert_eq!(manager.cleanup().await.unwrap(), 0);
+
+        release.send(()).unwrap();
+        done.await.unwrap();
+        assert_eq!(manager.cleanup().await.unwrap(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_eviction_never_drops_a_channel_with_new_work() {
+        let config = ChannelManagerConfig::new().with_channel_timeout(10);
+        let manager = Arc::new(StandardChannelManager::with_config(config, create_test_logger()));
+        let channel_type = ChannelType::group("racy");
+
+        // Keep making the channel look idle and sweeping it
+        let sweeper = tokio::spawn({
+            let (manager, channel_type) = (manager.clone(), channel_type.clone());
+            async move {
+                loop {
+                    if manager.has_channel(&channel_type).await.unwrap() {
+                        backdate(&manager, &channel_type, 60).await;
+                    }
+                    manager.evict_idle().await;
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+
+        // Work handed to a channel keeps it from being evicted, so the next
+        // package of the conversation finds the same actor
+        for _ in 0..200 {
+            let (release, released) = oneshot::channel::<()>();
+            let done = manager
+                .schedule(&channel_type, 1, |_| async move {
+                    let _ = released.await;
+                })
+                .await
+                .unwrap();
+            assert!(manager.has_channel(&channel_type).await.unwrap());
+            release.send(()).unwrap();
+            done.await.unwrap();
+        }
+        sweeper.abort();
+    }
+
+    #[tokio::test]
+    async fn test_sweeper_evicts_in_background() {
+        let config = ChannelManagerConfig::new().with_channel_timeout(10).with_cleanup_interval(1);
+        let manager = Arc::new(StandardChannelManager::with_config(config, create_test_logger()));
+        let mut events = manager.subscribe();
+        let channel_type = ChannelType::private("user1");
+
+        manager.get_or_create_channel(&channel_type).await.unwrap();
+        backdate(&manager, &channel_type, 60).await;
+        manager.start_sweeper();
+        assert!(manager.is_sweeping());
+
+        let event = tokio::time::timeout(Duration::from_secs(2), events.recv()).await.unwrap().unwrap();
+        assert!(matches!(
+            event,
+            EventEnum::Meta(MetaEvent::System { event_type: SystemEventType::ChannelEvicted, .. })
+        ));
+        assert_eq!(manager.channel_count().await.unwrap(), 0);
+
+        manager.stop_sweeper();
+        assert!(!manager.is_sweeping());
+    }
+
     #[tokio::test]
     async fn test_different_channel_types() {
         let logger = create_test_logger();
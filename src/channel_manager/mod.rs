@@ -1,11 +1,14 @@
 //! Channel Manager module for managing multiple channel instances
 //! 
-//! Manages dynamic creation and reuse of channels based on group_id/user_id/channel_id
+//! Manages dynamic creation and reuse of channels based on group_id/user_id/channel_id.
+//! Each channel processes its packages in order through a `ChannelActor`.
 
 pub mod types;
 pub mod traits;
 pub mod manager;
+pub mod actor;
 
 pub use types::*;
 pub use traits::*;
 pub use manager::*;
+pub use actor::*;
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use crate::channels::types::ChannelType;
 use crate::channel_manager::{ChannelStats, ChannelManagerConfig};
 use crate::errors::Result;
+use crate::events::Package;
 use std::fmt::Debug;
 use std::sync::Arc;
 
@@ -13,6 +14,11 @@ pub trait ChannelManager: Send + Sync + Debug {
     /// Get or create a channel by identifier
     async fn get_or_create_channel(&self, channel_type: &ChannelType) -> Result<Arc<dyn crate::streams::Stream>>;
     
+    /// Process packages in a channel, after the packages submitted before them
+    ///
+    /// The channel is created if it does not exist yet.
+    async fn submit(&self, channel_type: &ChannelType, packages: Vec<Package>) -> Result<Vec<Package>>;
+    
     /// Get an existing channel (returns None if not exists)
     async fn get_channel(&self, channel_type: &ChannelType) -> Result<Option<Arc<dyn crate::streams::Stream>>>;
     
@@ -60,6 +66,10 @@ mod tests {
             Err(crate::errors::LoquatError::Io("not implemented".to_string()))
         }
 
+        async fn submit(&self, _channel_type: &ChannelType, _packages: Vec<Package>) -> Result<Vec<Package>> {
+            Err(crate::errors::LoquatError::Io("not implemented".to_string()))
+        }
+
         async fn get_channel(&self, _channel_type: &ChannelType) -> Result<Option<Arc<dyn crate::streams::Stream>>> {
             Ok(None)
         }
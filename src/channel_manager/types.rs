@@ -1,8 +1,14 @@
 //! Channel manager type definitions
 
+use crate::channel_manager::{InboxOverflow, DEFAULT_OVERFLOW_LIMIT};
 use crate::channels::types::ChannelType;
+use crate::errors::Result;
+use crate::pools::{PoolConfig, PoolType, StageDefinition, StageLayout};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
 
 /// Channel information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +64,38 @@ pub struct ChannelManagerConfig {
     
     /// Cleanup interval in seconds
     pub cleanup_interval: u64,
+    
+    /// Execution settings per pool type (missing pools use the default)
+    #[serde(default)]
+    pub pool_configs: HashMap<PoolType, PoolConfig>,
+    
+    /// Custom stages of every channel stream
+    #[serde(default)]
+    pub stages: Vec<StageDefinition>,
+    
+    /// Custom stages per channel (e.g. "group:123"), replacing `stages`
+    #[serde(default)]
+    pub channel_stages: HashMap<String, Vec<StageDefinition>>,
+    
+    /// Submissions a channel's inbox holds before `inbox_overflow` applies
+    #[serde(default = "default_inbox_capacity")]
+    pub inbox_capacity: usize,
+    
+    /// What happens to submissions while a channel's inbox is full
+    #[serde(default)]
+    pub inbox_overflow: InboxOverflow,
+    
+    /// Submissions queued behind a full inbox with `InboxOverflow::Wait`
+    #[serde(default = "default_overflow_limit")]
+    pub overflow_limit: usize,
+}
+
+fn default_inbox_capacity() -> usize {
+    64
+}
+
+fn default_overflow_limit() -> usize {
+    DEFAULT_OVERFLOW_LIMIT
 }
 
 impl Default for ChannelManagerConfig {
@@ -67,6 +105,12 @@ impl Default for ChannelManagerConfig {
             channel_timeout: 300, // 5 minutes
             auto_create: true,
             cleanup_interval: 60, // 1 minute
+            pool_configs: HashMap::new(),
+            stages: Vec::new(),
+            channel_stages: HashMap::new(),
+            inbox_capacity: default_inbox_capacity(),
+            inbox_overflow: InboxOverflow::default(),
+            overflow_limit: default_overflow_limit(),
         }
     }
 }
@@ -100,6 +144,57 @@ impl ChannelManagerConfig {
         self.cleanup_interval = interval;
         self
     }
+    
+    /// Set execution settings of a pool type
+    pub fn with_pool_config(mut self, pool_type: PoolType, config: PoolConfig) -> Self {
+        self.pool_configs.insert(pool_type, config);
+        self
+    }
+    
+    /// Set the inbox capacity of every channel
+    pub fn with_inbox_capacity(mut self, capacity: usize) -> Self {
+        self.inbox_capacity = capacity;
+        self
+    }
+    
+    /// Set what happens to submissions while a channel's inbox is full
+    pub fn with_inbox_overflow(mut self, overflow: InboxOverflow) -> Self {
+        self.inbox_overflow = overflow;
+        self
+    }
+    
+    /// Set the number of submissions queued behind a full inbox
+    pub fn with_overflow_limit(mut self, limit: usize) -> Self {
+        self.overflow_limit = limit;
+        self
+    }
+    
+    /// Set the custom stages of every channel stream
+    pub fn with_stages(mut self, stages: Vec<StageDefinition>) -> Self {
+        self.stages = stages;
+        self
+    }
+    
+    /// Set the custom stages of one channel's stream
+    pub fn with_channel_stages(mut self, channel_type: &ChannelType, stages: Vec<StageDefinition>) -> Self {
+        self.channel_stages.insert(channel_type.to_string(), stages);
+        self
+    }
+    
+    /// Build the stage layout of a channel's stream
+    pub fn stage_layout(&self, channel_type: &ChannelType) -> Result<StageLayout> {
+        let stages = self.channel_stages.get(&channel_type.to_string()).unwrap_or(&self.stages);
+        StageLayout::new(stages)
+    }
+    
+    /// Check that all stage definitions are valid
+    pub fn validate_stages(&self) -> Result<()> {
+        StageLayout::new(&self.stages)?;
+        for stages in self.channel_stages.values() {
+            StageLayout::new(stages)?;
+        }
+        Ok(())
+    }
 }
 
 /// Channel statistics
@@ -116,6 +211,12 @@ pub struct ChannelStats {
     
     /// Peak channels count
     pub peak_channels: usize,
+    
+    /// Channels removed for being idle (also counted in `total_removed`)
+    pub total_evicted: usize,
+    
+    /// Packages submitted to each active channel
+    pub channel_packages: HashMap<ChannelType, u64>,
 }
 
 impl ChannelStats {
@@ -140,6 +241,78 @@ impl ChannelStats {
     }
 }
 
+/// Live channel counters behind `ChannelStats`
+///
+/// Updated with atomics, so packages are counted without taking a lock; the
+/// per-channel table is only locked when channels come and go.
+#[derive(Debug, Default)]
+pub struct ChannelCounters {
+    created: AtomicUsize,
+    removed: AtomicUsize,
+    evicted: AtomicUsize,
+    active: AtomicUsize,
+    peak: AtomicUsize,
+    packages: RwLock<HashMap<ChannelType, Arc<AtomicU64>>>,
+}
+
+impl ChannelCounters {
+    /// Create zeroed counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record channel creation
+    ///
+    /// Returns the channel's package counter.
+    pub fn record_created(&self, channel_type: &ChannelType) -> Arc<AtomicU64> {
+        self.created.fetch_add(1, Ordering::Relaxed);
+        let active = self.active.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak.fetch_max(active, Ordering::Relaxed);
+        
+        let counter = Arc::new(AtomicU64::new(0));
+        if let Ok(mut packages) = self.packages.write() {
+            packages.insert(channel_type.clone(), counter.clone());
+        }
+        counter
+    }
+    
+    /// Record channel removal (`evicted` if it was removed for being idle)
+    pub fn record_removed(&self, channel_type: &ChannelType, evicted: bool) {
+        self.removed.fetch_add(1, Ordering::Relaxed);
+        if evicted {
+            self.evicted.fetch_add(1, Ordering::Relaxed);
+        }
+        let _ = self.active.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |active| active.checked_sub(1));
+        
+        if let Ok(mut packages) = self.packages.write() {
+            packages.remove(channel_type);
+        }
+    }
+    
+    /// Take a snapshot of the counters
+    pub fn snapshot(&self) -> ChannelStats {
+        let channel_packages = self
+            .packages
+            .read()
+            .map(|packages| {
+                packages
+                    .iter()
+                    .map(|(channel_type, counter)| (channel_type.clone(), counter.load(Ordering::Relaxed)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        
+        ChannelStats {
+            total_created: self.created.load(Ordering::Relaxed),
+            total_removed: self.removed.load(Ordering::Relaxed),
+            active_channels: self.active.load(Ordering::Relaxed),
+            peak_channels: self.peak.load(Ordering::Relaxed),
+            total_evicted: self.evicted.load(Ordering::Relaxed),
+            channel_packages,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +345,9 @@ mod tests {
         assert_eq!(config.channel_timeout, 300);
         assert!(config.auto_create);
         assert_eq!(config.cleanup_interval, 60);
+        assert_eq!(config.inbox_capacity, 64);
+        assert_eq!(config.inbox_overflow, InboxOverflow::Wait);
+        assert_eq!(config.overflow_limit, DEFAULT_OVERFLOW_LIMIT);
     }
 
     #[test]
@@ -188,6 +364,22 @@ mod tests {
         assert_eq!(config.cleanup_interval, 120);
     }
 
+    #[test]
+    fn test_channel_manager_config_stages() {
+        let vip = ChannelType::group("vip");
+        let config = ChannelManagerConfig::new()
+            .with_stages(vec![StageDefinition::after("moderation", PoolType::PreProcess)])
+            .with_channel_stages(&vip, vec![]);
+
+        let layout = config.stage_layout(&ChannelType::group("other")).unwrap();
+        assert!(layout.contains(&PoolType::custom("moderation")));
+        assert_eq!(config.stage_layout(&vip).unwrap(), StageLayout::standard());
+        assert!(config.validate_stages().is_ok());
+
+        let invalid = config.with_channel_stages(&vip, vec![StageDefinition::before("input", PoolType::Output)]);
+        assert!(invalid.validate_stages().is_err());
+    }
+
     #[test]
     fn test_channel_stats() {
         let mut stats = ChannelStats::new();
@@ -204,4 +396,22 @@ mod tests {
         assert_eq!(stats.total_removed, 1);
         assert_eq!(stats.active_channels, 1);
     }
+
+    #[test]
+    fn test_channel_counters() {
+        let counters = ChannelCounters::new();
+        let (g1, g2) = (ChannelType::group("g1"), ChannelType::group("g2"));
+
+        counters.record_created(&g1).fetch_add(3, Ordering::Relaxed);
+        counters.record_created(&g2);
+        counters.record_removed(&g2, true);
+
+        let stats = counters.snapshot();
+        assert_eq!(stats.total_created, 2);
+        assert_eq!(stats.total_removed, 1);
+        assert_eq!(stats.total_evicted, 1);
+        assert_eq!(stats.active_channels, 1);
+        assert_eq!(stats.peak_channels, 2);
+        assert_eq!(stats.channel_packages, HashMap::from([(g1, 3)]));
+    }
 }
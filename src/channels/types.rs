@@ -1,5 +1,6 @@
 //! Channel type definitions
 
+use crate::events::{EventMetadata, SiteType, TargetSite};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
@@ -47,6 +48,38 @@ impl ChannelType {
             channel_id: channel_id.to_string(),
         }
     }
+
+    /// Parse the `group:<id>` / `private:<id>` / `channel:<id>` form produced by `Display`
+    pub fn parse(s: &str) -> Option<Self> {
+        let (kind, id) = s.split_once(':')?;
+        match kind {
+            "group" => Some(Self::group(id)),
+            "private" => Some(Self::private(id)),
+            "channel" => Some(Self::channel(id)),
+            _ => None,
+        }
+    }
+
+    /// Resolve the conversation of an event (priority: channel > group > user)
+    pub fn from_metadata(metadata: &EventMetadata) -> Option<Self> {
+        if let Some(channel_id) = &metadata.channel_id {
+            return Some(Self::channel(channel_id));
+        }
+        if let Some(group_id) = &metadata.group_id {
+            return Some(Self::group(group_id));
+        }
+        metadata.user_id.as_deref().map(Self::private)
+    }
+
+    /// Get the target site addressing this channel
+    pub fn target_site(&self) -> TargetSite {
+        let site_type = match self {
+            Self::Group { group_id } => SiteType::Group(group_id.clone()),
+            Self::Private { user_id } => SiteType::User(user_id.clone()),
+            Self::Channel { channel_id } => SiteType::Channel(channel_id.clone()),
+        };
+        TargetSite::new(self.id(), site_type)
+    }
 }
 
 impl std::fmt::Display for ChannelType {
@@ -70,6 +103,21 @@ mod tests {
         assert_eq!(ct.to_string(), "group:123456");
     }
 
+    #[test]
+    fn test_channel_type_from_metadata() {
+        let metadata = EventMetadata::new("test")
+            .with_user_id("u1")
+            .with_group_id("g1")
+            .with_channel_id("c1");
+        let ct = ChannelType::from_metadata(&metadata).unwrap();
+        assert_eq!(ct, ChannelType::channel("c1"));
+        assert_eq!(ct.target_site().site_type, SiteType::Channel("c1".to_string()));
+
+        let metadata = EventMetadata::new("test").with_user_id("u1");
+        assert_eq!(ChannelType::from_metadata(&metadata), Some(ChannelType::private("u1")));
+        assert!(ChannelType::from_metadata(&EventMetadata::new("test")).is_none());
+    }
+
     #[test]
     fn test_channel_type_private() {
         let ct = ChannelType::private("user123");
@@ -354,12 +354,35 @@ pub struct EngineConfig {
     pub auto_create_channels: bool,
     /// Enable auto-initialization
     pub auto_initialize: bool,
+    /// Capacity of the adapter-to-engine ingestion queue
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: u64,
+    /// Maximum number of accepted packages being processed at once
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: u64,
+}
+
+fn default_queue_capacity() -> u64 {
+    1024
+}
+
+fn default_max_in_flight() -> u64 {
+    256
 }
 
 impl Validate for EngineConfig {
     fn validate(&self) -> Result<()> {
-        // EngineConfig doesn't have strict validation requirements
-        // All fields are boolean flags that can be any combination
+        // Boolean flags can be any combination, but the queue must hold at least one package
+        if self.queue_capacity == 0 {
+            return Err(ConfigError::ValidationError(
+                "EngineConfig: queue_capacity must be greater than 0".to_string()
+            ).into());
+        }
+        if self.max_in_flight == 0 {
+            return Err(ConfigError::ValidationError(
+                "EngineConfig: max_in_flight must be greater than 0".to_string()
+            ).into());
+        }
         Ok(())
     }
 }
@@ -370,6 +393,8 @@ impl Default for EngineConfig {
             auto_route: true,
             auto_create_channels: true,
             auto_initialize: true,
+            queue_capacity: default_queue_capacity(),
+            max_in_flight: default_max_in_flight(),
         }
     }
 }
@@ -594,6 +619,8 @@ impl LoquatConfig {
         merge_bool(&mut self.engine.auto_route, other.engine.auto_route, engine_default.auto_route);
         merge_bool(&mut self.engine.auto_create_channels, other.engine.auto_create_channels, engine_default.auto_create_channels);
         merge_bool(&mut self.engine.auto_initialize, other.engine.auto_initialize, engine_default.auto_initialize);
+        merge_u64(&mut self.engine.queue_capacity, other.engine.queue_capacity, engine_default.queue_capacity);
+        merge_u64(&mut self.engine.max_in_flight, other.engine.max_in_flight, engine_default.max_in_flight);
         
         // Merge web config
         let web_default = WebConfig::default();
@@ -0,0 +1,269 @@
+//! Outbound dispatcher - delivers replies of processed packages to adapters
+//!
+//! Output workers attach `OutboundMessage`s to a package; once the package
+//! has left the PostOutput pool the engine hands it to the dispatcher, which
+//! sends every message to the adapter selected by the route target.
+
+use crate::adapters::{AdapterManager, Message, Target};
+use crate::channels::types::ChannelType;
+use crate::events::{EventEnum, Package};
+use crate::logging::traits::{LogContext, LogLevel, Logger};
+use crate::routers::types::RouteTarget;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Key of the outbound message list in `Package::extra`
+pub const OUTBOUND_KEY: &str = "outbound";
+
+/// A message to deliver after the package has been processed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboundMessage {
+    /// Adapter to send through, overriding the route target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapter_id: Option<String>,
+    /// Bot account to send from (the `self_id` of the event being answered)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub self_id: Option<String>,
+    /// Message target
+    pub target: Target,
+    /// Message content
+    pub message: Message,
+}
+
+impl OutboundMessage {
+    /// Create a new outbound message
+    pub fn new(target: Target, message: Message) -> Self {
+        Self {
+            adapter_id: None,
+            self_id: None,
+            target,
+            message,
+        }
+    }
+
+    /// Create a reply to the conversation an event came from
+    ///
+    /// The reply is sent from the account that received the event. Returns
+    /// `None` if the event carries no channel, group or user.
+    pub fn reply_to(event: &EventEnum, message: Message) -> Option<Self> {
+        let target = match ChannelType::from_metadata(event.metadata())? {
+            ChannelType::Channel { channel_id } => Target::Channel { channel_id },
+            ChannelType::Group { group_id } => Target::Group { group_id },
+            ChannelType::Private { user_id } => Target::User { user_id },
+        };
+        let mut reply = Self::new(target, message);
+        reply.self_id = event.self_id().map(|id| id.to_string());
+        Some(reply)
+    }
+
+    /// Send through a specific adapter
+    pub fn with_adapter(mut self, adapter_id: &str) -> Self {
+        self.adapter_id = Some(adapter_id.to_string());
+        self
+    }
+
+    /// Send from a specific bot account
+    pub fn with_self_id(mut self, self_id: &str) -> Self {
+        self.self_id = Some(self_id.to_string());
+        self
+    }
+}
+
+/// Attach an outbound message to a package
+pub fn push_outbound(package: &mut Package, outbound: OutboundMessage) {
+    if !package.extra.is_object() {
+        package.extra = serde_json::json!({});
+    }
+    let Ok(value) = serde_json::to_value(outbound) else {
+        return;
+    };
+    match package.extra.get_mut(OUTBOUND_KEY).and_then(|v| v.as_array_mut()) {
+        Some(list) => list.push(value),
+        None => package.extra[OUTBOUND_KEY] = serde_json::json!([value]),
+    }
+}
+
+/// Get the outbound messages attached to a package
+///
+/// Entries that fail to deserialize are skipped.
+pub fn outbound_messages(package: &Package) -> Vec<OutboundMessage> {
+    package.extra[OUTBOUND_KEY]
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .filter_map(|v| serde_json::from_value(v.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Sends outbound messages of processed packages through the adapter manager
+///
+/// Delivered messages are counted in the statistics of the adapter that
+/// sent them.
+pub struct OutboundDispatcher {
+    adapter_manager: Arc<AdapterManager>,
+    logger: Arc<dyn Logger>,
+}
+
+impl std::fmt::Debug for OutboundDispatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutboundDispatcher").finish_non_exhaustive()
+    }
+}
+
+impl OutboundDispatcher {
+    /// Create a new dispatcher
+    pub fn new(adapter_manager: Arc<AdapterManager>, logger: Arc<dyn Logger>) -> Self {
+        Self {
+            adapter_manager,
+            logger,
+        }
+    }
+
+    /// Resolve the adapters a message is sent through
+    ///
+    /// Priority: the message's own adapter, then the route target, then the
+    /// adapter the package's events came from.
+    async fn resolve_adapters(
+        &self,
+        outbound: &OutboundMessage,
+        package: &Package,
+        route_target: &RouteTarget,
+    ) -> Vec<String> {
+        if let Some(adapter_id) = &outbound.adapter_id {
+            return vec![adapter_id.clone()];
+        }
+        match route_target {
+            RouteTarget::Adapter(adapter_id) => vec![adapter_id.clone()],
+            RouteTarget::Broadcast => self
+                .adapter_manager
+                .list_adapters()
+                .await
+                .iter()
+                .filter(|adapter| adapter.is_running())
+                .map(|adapter| adapter.adapter_id().to_string())
+                .collect(),
+            RouteTarget::None => package
+                .events()
+                .find_map(|event| event.adapter_id())
+                .map(|id| vec![id.to_string()])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Deliver the outbound messages of a package
+    ///
+    /// Returns the number of successful sends. Failures are logged.
+    pub async fn dispatch(&self, package: &Package, route_target: &RouteTarget) -> usize {
+        let mut sent = 0;
+        for outbound in outbound_messages(package) {
+            let adapters = self.resolve_adapters(&outbound, package, route_target).await;
+            if adapters.is_empty() {
+                let context = LogContext::new().with_component("OutboundDispatcher");
+                self.logger.log(
+                    LogLevel::Warn,
+                    &format!("No adapter to deliver reply of package {}", package.package_id),
+                    &context,
+                );
+                continue;
+            }
+
+            for adapter_id in adapters {
+                let result = self.adapter_manager
+                    .send_message_as(
+                        &adapter_id,
+                        outbound.self_id.as_deref(),
+                        outbound.target.clone(),
+                        outbound.message.clone(),
+                    )
+                    .await;
+
+                match result {
+                    Ok(_) => sent += 1,
+                    Err(e) => {
+                        let context = LogContext::new().with_component("OutboundDispatcher");
+                        self.logger.log(
+                            LogLevel::Error,
+                            &format!("Failed to send reply of package {} via {}: {}", package.package_id, adapter_id, e),
+                            &context,
+                        );
+                    }
+                }
+            }
+        }
+        sent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{AdapterConfig, AdapterManagerConfig, EchoAdapter};
+    use crate::events::{EventMetadata, MessageEvent};
+
+    fn create_test_logger() -> Arc<dyn crate::logging::Logger> {
+        let formatter = Arc::new(crate::logging::formatters::JsonFormatter::new());
+        let writer = Arc::new(crate::logging::writers::ConsoleWriter::new());
+        Arc::new(crate::logging::StructuredLogger::new(formatter, writer))
+    }
+
+    fn text(content: &str) -> Message {
+        Message::Text { content: content.to_string() }
+    }
+
+    #[test]
+    fn test_outbound_roundtrip() {
+        let event = EventEnum::Message(MessageEvent::Text {
+            text: "hi".to_string(),
+            metadata: EventMetadata::new("test").with_user_id("u1").with_group_id("g1").with_self_id("10000"),
+        });
+        let reply = OutboundMessage::reply_to(&event, text("hello")).unwrap();
+        assert_eq!(reply.target, Target::Group { group_id: "g1".to_string() });
+        assert_eq!(reply.self_id.as_deref(), Some("10000"));
+
+        let event = EventEnum::Message(MessageEvent::Text {
+            text: "hi".to_string(),
+            metadata: EventMetadata::new("test").with_user_id("u1").with_channel_id("c1"),
+        });
+        let channel_reply = OutboundMessage::reply_to(&event, text("hello")).unwrap();
+        assert_eq!(channel_reply.target, Target::Channel { channel_id: "c1".to_string() });
+
+        let mut package = Package::new();
+        push_outbound(&mut package, reply.clone());
+        push_outbound(&mut package, OutboundMessage::new(Target::User { user_id: "u2".to_string() }, text("dm")).with_adapter("a2"));
+
+        let messages = outbound_messages(&package);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0], reply);
+        assert_eq!(messages[1].adapter_id.as_deref(), Some("a2"));
+        assert!(outbound_messages(&Package::new()).is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dispatch_resolves_adapters() {
+        let manager = Arc::new(AdapterManager::new(AdapterManagerConfig::default(), create_test_logger()));
+        let adapter = EchoAdapter::new(AdapterConfig::new("echo", "echo-1", "echo://"));
+        manager.add_adapter(Box::new(adapter)).await.unwrap();
+        let dispatcher = OutboundDispatcher::new(manager.clone(), create_test_logger());
+
+        let event = EventEnum::Message(MessageEvent::Text {
+            text: "hi".to_string(),
+            metadata: EventMetadata::new("test").with_user_id("u1").with_adapter_id("echo-1"),
+        });
+        let mut package = crate::engine::ingestion::package_event("other-1", event);
+        push_outbound(&mut package, OutboundMessage::new(Target::User { user_id: "u1".to_string() }, text("hi")));
+
+        // Adapter not started yet, the send fails
+        assert_eq!(dispatcher.dispatch(&package, &RouteTarget::None).await, 0);
+
+        manager.start_all().await.unwrap();
+        assert_eq!(dispatcher.dispatch(&package, &RouteTarget::None).await, 1);
+        assert_eq!(dispatcher.dispatch(&package, &RouteTarget::Broadcast).await, 1);
+        assert_eq!(dispatcher.dispatch(&package, &RouteTarget::Adapter("missing".to_string())).await, 0);
+
+        // The adapter counts what it delivered
+        let adapter = manager.get_adapter("echo-1").await.unwrap();
+        assert_eq!(adapter.statistics().messages_sent, 2);
+    }
+}
@@ -1,25 +1,32 @@
 //! Standard Loquat Engine implementation
 
-use crate::channel_manager::{StandardChannelManager, ChannelManager as _};
+use crate::channel_manager::{ChannelManagerConfig, StandardChannelManager, ChannelManager as _};
 use crate::channels::types::ChannelType;
 use crate::engine::types::{EngineConfig, EngineStats, EngineState, ProcessingContext, EngineStatus};
+use crate::engine::dispatch::OutboundDispatcher;
 use crate::engine::traits::Engine;
-use crate::errors::{LoquatError, Result};
+use crate::errors::{ChannelError, LoquatError, Result};
 use crate::events::Package;
 use crate::logging::traits::{LogContext, LogLevel, Logger};
-use crate::routers::{Router, StandardRouter};
+use crate::routers::{RouteTarget, Router, StandardRouter};
 use crate::streams::Stream;
+use crate::pools::{PoolMonitor, PoolType};
+use crate::workers::{WorkerRegistration, WorkerRegistry, WorkerScope};
 use async_trait::async_trait;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::oneshot;
 
 /// Standard Loquat Engine - core coordinator
 #[derive(Clone)]
 pub struct StandardEngine {
     config: EngineConfig,
-    stats: EngineStats,
+    stats: Arc<std::sync::RwLock<EngineStats>>,
     state: Arc<tokio::sync::RwLock<EngineState>>,
     router: Arc<StandardRouter>,
     channel_manager: Arc<StandardChannelManager>,
+    /// Stream shared by packages that belong to no channel, built on first use
+    default_stream: Arc<OnceLock<Arc<dyn Stream>>>,
+    dispatcher: Option<Arc<OutboundDispatcher>>,
     logger: Arc<dyn Logger>,
 }
 
@@ -29,6 +36,7 @@ impl std::fmt::Debug for StandardEngine {
             .field("config", &self.config)
             .field("stats", &self.stats)
             .field("state", &self.state)
+            .field("dispatcher", &self.dispatcher)
             .finish()
     }
 }
@@ -38,13 +46,15 @@ impl StandardEngine {
         let logger_clone = logger.clone();
         Self {
             config: EngineConfig::new(),
-            stats: EngineStats::new(),
+            stats: Arc::new(std::sync::RwLock::new(EngineStats::new())),
             state: Arc::new(tokio::sync::RwLock::new(EngineState {
                 status: EngineStatus::Stopped,
                 last_error: None,
             })),
             router: Arc::new(StandardRouter::new(logger_clone.clone())),
             channel_manager: Arc::new(StandardChannelManager::new(logger_clone)),
+            default_stream: Arc::new(OnceLock::new()),
+            dispatcher: None,
             logger,
         }
     }
@@ -53,23 +63,91 @@ impl StandardEngine {
         let logger_clone = logger.clone();
         Self {
             config,
-            stats: EngineStats::new(),
+            stats: Arc::new(std::sync::RwLock::new(EngineStats::new())),
             state: Arc::new(tokio::sync::RwLock::new(EngineState {
                 status: EngineStatus::Stopped,
                 last_error: None,
             })),
             router: Arc::new(StandardRouter::new(logger_clone.clone())),
             channel_manager: Arc::new(StandardChannelManager::new(logger_clone)),
+            default_stream: Arc::new(OnceLock::new()),
+            dispatcher: None,
             logger,
         }
     }
     
+    /// Deliver replies of processed packages through `dispatcher`
+    pub fn with_dispatcher(mut self, dispatcher: Arc<OutboundDispatcher>) -> Self {
+        self.dispatcher = Some(dispatcher);
+        self
+    }
+    
+    /// Run the workers of `registry` in every channel of this engine
+    pub fn with_worker_registry(mut self, registry: Arc<WorkerRegistry>) -> Self {
+        self.channel_manager = Arc::new(
+            StandardChannelManager::with_config(self.channel_manager.config().clone(), self.logger.clone())
+                .with_worker_registry(registry)
+                .with_pool_monitor(self.pool_monitor()),
+        );
+        self.default_stream = Arc::new(OnceLock::new());
+        self
+    }
+    
+    /// Create channel streams with `config` (pool settings, custom stages, limits)
+    pub fn with_channel_manager_config(mut self, config: ChannelManagerConfig) -> Result<Self> {
+        config.validate_stages()?;
+        self.channel_manager = Arc::new(
+            StandardChannelManager::with_config(config, self.logger.clone())
+                .with_worker_registry(self.worker_registry())
+                .with_pool_monitor(self.pool_monitor()),
+        );
+        self.default_stream = Arc::new(OnceLock::new());
+        Ok(self)
+    }
+    
+    /// Register a third-party worker, if its pool accepts them
+    pub fn register_third_party_worker(
+        &self,
+        pool_type: PoolType,
+        scope: WorkerScope,
+        registration: WorkerRegistration,
+    ) -> Result<()> {
+        self.channel_manager.register_third_party_worker(pool_type, scope, registration)
+    }
+    
+    /// Get the worker registry shared by all channels
+    ///
+    /// Workers can be registered, removed and re-prioritized while packages
+    /// are flowing; existing channels pick the changes up on their next batch.
+    pub fn worker_registry(&self) -> Arc<WorkerRegistry> {
+        self.channel_manager.worker_registry().clone()
+    }
+    
+    /// Get the pool metrics and quarantined packages of all channels
+    pub fn pool_monitor(&self) -> Arc<PoolMonitor> {
+        self.channel_manager.pool_monitor().clone()
+    }
+    
+    /// Get the manager of this engine's channels (stats, eviction events)
+    pub fn channel_manager(&self) -> Arc<StandardChannelManager> {
+        self.channel_manager.clone()
+    }
+    
+    /// Update the statistics shared by all clones of this engine
+    pub fn update_stats<F: FnOnce(&mut EngineStats)>(&self, update: F) {
+        if let Ok(mut stats) = self.stats.write() {
+            update(&mut stats);
+        }
+    }
+    
     async fn get_processing_context(&self, package: &Package) -> Result<ProcessingContext> {
         let mut context = ProcessingContext::new();
+        let mut route_channel = None;
         
         if self.config.auto_route {
             let route_result = self.router.route_package(package).await;
             context.route_target = Some(route_result.state.adapter_target.clone());
+            route_channel = route_result.state.channel_type.clone();
             
             let message = format!(
                 "Routed package {} to {:?}",
@@ -83,42 +161,74 @@ impl StandardEngine {
         }
         
         if self.config.auto_create_channels {
-            if let Some(channel_type) = self.extract_channel_type(&package.package_id) {
-                context.channel_type = Some(channel_type);
-            }
+            context.channel_type = self.resolve_channel_type(package, route_channel.as_deref());
         }
         
         Ok(context)
     }
     
-    fn extract_channel_type(&self, adapter_id: &str) -> Option<ChannelType> {
-        if adapter_id.starts_with("group:") {
-            let id = adapter_id.trim_start_matches("group:");
-            return Some(ChannelType::group(id));
-        }
-        if adapter_id.starts_with("private:") {
-            let id = adapter_id.trim_start_matches("private:");
-            return Some(ChannelType::private(id));
-        }
-        if adapter_id.starts_with("channel:") {
-            let id = adapter_id.trim_start_matches("channel:");
-            return Some(ChannelType::channel(id));
-        }
-        None
+    /// Resolve the conversation a package belongs to
+    ///
+    /// Priority: the router's channel type, then the metadata of the first
+    /// event that identifies a conversation, then the channel recorded by
+    /// the ingestion bus.
+    fn resolve_channel_type(&self, package: &Package, route_channel: Option<&str>) -> Option<ChannelType> {
+        route_channel
+            .and_then(|channel| self.extract_channel_type(channel))
+            .or_else(|| package.events().find_map(|event| ChannelType::from_metadata(event.metadata())))
+            .or_else(|| package.extra["channel"].as_str().and_then(|channel| self.extract_channel_type(channel)))
     }
     
-    async fn process_pipeline(&self, package: &Package, context: &ProcessingContext) -> Result<Package> {
-        let mut stream: Arc<dyn Stream> = {
-            Arc::new(crate::streams::StandardStream::new(
-                "default".to_string(),
-                ChannelType::group("default"),
-                self.logger.clone(),
-            ))
-        };
+    fn extract_channel_type(&self, channel: &str) -> Option<ChannelType> {
+        ChannelType::parse(channel)
+    }
+    
+    /// Stream for packages that belong to no channel
+    fn default_stream(&self) -> Arc<dyn Stream> {
+        self.default_stream
+            .get_or_init(|| {
+                let default_channel = ChannelType::group("default");
+                let layout = self.channel_manager.config().stage_layout(&default_channel).unwrap_or_default();
+                Arc::new(crate::streams::StandardStream::with_registry(
+                    "default".to_string(),
+                    default_channel,
+                    self.worker_registry(),
+                    &self.channel_manager.config().pool_configs,
+                    Arc::new(layout),
+                    self.pool_monitor(),
+                    self.logger.clone(),
+                ))
+            })
+            .clone()
+    }
+    
+    /// Accept a package for processing
+    ///
+    /// The package is routed and, if it belongs to a channel, put in that
+    /// channel's inbox: packages of one channel are processed and their
+    /// replies delivered in the order they were accepted, while channels
+    /// run in parallel. Wait for the result with `complete`.
+    pub async fn accept(&self, package: Package) -> Result<PendingPackage> {
+        // Check if engine is running before processing
+        {
+            let state = self.state.read().await;
+            if !state.status.is_running() {
+                return Err(LoquatError::Unknown("Engine is not running".to_string()));
+            }
+        }
         
-        if let Some(channel_type) = &context.channel_type {
-            match self.channel_manager.get_or_create_channel(channel_type).await {
-                Ok(s) => stream = s,
+        let started_at = std::time::Instant::now();
+        let context = self.get_processing_context(&package).await?;
+        
+        if let Some(channel_type) = context.channel_type.clone() {
+            let engine = self.clone();
+            let work = |stream: Arc<dyn Stream>| {
+                let (package, context) = (package.clone(), context.clone());
+                async move { engine.run(stream, package, context, started_at).await }
+            };
+            match self.channel_manager.schedule(&channel_type, 1, work).await {
+                Ok(result) => return Ok(PendingPackage(Pending::Queued { channel_type, result })),
+                Err(e @ LoquatError::Channel(ChannelError::InboxFull(_))) => return Err(e),
                 Err(e) => {
                     let message = format!("Failed to get channel for {:?}: {}", channel_type, e);
                     let mut log_context = LogContext::new();
@@ -130,18 +240,68 @@ impl StandardEngine {
             }
         }
         
+        Ok(PendingPackage(Pending::Direct { package, context, started_at }))
+    }
+    
+    /// Wait until an accepted package is processed
+    pub async fn complete(&self, pending: PendingPackage) -> Result<Package> {
+        match pending.0 {
+            Pending::Queued { channel_type, result } => result
+                .await
+                .map_err(|_| LoquatError::from(ChannelError::Stopped(channel_type.to_string())))?,
+            Pending::Direct { package, context, started_at } => {
+                self.run(self.default_stream(), package, context, started_at).await
+            }
+        }
+    }
+    
+    /// Process a package through a stream and deliver its replies
+    ///
+    /// Replies of every package the stream returns are delivered; the first
+    /// of them is the result.
+    async fn run(
+        &self,
+        stream: Arc<dyn Stream>,
+        package: Package,
+        context: ProcessingContext,
+        started_at: std::time::Instant,
+    ) -> Result<Package> {
+        let processed = self.process_pipeline(stream.as_ref(), &package).await;
+        
+        // Outbound dispatch runs after the PostOutput pool
+        if let Some(dispatcher) = &self.dispatcher {
+            let route_target = context.route_target.clone().unwrap_or(RouteTarget::None);
+            for output in &processed {
+                dispatcher.dispatch(output, &route_target).await;
+            }
+        }
+        let result = processed.into_iter().next().unwrap_or(package);
+        
+        let duration_ms = started_at.elapsed().as_millis() as u64;
+        self.update_stats(|stats| {
+            stats.record_package(true);
+            stats.update_avg_time(duration_ms);
+        });
+        
+        Ok(result)
+    }
+    
+    /// Run a package through a stream, returning every package it produced
+    ///
+    /// Falls back to the package itself if the stream failed or returned
+    /// nothing.
+    async fn process_pipeline(&self, stream: &dyn Stream, package: &Package) -> Vec<Package> {
         match stream.process(vec![package.clone()]).await {
-            Ok(processed) => {
-                if let Some(p) = processed.into_iter().next() {
-                    let message = format!("Processed package {}", package.package_id);
-                    let mut log_context = LogContext::new();
-                    log_context.component = Some("Engine".to_string());
-                    log_context.add("package_id", package.package_id.to_string());
-                    log_context.add("event_type", "process_success");
-                    self.logger.log(LogLevel::Debug, &message, &log_context);
-                    return Ok(p);
-                }
+            Ok(processed) if !processed.is_empty() => {
+                let message = format!("Processed package {} into {} packages", package.package_id, processed.len());
+                let mut log_context = LogContext::new();
+                log_context.component = Some("Engine".to_string());
+                log_context.add("package_id", package.package_id.to_string());
+                log_context.add("event_type", "process_success");
+                self.logger.log(LogLevel::Debug, &message, &log_context);
+                return processed;
             }
+            Ok(_) => {}
             Err(e) => {
                 let message = format!("Failed to process package {:?}: {}", package.package_id, e);
                 let mut log_context = LogContext::new();
@@ -149,14 +309,33 @@ impl StandardEngine {
                 log_context.add("package_id", package.package_id.to_string());
                 log_context.add("event_type", "process_error");
                 self.logger.log(LogLevel::Error, &message, &log_context);
-                return Ok(package.clone());
             }
         }
         
-        Ok(package.clone())
+        vec![package.clone()]
     }
 }
 
+/// A package accepted by `StandardEngine::accept`, waiting to be processed
+#[derive(Debug)]
+pub struct PendingPackage(Pending);
+
+#[derive(Debug)]
+enum Pending {
+    /// Queued in the inbox of its channel
+    Queued {
+        channel_type: ChannelType,
+        result: oneshot::Receiver<Result<Package>>,
+    },
+    
+    /// Belongs to no channel; processed by `complete` itself
+    Direct {
+        package: Package,
+        context: ProcessingContext,
+        started_at: std::time::Instant,
+    },
+}
+
 #[async_trait]
 impl Engine for StandardEngine {
     fn config(&self) -> &EngineConfig {
@@ -164,7 +343,11 @@ impl Engine for StandardEngine {
     }
 
     fn stats(&self) -> EngineStats {
-        self.stats.clone()
+        let mut stats = self.stats.read().map(|stats| stats.clone()).unwrap_or_default();
+        let channels = self.channel_manager.stats();
+        stats.total_channels_created = channels.total_created;
+        stats.active_channels = channels.active_channels;
+        stats
     }
 
     fn state(&self) -> EngineState {
@@ -218,6 +401,9 @@ impl Engine for StandardEngine {
         state.status = EngineStatus::Running;
         drop(state);
         
+        // Evict idle channels in the background while running
+        self.channel_manager.start_sweeper();
+        
         self.logger.log(LogLevel::Info, "Engine started and ready to process", &log_context);
         
         Ok(())
@@ -228,6 +414,8 @@ impl Engine for StandardEngine {
         state.status = EngineStatus::Stopped;
         drop(state);
         
+        self.channel_manager.stop_sweeper();
+        
         let mut log_context = LogContext::new();
         log_context.component = Some("Engine".to_string());
         self.logger.log(LogLevel::Info, "Engine stopped", &log_context);
@@ -236,31 +424,13 @@ impl Engine for StandardEngine {
     }
 
     async fn process(&mut self, package: Package) -> Result<Package> {
-        // Check if engine is running before processing
-        {
-            let state = self.state.read().await;
-            if !state.status.is_running() {
-                return Err(LoquatError::Unknown("Engine is not running".to_string()));
-            }
-        }
-        
-        let start_time = std::time::Instant::now();
-        
-        let context = self.get_processing_context(&package).await?;
-        let result = self.process_pipeline(&package, &context).await?;
-        
-        let duration_ms = start_time.elapsed().as_millis() as u64;
-        let mut stats = self.stats.clone();
-        stats.record_package(true);
-        stats.update_avg_time(duration_ms);
-        self.stats = stats;
+        let pending = self.accept(package).await?;
         
         // Note: We do NOT change engine status here
         // Engine status is controlled by start/stop, not by individual package processing
-        
-        Ok(result)
+        self.complete(pending).await
     }
-
+    
     async fn get_channel(&self, channel_type: &ChannelType) -> Result<Option<Arc<dyn Stream>>> {
         self.channel_manager.get_channel(channel_type).await
     }
@@ -304,9 +474,11 @@ mod tests {
         
         assert!(engine.start().await.is_ok());
         assert!(engine.is_running());
+        assert!(engine.channel_manager().is_sweeping());
         
         assert!(engine.stop().await.is_ok());
         assert!(!engine.is_running());
+        assert!(!engine.channel_manager().is_sweeping());
     }
 
     #[tokio::test]
@@ -319,4 +491,244 @@ mod tests {
         assert_eq!(engine.extract_channel_type("channel:test_channel"), Some(ChannelType::channel("test_channel")));
         assert!(engine.extract_channel_type("unknown").is_none());
     }
+
+    #[tokio::test]
+    async fn test_engine_reuses_conversation_channels() {
+        use crate::channel_manager::ChannelManager;
+        use crate::events::{Block, BlockType, EventEnum, EventMetadata, Group, MessageEvent};
+
+        let text_package = |metadata: EventMetadata| {
+            let event = EventEnum::Message(MessageEvent::Text { text: "hi".to_string(), metadata });
+            Package::new().with_block(Block::new(BlockType::Message).with_group(Group::new("g").with_event(event)))
+        };
+
+        let mut engine = StandardEngine::new(create_test_logger());
+        engine.start().await.unwrap();
+        engine.process(text_package(EventMetadata::new("test").with_user_id("u1").with_group_id("g1"))).await.unwrap();
+        engine.process(text_package(EventMetadata::new("test").with_user_id("u2").with_group_id("g1"))).await.unwrap();
+        engine.process(text_package(EventMetadata::new("test").with_user_id("u1"))).await.unwrap();
+
+        assert!(engine.get_channel(&ChannelType::group("g1")).await.unwrap().is_some());
+        assert!(engine.get_channel(&ChannelType::private("u1")).await.unwrap().is_some());
+        assert_eq!(engine.channel_manager.channel_count().await.unwrap(), 2);
+        assert_eq!(engine.stats().active_channels, 2);
+        assert_eq!(engine.channel_manager.stats().channel_packages.get(&ChannelType::group("g1")), Some(&2));
+
+        // Without routing the channel still comes from the event metadata
+        let config = EngineConfig::new().with_auto_route(false);
+        let engine = StandardEngine::with_config(config, create_test_logger());
+        let package = text_package(EventMetadata::new("test").with_user_id("u1").with_channel_id("c1"));
+        let context = engine.get_processing_context(&package).await.unwrap();
+        assert_eq!(context.channel_type, Some(ChannelType::channel("c1")));
+
+        let package = Package::new().with_extra(serde_json::json!({"channel": "group:g2"}));
+        let context = engine.get_processing_context(&package).await.unwrap();
+        assert_eq!(context.channel_type, Some(ChannelType::group("g2")));
+    }
+
+    #[tokio::test]
+    async fn test_engine_runtime_worker_registration() {
+        use crate::events::{SiteType, TargetSite};
+        use crate::pools::PoolType;
+        use crate::workers::testing::{TestWorker, WorkerCalls};
+        use crate::workers::WorkerScope;
+
+        let package = || {
+            Package::new()
+                .with_extra(serde_json::json!({"channel": "group:g1"}))
+                .with_target_site(TargetSite::new("g1", SiteType::Group("g1".to_string())))
+        };
+        let config = EngineConfig::new().with_auto_route(false);
+        let mut engine = StandardEngine::with_config(config, create_test_logger());
+        engine.start().await.unwrap();
+
+        // The channel exists before any worker is registered
+        engine.process(package()).await.unwrap();
+        assert!(engine.get_channel(&ChannelType::group("g1")).await.unwrap().is_some());
+
+        let calls = WorkerCalls::new();
+        let registry = engine.worker_registry();
+        let worker = TestWorker::new("global").with_calls(&calls);
+        registry.register(PoolType::Process, WorkerScope::Global, worker.register(1)).unwrap();
+        let worker = TestWorker::new("scoped").with_calls(&calls);
+        let scope = WorkerScope::Channel(ChannelType::group("g2"));
+        registry.register(PoolType::Process, scope.clone(), worker.register(0)).unwrap();
+
+        engine.process(package()).await.unwrap();
+        assert_eq!(calls.workers(), vec!["global"]);
+
+        // Moving the scoped worker to g1 and re-prioritizing it puts it in front
+        registry.unregister(&PoolType::Process, &scope, "scoped").unwrap();
+        let worker = TestWorker::new("scoped").with_calls(&calls);
+        let scope = WorkerScope::Channel(ChannelType::group("g1"));
+        registry.register(PoolType::Process, scope.clone(), worker.register(2)).unwrap();
+        registry.set_priority(&PoolType::Process, &scope, "scoped", 0).unwrap();
+
+        engine.process(package()).await.unwrap();
+        assert_eq!(calls.workers(), vec!["global", "scoped"]);
+    }
+
+    #[tokio::test]
+    async fn test_engine_custom_stages() {
+        use crate::pools::StageDefinition;
+        use crate::workers::testing::{TestWorker, WorkerCalls};
+        use crate::workers::WorkerType;
+        use crate::events::{SiteType, TargetSite};
+
+        let config = ChannelManagerConfig::new().with_stages(vec![
+            StageDefinition::after("moderation", PoolType::PreProcess).with_third_party(true),
+            StageDefinition::before("audit", PoolType::Output),
+        ]);
+        let mut engine = StandardEngine::with_config(EngineConfig::new().with_auto_route(false), create_test_logger())
+            .with_channel_manager_config(config)
+            .unwrap();
+        engine.start().await.unwrap();
+
+        let calls = WorkerCalls::new();
+        let registration = |name: &str| TestWorker::new(name).with_calls(&calls).register(0);
+        engine.worker_registry().register(PoolType::Process, WorkerScope::Global, registration("process")).unwrap();
+        let moderation = WorkerType::Custom("moderation".to_string()).pool_type();
+        engine
+            .register_third_party_worker(moderation, WorkerScope::Global, registration("moderation"))
+            .unwrap();
+        engine
+            .register_third_party_worker(PoolType::PreProcess, WorkerScope::Global, registration("pre_process"))
+            .unwrap();
+
+        // Third-party workers are kept out of stages that do not allow them
+        assert!(engine
+            .register_third_party_worker(PoolType::custom("audit"), WorkerScope::Global, registration("audit"))
+            .is_err());
+        assert!(engine
+            .register_third_party_worker(PoolType::PostProcess, WorkerScope::Global, registration("post"))
+            .is_err());
+
+        let package = Package::new()
+            .with_extra(serde_json::json!({"channel": "group:g1"}))
+            .with_target_site(TargetSite::new("g1", SiteType::Group("g1".to_string())));
+        engine.process(package).await.unwrap();
+        assert_eq!(calls.workers(), vec!["pre_process", "moderation", "process"]);
+        assert_eq!(engine.pool_monitor().metrics(&PoolType::custom("moderation")).batches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_engine_orders_packages_per_channel() {
+        use crate::workers::testing::TestWorker;
+        use crate::events::{SiteType, TargetSite};
+
+        let mut engine = StandardEngine::with_config(EngineConfig::new().with_auto_route(false), create_test_logger());
+        engine.start().await.unwrap();
+        let worker = TestWorker::new("sleep");
+        let finished = worker.calls();
+        engine.worker_registry().register(PoolType::Process, WorkerScope::Global, worker.register(0)).unwrap();
+
+        let package = |name: &str, group: &str, delay: u64| {
+            Package::new()
+                .with_extra(serde_json::json!({"channel": format!("group:{}", group), "name": name, "delay": delay}))
+                .with_target_site(TargetSite::new(group, SiteType::Group(group.to_string())))
+        };
+        let slow = engine.accept(package("g1-first", "g1", 60)).await.unwrap();
+        let fast = engine.accept(package("g1-second", "g1", 0)).await.unwrap();
+        let other = engine.accept(package("g2-first", "g2", 0)).await.unwrap();
+
+        let (slow, fast, other) = tokio::join!(engine.complete(slow), engine.complete(fast), engine.complete(other));
+        assert!(slow.is_ok() && fast.is_ok() && other.is_ok());
+
+        // g1 keeps its order while g2 does not wait for g1
+        assert_eq!(finished.field("name").concat(), vec!["g2-first", "g1-first", "g1-second"]);
+        assert_eq!(engine.stats().successful_packages, 3);
+    }
+
+    #[tokio::test]
+    async fn test_engine_full_channel_does_not_block_others() {
+        use crate::channel_manager::ChannelManagerConfig;
+        use crate::workers::testing::TestWorker;
+        use crate::events::{SiteType, TargetSite};
+
+        let config = ChannelManagerConfig::new().with_inbox_capacity(1);
+        let mut engine = StandardEngine::with_config(EngineConfig::new().with_auto_route(false), create_test_logger())
+            .with_channel_manager_config(config)
+            .unwrap();
+        engine.start().await.unwrap();
+        let worker = TestWorker::new("sleep");
+        let finished = worker.calls();
+        engine.worker_registry().register(PoolType::Process, WorkerScope::Global, worker.register(0)).unwrap();
+
+        let package = |name: &str, group: &str, delay: u64| {
+            Package::new()
+                .with_extra(serde_json::json!({"channel": format!("group:{}", group), "name": name, "delay": delay}))
+                .with_target_site(TargetSite::new(group, SiteType::Group(group.to_string())))
+        };
+
+        // g1 runs one slow package, holds one in its inbox and one behind it
+        let started = std::time::Instant::now();
+        let mut blocked = Vec::new();
+        for name in ["g1-a", "g1-b", "g1-c"] {
+            blocked.push(engine.accept(package(name, "g1", 100)).await.unwrap());
+        }
+        let other = engine.accept(package("g2-a", "g2", 0)).await.unwrap();
+        engine.complete(other).await.unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_millis(100));
+
+        for pending in blocked {
+            engine.complete(pending).await.unwrap();
+        }
+        assert_eq!(finished.field("name").concat(), vec!["g2-a", "g1-a", "g1-b", "g1-c"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_engine_dispatches_replies() {
+        use crate::adapters::{AdapterConfig, AdapterManager, AdapterManagerConfig, EchoAdapter, Message, Target};
+        use crate::engine::dispatch::{push_outbound, OutboundMessage};
+        use crate::engine::ingestion::package_event;
+        use crate::events::{EventEnum, EventMetadata, MessageEvent};
+
+        let manager = Arc::new(AdapterManager::new(AdapterManagerConfig::default(), create_test_logger()));
+        manager.add_adapter(Box::new(EchoAdapter::new(AdapterConfig::new("echo", "echo-1", "echo://")))).await.unwrap();
+        manager.start_all().await.unwrap();
+
+        let dispatcher = Arc::new(OutboundDispatcher::new(manager.clone(), create_test_logger()));
+        let mut engine = StandardEngine::new(create_test_logger()).with_dispatcher(dispatcher);
+        engine.start().await.unwrap();
+
+        let event = EventEnum::Message(MessageEvent::Text {
+            text: "ping".to_string(),
+            metadata: EventMetadata::new("test").with_user_id("u1"),
+        });
+        let mut package = package_event("echo-1", event);
+        let target = Target::User { user_id: "u1".to_string() };
+        push_outbound(&mut package, OutboundMessage::new(target, Message::Text { content: "pong".to_string() }));
+
+        assert!(engine.process(package).await.is_ok());
+        assert_eq!(manager.get_adapter("echo-1").await.unwrap().statistics().messages_sent, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_engine_dispatches_replies_of_every_output() {
+        use crate::adapters::{AdapterConfig, AdapterManager, AdapterManagerConfig, EchoAdapter, Message, Target};
+        use crate::engine::dispatch::{push_outbound, OutboundMessage};
+        use crate::events::TargetSite;
+        use crate::workers::testing::TestWorker;
+        use crate::workers::{WorkerResult, WorkerScope};
+
+        let manager = Arc::new(AdapterManager::new(AdapterManagerConfig::default(), create_test_logger()));
+        manager.add_adapter(Box::new(EchoAdapter::new(AdapterConfig::new("echo", "echo-1", "echo://")))).await.unwrap();
+        manager.start_all().await.unwrap();
+
+        let dispatcher = Arc::new(OutboundDispatcher::new(manager.clone(), create_test_logger()));
+        let mut engine = StandardEngine::new(create_test_logger()).with_dispatcher(dispatcher);
+        engine.start().await.unwrap();
+
+        // The worker fans the package out; only the second output carries a reply
+        let mut replied = Package::new();
+        let target = Target::User { user_id: "u1".to_string() };
+        let reply = OutboundMessage::new(target, Message::Text { content: "pong".to_string() }).with_adapter("echo-1");
+        push_outbound(&mut replied, reply);
+        let outputs = vec![Package::new(), replied];
+        let worker = TestWorker::new("fan_out").with_results(vec![WorkerResult::jump(PoolType::Output, outputs)]);
+        engine.worker_registry().register(PoolType::Process, WorkerScope::Global, worker.register(0)).unwrap();
+
+        assert!(engine.process(Package::new().with_target_site(TargetSite::group("g1"))).await.is_ok());
+        assert_eq!(manager.get_adapter("echo-1").await.unwrap().statistics().messages_sent, 1);
+    }
 }
@@ -0,0 +1,436 @@
+//! Ingestion bus - feeds adapter events into the engine
+//!
+//! Events from every attached adapter are wrapped into a Package and pushed
+//! through a bounded queue. At most `max_in_flight` accepted packages are
+//! processed at once; beyond that the queue fills up and the forwarding tasks
+//! wait. Events lagging behind in an adapter's broadcast channel are counted
+//! as dropped.
+
+use crate::adapters::AdapterManager;
+use crate::channels::types::ChannelType;
+use crate::engine::engine::StandardEngine;
+use crate::errors::{LoquatError, Result};
+use crate::events::{Block, BlockType, EventEnum, Group, Package, TargetSite};
+use crate::logging::traits::{LogContext, LogLevel, Logger};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, Mutex, Semaphore};
+use tokio::task::JoinHandle;
+
+/// Default capacity of the ingestion queue
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+/// Default number of packages processed at once
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 256;
+
+/// Ingestion bus configuration
+#[derive(Debug, Clone)]
+pub struct IngestionConfig {
+    /// Maximum number of packages waiting for the engine
+    pub queue_capacity: usize,
+
+    /// Maximum number of accepted packages being processed at once
+    pub max_in_flight: usize,
+}
+
+impl Default for IngestionConfig {
+    fn default() -> Self {
+        Self {
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+        }
+    }
+}
+
+impl IngestionConfig {
+    /// Create a new ingestion config
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set queue capacity
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity.max(1);
+        self
+    }
+
+    /// Set the number of packages processed at once
+    pub fn with_max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = max.max(1);
+        self
+    }
+}
+
+/// Wrap an adapter event into a Package
+///
+/// The package targets the conversation the event belongs to and the bot
+/// that received it. Events that do not name their adapter get `adapter_id`
+/// as their origin.
+pub fn package_event(adapter_id: &str, mut event: EventEnum) -> Package {
+    let metadata = event.metadata_mut();
+    if metadata.adapter_id.is_none() {
+        metadata.adapter_id = Some(adapter_id.to_string());
+    }
+    let block_type = match &event {
+        EventEnum::Message(_) => BlockType::Message,
+        EventEnum::Notice(_) => BlockType::Notice,
+        EventEnum::Request(_) => BlockType::Request,
+        EventEnum::Meta(_) => BlockType::Meta,
+    };
+    let channel_type = ChannelType::from_metadata(event.metadata());
+    let self_id = event.self_id().map(|id| id.to_string());
+
+    let group_id = channel_type
+        .as_ref()
+        .map(|ct| ct.to_string())
+        .unwrap_or_else(|| adapter_id.to_string());
+    let mut package = Package::new()
+        .with_block(Block::new(block_type).with_group(Group::with_type(&group_id, "ingestion").with_event(event)));
+
+    if let Some(channel_type) = &channel_type {
+        package = package.with_target_site(channel_type.target_site());
+    }
+    if let Some(self_id) = &self_id {
+        package = package.with_target_site(TargetSite::bot(self_id));
+    }
+
+    package.extra = serde_json::json!({
+        "channel": channel_type.map(|ct| ct.to_string()),
+    });
+    package
+}
+
+/// Bounded queue between adapters and the engine
+pub struct IngestionBus {
+    engine: StandardEngine,
+    sender: mpsc::Sender<Package>,
+    receiver: Mutex<Option<mpsc::Receiver<Package>>>,
+    in_flight: Arc<Semaphore>,
+    attached: Arc<Mutex<HashSet<String>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+    logger: Arc<dyn Logger>,
+}
+
+impl std::fmt::Debug for IngestionBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IngestionBus")
+            .field("queue_depth", &self.queue_depth())
+            .field("queue_capacity", &self.queue_capacity())
+            .finish()
+    }
+}
+
+impl IngestionBus {
+    /// Create a new ingestion bus feeding `engine`
+    pub fn new(engine: StandardEngine, config: IngestionConfig, logger: Arc<dyn Logger>) -> Self {
+        let (sender, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        engine.update_stats(|stats| {
+            stats.queue_capacity = sender.max_capacity();
+            stats.record_queue_depth(0);
+        });
+
+        Self {
+            engine,
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+            in_flight: Arc::new(Semaphore::new(config.max_in_flight.max(1))),
+            attached: Arc::new(Mutex::new(HashSet::new())),
+            tasks: Mutex::new(Vec::new()),
+            logger,
+        }
+    }
+
+    /// Get the number of packages waiting in the queue
+    pub fn queue_depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Get the queue capacity
+    pub fn queue_capacity(&self) -> usize {
+        self.sender.max_capacity()
+    }
+
+    /// Start processing queued packages
+    pub async fn start(&self) -> Result<()> {
+        let Some(mut receiver) = self.receiver.lock().await.take() else {
+            return Err(LoquatError::Unknown("Ingestion bus is already running".to_string()));
+        };
+
+        let engine = self.engine.clone();
+        let sender = self.sender.clone();
+        let logger = self.logger.clone();
+        let in_flight = Arc::clone(&self.in_flight);
+        let task = tokio::spawn(async move {
+            while let Some(package) = receiver.recv().await {
+                let depth = sender.max_capacity() - sender.capacity();
+                engine.update_stats(|stats| stats.record_queue_depth(depth));
+
+                // Stop taking packages while `max_in_flight` are processed,
+                // so a full queue pushes back on the adapters
+                let Ok(permit) = Arc::clone(&in_flight).acquire_owned().await else {
+                    break;
+                };
+
+                // Accepting in queue order keeps each channel's packages in
+                // order; waiting for them does not hold up other channels
+                let package_id = package.package_id.clone();
+                match engine.accept(package).await {
+                    Ok(pending) => {
+                        let (engine, logger) = (engine.clone(), logger.clone());
+                        tokio::spawn(async move {
+                            if let Err(e) = engine.complete(pending).await {
+                                report_failure(&engine, logger.as_ref(), &package_id, &e);
+                            }
+                            drop(permit);
+                        });
+                    }
+                    Err(e) => report_failure(&engine, logger.as_ref(), &package_id, &e),
+                }
+            }
+        });
+
+        self.tasks.lock().await.push(task);
+        Ok(())
+    }
+
+    /// Stop forwarding and processing
+    ///
+    /// Packages still queued are discarded.
+    pub async fn stop(&self) -> Result<()> {
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
+        self.attached.lock().await.clear();
+        Ok(())
+    }
+
+    /// Queue an event, waiting while the queue is full
+    pub async fn submit(&self, adapter_id: &str, event: EventEnum) -> Result<()> {
+        enqueue(&self.engine, &self.sender, package_event(adapter_id, event)).await
+    }
+
+    /// Forward every event published on `receiver`
+    ///
+    /// Returns false if the adapter is already attached.
+    pub async fn attach(&self, adapter_id: &str, mut receiver: broadcast::Receiver<EventEnum>) -> bool {
+        if !self.attached.lock().await.insert(adapter_id.to_string()) {
+            return false;
+        }
+
+        let adapter_id = adapter_id.to_string();
+        let engine = self.engine.clone();
+        let sender = self.sender.clone();
+        let attached = Arc::clone(&self.attached);
+        let logger = self.logger.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if enqueue(&engine, &sender, package_event(&adapter_id, event)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        engine.update_stats(|stats| stats.record_dropped(skipped as usize));
+                        let context = LogContext::new().with_component("IngestionBus");
+                        logger.log(
+                            LogLevel::Warn,
+                            &format!("Lagging behind adapter {}, dropped {} events", adapter_id, skipped),
+                            &context,
+                        );
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+
+            // Allow a reloaded adapter with the same ID to be attached again
+            attached.lock().await.remove(&adapter_id);
+        });
+
+        self.tasks.lock().await.push(task);
+        true
+    }
+
+    /// Attach every adapter loaded in `manager`
+    ///
+    /// Already attached adapters are skipped; returns the number of newly
+    /// attached adapters.
+    pub async fn attach_manager(&self, manager: &AdapterManager) -> usize {
+        let mut count = 0;
+        for adapter in manager.list_adapters().await {
+            if self.attach(adapter.adapter_id(), adapter.subscribe()).await {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Periodically attach adapters added or reloaded in `manager`
+    pub async fn watch_manager(self: &Arc<Self>, manager: Arc<AdapterManager>, interval: Duration) {
+        let bus = Arc::downgrade(self);
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(bus) = bus.upgrade() else {
+                    break;
+                };
+                bus.attach_manager(&manager).await;
+            }
+        });
+
+        self.tasks.lock().await.push(task);
+    }
+}
+
+/// Push a package into the queue and record the new depth
+async fn enqueue(engine: &StandardEngine, sender: &mpsc::Sender<Package>, package: Package) -> Result<()> {
+    sender
+        .send(package)
+        .await
+        .map_err(|_| LoquatError::Unknown("Ingestion queue is closed".to_string()))?;
+    let depth = sender.max_capacity() - sender.capacity();
+    engine.update_stats(|stats| stats.record_queue_depth(depth));
+    Ok(())
+}
+
+/// Count and log a package the engine failed to process
+fn report_failure(engine: &StandardEngine, logger: &dyn Logger, package_id: &str, error: &LoquatError) {
+    engine.update_stats(|stats| stats.record_package(false));
+    let context = LogContext::new().with_component("IngestionBus");
+    logger.log(
+        LogLevel::Error,
+        &format!("Failed to process package {}: {}", package_id, error),
+        &context,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::traits::Engine;
+    use crate::events::{EventMetadata, MessageEvent, SiteType};
+
+    fn create_test_logger() -> Arc<dyn crate::logging::Logger> {
+        let formatter = Arc::new(crate::logging::formatters::JsonFormatter::new());
+        let writer = Arc::new(crate::logging::writers::ConsoleWriter::new());
+        Arc::new(crate::logging::StructuredLogger::new(formatter, writer))
+    }
+
+    fn text_event(text: &str) -> EventEnum {
+        group_event(text, "g1")
+    }
+
+    fn group_event(text: &str, group: &str) -> EventEnum {
+        EventEnum::Message(MessageEvent::Text {
+            text: text.to_string(),
+            metadata: EventMetadata::new("test")
+                .with_user_id("u1")
+                .with_group_id(group)
+                .with_self_id("bot"),
+        })
+    }
+
+    #[test]
+    fn test_package_event() {
+        let package = package_event("adapter-1", text_event("hi"));
+
+        assert_eq!(package.blocks.len(), 1);
+        assert_eq!(package.blocks[0].block_type, BlockType::Message);
+        assert_eq!(package.blocks[0].groups[0].group_id, "group:g1");
+        assert_eq!(package.blocks[0].groups[0].events.len(), 1);
+        assert_eq!(package.target_sites[0].site_type, SiteType::Group("g1".to_string()));
+        assert_eq!(package.target_sites[1].site_type, SiteType::Bot("bot".to_string()));
+        assert_eq!(package.events().next().unwrap().adapter_id(), Some("adapter-1"));
+        assert_eq!(package.extra["channel"], "group:g1");
+
+        let event = EventEnum::Message(MessageEvent::Text {
+            text: "hi".to_string(),
+            metadata: EventMetadata::new("test").with_user_id("u1").with_guild_id("g1").with_channel_id("c1"),
+        });
+        let package = package_event("adapter-1", event);
+        assert_eq!(package.blocks[0].groups[0].group_id, "channel:c1");
+        assert_eq!(package.target_sites[0].site_type, SiteType::Channel("c1".to_string()));
+
+        // An adapter that names itself keeps its own ID
+        let event = EventEnum::Message(MessageEvent::Text {
+            text: "hi".to_string(),
+            metadata: EventMetadata::new("test").with_user_id("u1").with_adapter_id("satori-1"),
+        });
+        let package = package_event("adapter-1", event);
+        assert_eq!(package.events().next().unwrap().adapter_id(), Some("satori-1"));
+    }
+
+    #[tokio::test]
+    async fn test_ingestion_queue_depth() {
+        let engine = StandardEngine::new(create_test_logger());
+        let bus = IngestionBus::new(engine.clone(), IngestionConfig::new().with_queue_capacity(2), create_test_logger());
+        assert_eq!(engine.stats().queue_capacity, 2);
+
+        bus.submit("adapter-1", text_event("a")).await.unwrap();
+        bus.submit("adapter-1", text_event("b")).await.unwrap();
+        assert_eq!(bus.queue_depth(), 2);
+        assert_eq!(engine.stats().queue_depth, 2);
+
+        // Backpressure: a full queue makes the producer wait
+        let blocked = tokio::time::timeout(Duration::from_millis(50), bus.submit("adapter-1", text_event("c"))).await;
+        assert!(blocked.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ingestion_feeds_engine() {
+        let mut engine = StandardEngine::new(create_test_logger());
+        engine.start().await.unwrap();
+        let bus = IngestionBus::new(engine.clone(), IngestionConfig::new(), create_test_logger());
+        bus.start().await.unwrap();
+        assert!(bus.start().await.is_err());
+
+        let (sender, receiver) = broadcast::channel(16);
+        assert!(bus.attach("adapter-1", receiver).await);
+        assert!(!bus.attach("adapter-1", sender.subscribe()).await);
+
+        sender.send(text_event("hello")).unwrap();
+        sender.send(text_event("world")).unwrap();
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while engine.stats().total_packages < 2 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(engine.stats().successful_packages, 2);
+        assert_eq!(engine.stats().queue_depth, 0);
+
+        bus.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ingestion_bounds_in_flight_packages() {
+        use crate::engine::types::EngineConfig;
+        use crate::pools::PoolType;
+        use crate::workers::testing::TestWorker;
+        use crate::workers::WorkerScope;
+
+        let mut engine = StandardEngine::with_config(EngineConfig::new().with_auto_route(false), create_test_logger());
+        engine.start().await.unwrap();
+        let worker = TestWorker::new("slow").with_delay(Duration::from_millis(20));
+        let calls = worker.calls();
+        engine.worker_registry().register(PoolType::Process, WorkerScope::Global, worker.register(0)).unwrap();
+
+        let bus = IngestionBus::new(engine.clone(), IngestionConfig::new().with_max_in_flight(1), create_test_logger());
+        bus.start().await.unwrap();
+        // Channels run side by side, so only the bound keeps them apart
+        for group in ["g1", "g2", "g3"] {
+            bus.submit("adapter-1", group_event("hi", group)).await.unwrap();
+        }
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while engine.stats().total_packages < 3 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(engine.stats().successful_packages, 3);
+        assert_eq!(calls.count(), 3);
+        assert_eq!(calls.peak(), 1);
+
+        bus.stop().await.unwrap();
+    }
+}
@@ -6,11 +6,18 @@
 //! - Gets/creates Channel via ChannelManager
 //! - Processes Package via Stream
 //! - Outputs result
+//!
+//! Adapter events enter the engine through the ingestion bus, and replies
+//! leave it through the outbound dispatcher.
 
 pub mod types;
 pub mod traits;
 pub mod engine;
+pub mod ingestion;
+pub mod dispatch;
 
 pub use types::*;
 pub use traits::*;
 pub use engine::*;
+pub use ingestion::*;
+pub use dispatch::*;
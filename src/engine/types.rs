@@ -94,6 +94,15 @@ pub struct EngineStats {
     
     /// Average processing time (ms)
     pub avg_processing_time_ms: u64,
+    
+    /// Packages waiting in the ingestion queue
+    pub queue_depth: usize,
+    
+    /// Capacity of the ingestion queue
+    pub queue_capacity: usize,
+    
+    /// Events dropped because the ingestion queue was full
+    pub dropped_events: usize,
 }
 
 impl EngineStats {
@@ -125,6 +134,16 @@ impl EngineStats {
             self.avg_processing_time_ms = (current_avg * (n - 1) + time_ms) / n;
         }
     }
+    
+    /// Record the current ingestion queue depth
+    pub fn record_queue_depth(&mut self, depth: usize) {
+        self.queue_depth = depth;
+    }
+    
+    /// Record events dropped under backpressure
+    pub fn record_dropped(&mut self, count: usize) {
+        self.dropped_events += count;
+    }
 }
 
 /// Engine state
@@ -229,6 +248,12 @@ mod tests {
         // First update: avg = (0 * 0 + 100) / 1 = 100
         // Second update: avg = (100 * 1 + 200) / 2 = 125
         assert_eq!(stats.avg_processing_time_ms, 125);
+        
+        stats.record_queue_depth(3);
+        stats.record_dropped(2);
+        stats.record_dropped(1);
+        assert_eq!(stats.queue_depth, 3);
+        assert_eq!(stats.dropped_events, 3);
     }
 
     #[test]
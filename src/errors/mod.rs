@@ -53,6 +53,18 @@ pub enum AdapterError {
 
     #[error("Hot reload error: {0}")]
     HotReloadError(String),
+
+    #[error("Adapter is not running: {0}")]
+    NotRunning(String),
+
+    #[error("Adapter send failed: {0}")]
+    SendFailed(String),
+
+    #[error("Adapter connection failed: {0}")]
+    ConnectionFailed(String),
+
+    #[error("Event conversion failed: {0}")]
+    ConversionFailed(String),
 }
 
 /// Logging related errors
@@ -173,6 +185,12 @@ pub enum ChannelError {
 
     #[error("Channel removal failed: {0}")]
     RemovalFailed(String),
+
+    #[error("Channel inbox full: {0}")]
+    InboxFull(String),
+
+    #[error("Channel stopped: {0}")]
+    Stopped(String),
 }
 
 /// Main error wrapper for entire framework
@@ -4,7 +4,7 @@ use crate::events::message::MessageEvent;
 use crate::events::notice::NoticeEvent;
 use crate::events::request::RequestEvent;
 use crate::events::meta::MetaEvent;
-use crate::events::traits::Event;
+use crate::events::traits::{Event, EventMetadata};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
@@ -24,6 +24,20 @@ pub enum EventEnum {
     Meta(MetaEvent),
 }
 
+/// 事件类别
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum EventCategory {
+    /// 消息事件
+    Message,
+    /// 通知事件
+    Notice,
+    /// 请求事件
+    Request,
+    /// 元事件
+    Meta,
+}
+
 /// 事件状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -155,6 +169,51 @@ impl EventEnum {
         }
     }
 
+    /// 获取频道 ID
+    pub fn channel_id(&self) -> Option<&str> {
+        self.metadata().channel_id.as_deref()
+    }
+
+    /// 获取服务器 (guild) ID
+    pub fn guild_id(&self) -> Option<&str> {
+        self.metadata().guild_id.as_deref()
+    }
+
+    /// 获取来源适配器 ID
+    pub fn adapter_id(&self) -> Option<&str> {
+        self.metadata().adapter_id.as_deref()
+    }
+
+    /// 获取事件元数据
+    pub fn metadata(&self) -> &EventMetadata {
+        match self {
+            EventEnum::Message(evt) => evt.metadata(),
+            EventEnum::Notice(evt) => evt.metadata(),
+            EventEnum::Request(evt) => evt.metadata(),
+            EventEnum::Meta(evt) => evt.metadata(),
+        }
+    }
+
+    /// 获取可变事件元数据
+    pub fn metadata_mut(&mut self) -> &mut EventMetadata {
+        match self {
+            EventEnum::Message(evt) => evt.metadata_mut(),
+            EventEnum::Notice(evt) => evt.metadata_mut(),
+            EventEnum::Request(evt) => evt.metadata_mut(),
+            EventEnum::Meta(evt) => evt.metadata_mut(),
+        }
+    }
+
+    /// 获取事件类别
+    pub fn category(&self) -> EventCategory {
+        match self {
+            EventEnum::Message(_) => EventCategory::Message,
+            EventEnum::Notice(_) => EventCategory::Notice,
+            EventEnum::Request(_) => EventCategory::Request,
+            EventEnum::Meta(_) => EventCategory::Meta,
+        }
+    }
+
     /// 判断是否为消息事件
     pub fn is_message(&self) -> bool {
         matches!(self, EventEnum::Message(_))
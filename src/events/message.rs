@@ -7,7 +7,88 @@ use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 use std::fmt::Debug;
 
+/// A single part of a mixed-content message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessageSegment {
+    /// Plain text
+    Text {
+        /// Text content
+        text: String,
+    },
+    /// Mention (@) of a user
+    At {
+        /// Mentioned user ID
+        user_id: String,
+    },
+    /// Quote of another message
+    Reply {
+        /// Quoted message ID
+        message_id: String,
+    },
+    /// Image
+    Image {
+        /// Image URL
+        url: String,
+    },
+    /// Voice
+    Voice {
+        /// Voice URL
+        url: String,
+        /// Duration in seconds
+        #[serde(default)]
+        duration: u32,
+    },
+    /// Video
+    Video {
+        /// Video URL
+        url: String,
+        /// Optional cover URL
+        #[serde(default)]
+        cover_url: Option<String>,
+    },
+    /// File
+    File {
+        /// File URL
+        url: String,
+        /// File name
+        name: String,
+        /// File size in bytes
+        #[serde(default)]
+        size: u64,
+    },
+    /// Location
+    Location {
+        /// Latitude
+        latitude: f64,
+        /// Longitude
+        longitude: f64,
+        /// Address description
+        #[serde(default)]
+        address: Option<String>,
+    },
+    /// Forwarded message
+    Forward {
+        /// Forwarded message ID
+        forward_from: String,
+    },
+    /// Sticker / face
+    Sticker {
+        /// Sticker ID
+        sticker_id: String,
+    },
+    /// Markdown content
+    Markdown {
+        /// Markdown source
+        content: String,
+    },
+}
+
 /// Message event types
+///
+/// Single-content messages use the dedicated variants; messages mixing
+/// several kinds of content (e.g. a quote, mentions and an image) are
+/// delivered as `Chain`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum MessageEvent {
     /// Text message
@@ -119,6 +200,16 @@ pub enum MessageEvent {
         /// Event metadata
         metadata: EventMetadata,
     },
+
+    /// Mixed-content message
+    Chain {
+        /// Platform message ID
+        message_id: String,
+        /// Ordered message segments
+        segments: Vec<MessageSegment>,
+        /// Event metadata
+        metadata: EventMetadata,
+    },
 }
 
 impl Event for MessageEvent {
@@ -135,6 +226,7 @@ impl Event for MessageEvent {
             MessageEvent::Location { metadata, .. } => &metadata.event_id,
             MessageEvent::Sticker { metadata, .. } => &metadata.event_id,
             MessageEvent::Markdown { metadata, .. } => &metadata.event_id,
+            MessageEvent::Chain { metadata, .. } => &metadata.event_id,
         }
     }
     
@@ -151,6 +243,7 @@ impl Event for MessageEvent {
             MessageEvent::Location { .. } => "message.location",
             MessageEvent::Sticker { .. } => "message.sticker",
             MessageEvent::Markdown { .. } => "message.markdown",
+            MessageEvent::Chain { .. } => "message.chain",
         }
     }
     
@@ -167,6 +260,7 @@ impl Event for MessageEvent {
             MessageEvent::Location { metadata, .. } => metadata.timestamp,
             MessageEvent::Sticker { metadata, .. } => metadata.timestamp,
             MessageEvent::Markdown { metadata, .. } => metadata.timestamp,
+            MessageEvent::Chain { metadata, .. } => metadata.timestamp,
         }
     }
     
@@ -183,6 +277,7 @@ impl Event for MessageEvent {
             MessageEvent::Location { metadata, .. } => metadata.source.clone(),
             MessageEvent::Sticker { metadata, .. } => metadata.source.clone(),
             MessageEvent::Markdown { metadata, .. } => metadata.source.clone(),
+            MessageEvent::Chain { metadata, .. } => metadata.source.clone(),
         }
     }
     
@@ -199,6 +294,7 @@ impl Event for MessageEvent {
             MessageEvent::Location { metadata, .. } => metadata.user_id.as_deref(),
             MessageEvent::Sticker { metadata, .. } => metadata.user_id.as_deref(),
             MessageEvent::Markdown { metadata, .. } => metadata.user_id.as_deref(),
+            MessageEvent::Chain { metadata, .. } => metadata.user_id.as_deref(),
         }
     }
     
@@ -215,6 +311,7 @@ impl Event for MessageEvent {
             MessageEvent::Location { metadata, .. } => metadata.group_id.as_deref(),
             MessageEvent::Sticker { metadata, .. } => metadata.group_id.as_deref(),
             MessageEvent::Markdown { metadata, .. } => metadata.group_id.as_deref(),
+            MessageEvent::Chain { metadata, .. } => metadata.group_id.as_deref(),
         }
     }
     
@@ -231,6 +328,7 @@ impl Event for MessageEvent {
             MessageEvent::Location { metadata, .. } => metadata.self_id.as_deref(),
             MessageEvent::Sticker { metadata, .. } => metadata.self_id.as_deref(),
             MessageEvent::Markdown { metadata, .. } => metadata.self_id.as_deref(),
+            MessageEvent::Chain { metadata, .. } => metadata.self_id.as_deref(),
         }
     }
     
@@ -247,12 +345,50 @@ impl Event for MessageEvent {
             MessageEvent::Location { metadata, .. } => metadata.correlation_id.as_deref(),
             MessageEvent::Sticker { metadata, .. } => metadata.correlation_id.as_deref(),
             MessageEvent::Markdown { metadata, .. } => metadata.correlation_id.as_deref(),
+            MessageEvent::Chain { metadata, .. } => metadata.correlation_id.as_deref(),
+        }
+    }
+    
+    fn metadata(&self) -> &EventMetadata {
+        match self {
+            MessageEvent::Text { metadata, .. }
+            | MessageEvent::Image { metadata, .. }
+            | MessageEvent::Voice { metadata, .. }
+            | MessageEvent::Video { metadata, .. }
+            | MessageEvent::At { metadata, .. }
+            | MessageEvent::Reply { metadata, .. }
+            | MessageEvent::Forward { metadata, .. }
+            | MessageEvent::File { metadata, .. }
+            | MessageEvent::Location { metadata, .. }
+            | MessageEvent::Sticker { metadata, .. }
+            | MessageEvent::Markdown { metadata, .. }
+            | MessageEvent::Chain { metadata, .. } => metadata,
+        }
+    }
+
+    fn metadata_mut(&mut self) -> &mut EventMetadata {
+        match self {
+            MessageEvent::Text { metadata, .. }
+            | MessageEvent::Image { metadata, .. }
+            | MessageEvent::Voice { metadata, .. }
+            | MessageEvent::Video { metadata, .. }
+            | MessageEvent::At { metadata, .. }
+            | MessageEvent::Reply { metadata, .. }
+            | MessageEvent::Forward { metadata, .. }
+            | MessageEvent::File { metadata, .. }
+            | MessageEvent::Location { metadata, .. }
+            | MessageEvent::Sticker { metadata, .. }
+            | MessageEvent::Markdown { metadata, .. }
+            | MessageEvent::Chain { metadata, .. } => metadata,
         }
     }
 }
 
 impl MessageEvent {
     /// Get message content (text or fallback to type)
+    ///
+    /// For chains this is the first text segment; use `plain_text` to get
+    /// all of the text.
     pub fn content(&self) -> Option<&str> {
         match self {
             MessageEvent::Text { text, .. } => Some(text),
@@ -261,6 +397,10 @@ impl MessageEvent {
             MessageEvent::Markdown { content, .. } => Some(content),
             MessageEvent::Image { caption, .. } => caption.as_deref(),
             MessageEvent::At { text, .. } => Some(text),
+            MessageEvent::Chain { segments, .. } => segments.iter().find_map(|segment| match segment {
+                MessageSegment::Text { text } => Some(text.as_str()),
+                _ => None,
+            }),
             _ => None,
         }
     }
@@ -273,6 +413,80 @@ impl MessageEvent {
             MessageEvent::Video { url, .. } => Some(url),
             MessageEvent::File { url, .. } => Some(url),
             MessageEvent::Sticker { sticker_id, .. } => Some(sticker_id),
+            MessageEvent::Chain { segments, .. } => segments.iter().find_map(|segment| match segment {
+                MessageSegment::Image { url }
+                | MessageSegment::Voice { url, .. }
+                | MessageSegment::Video { url, .. }
+                | MessageSegment::File { url, .. } => Some(url.as_str()),
+                MessageSegment::Sticker { sticker_id } => Some(sticker_id.as_str()),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Get the platform message ID
+    ///
+    /// Single-content variants carry it in `metadata.extra["message_id"]`.
+    pub fn message_id(&self) -> Option<&str> {
+        match self {
+            MessageEvent::Chain { message_id, .. } => Some(message_id),
+            _ => self.metadata().extra["message_id"].as_str(),
+        }
+    }
+
+    /// Get all text of the message, concatenated in order
+    pub fn plain_text(&self) -> String {
+        match self {
+            MessageEvent::Chain { segments, .. } => segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    MessageSegment::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ => self.content().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Get the IDs of mentioned users
+    pub fn mentions(&self) -> Vec<&str> {
+        match self {
+            MessageEvent::At { at_list, .. } => at_list.iter().map(|s| s.as_str()).collect(),
+            MessageEvent::Chain { segments, .. } => segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    MessageSegment::At { user_id } => Some(user_id.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the URLs of attached images
+    pub fn images(&self) -> Vec<&str> {
+        match self {
+            MessageEvent::Image { url, .. } => vec![url.as_str()],
+            MessageEvent::Chain { segments, .. } => segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    MessageSegment::Image { url } => Some(url.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the ID of the quoted message
+    pub fn reply_to(&self) -> Option<&str> {
+        match self {
+            MessageEvent::Reply { reply_to, .. } => Some(reply_to),
+            MessageEvent::Chain { segments, .. } => segments.iter().find_map(|segment| match segment {
+                MessageSegment::Reply { message_id } => Some(message_id.as_str()),
+                _ => None,
+            }),
             _ => None,
         }
     }
@@ -327,4 +541,45 @@ mod tests {
         assert_eq!(event.content(), Some("@all Hello"));
         assert_eq!(event.user_id(), None);
     }
+
+    #[test]
+    fn test_message_event_chain() {
+        let event = MessageEvent::Chain {
+            message_id: "m1".to_string(),
+            segments: vec![
+                MessageSegment::Reply { message_id: "m0".to_string() },
+                MessageSegment::At { user_id: "user1".to_string() },
+                MessageSegment::Text { text: " look".to_string() },
+                MessageSegment::Image { url: "https://example.com/a.jpg".to_string() },
+                MessageSegment::Text { text: " here".to_string() },
+            ],
+            metadata: EventMetadata::new("message.chain"),
+        };
+
+        assert_eq!(event.event_type(), "message.chain");
+        assert_eq!(event.message_id(), Some("m1"));
+        assert_eq!(event.plain_text(), " look here");
+        assert_eq!(event.mentions(), vec!["user1"]);
+        assert_eq!(event.images(), vec!["https://example.com/a.jpg"]);
+        assert_eq!(event.reply_to(), Some("m0"));
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["Chain"]["segments"][1], serde_json::json!({"type": "at", "user_id": "user1"}));
+        assert_eq!(serde_json::from_value::<MessageEvent>(json).unwrap(), event);
+    }
+
+    #[test]
+    fn test_message_event_json_compat() {
+        let json = serde_json::json!({"Reply": {
+            "reply_to": "m0",
+            "text": "ok",
+            "metadata": serde_json::to_value(EventMetadata::new("message.reply").with_extra("message_id", "m1")).unwrap(),
+        }});
+        let event: MessageEvent = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(event.reply_to(), Some("m0"));
+        assert_eq!(event.message_id(), Some("m1"));
+        assert_eq!(event.plain_text(), "ok");
+        assert_eq!(serde_json::to_value(&event).unwrap(), json);
+    }
 }
@@ -151,6 +151,16 @@ pub enum SystemEventType {
     StorageWarning,
     /// 存储空间严重警告
     StorageCritical,
+    /// 数据包被池隔离（迭代超限或循环）
+    PackageQuarantined,
+    /// Worker 熔断器打开
+    WorkerCircuitOpened,
+    /// Worker 熔断器恢复关闭
+    WorkerCircuitClosed,
+    /// Worker 达到并发或速率上限，数据包被丢弃
+    WorkerOverflow,
+    /// 空闲频道被回收
+    ChannelEvicted,
     /// 其他系统事件
     Other(String),
 }
@@ -216,6 +226,11 @@ impl Event for MetaEvent {
                 SystemEventType::CacheDisconnected => "meta.system.cache_disconnected",
                 SystemEventType::StorageWarning => "meta.system.storage_warning",
                 SystemEventType::StorageCritical => "meta.system.storage_critical",
+                SystemEventType::PackageQuarantined => "meta.system.package_quarantined",
+                SystemEventType::WorkerCircuitOpened => "meta.system.worker_circuit_opened",
+                SystemEventType::WorkerCircuitClosed => "meta.system.worker_circuit_closed",
+                SystemEventType::WorkerOverflow => "meta.system.worker_overflow",
+                SystemEventType::ChannelEvicted => "meta.system.channel_evicted",
                 SystemEventType::Other(_) => "meta.system.other",
             },
             MetaEvent::Performance { .. } => "meta.performance",
@@ -296,6 +311,28 @@ impl Event for MetaEvent {
             MetaEvent::Plugin { metadata, .. } => metadata.correlation_id.as_deref(),
         }
     }
+    
+    fn metadata(&self) -> &EventMetadata {
+        match self {
+            MetaEvent::Heartbeat { metadata, .. } => metadata,
+            MetaEvent::Lifecycle { metadata, .. } => metadata,
+            MetaEvent::ConnectionChange { metadata, .. } => metadata,
+            MetaEvent::System { metadata, .. } => metadata,
+            MetaEvent::Performance { metadata, .. } => metadata,
+            MetaEvent::Plugin { metadata, .. } => metadata,
+        }
+    }
+
+    fn metadata_mut(&mut self) -> &mut EventMetadata {
+        match self {
+            MetaEvent::Heartbeat { metadata, .. } => metadata,
+            MetaEvent::Lifecycle { metadata, .. } => metadata,
+            MetaEvent::ConnectionChange { metadata, .. } => metadata,
+            MetaEvent::System { metadata, .. } => metadata,
+            MetaEvent::Performance { metadata, .. } => metadata,
+            MetaEvent::Plugin { metadata, .. } => metadata,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -306,6 +306,40 @@ impl Event for NoticeEvent {
             NoticeEvent::SystemNotice { metadata, .. } => metadata.correlation_id.as_deref(),
         }
     }
+    
+    fn metadata(&self) -> &EventMetadata {
+        match self {
+            NoticeEvent::GroupMemberJoin { metadata, .. } => metadata,
+            NoticeEvent::GroupMemberLeave { metadata, .. } => metadata,
+            NoticeEvent::GroupMemberKick { metadata, .. } => metadata,
+            NoticeEvent::GroupMemberBan { metadata, .. } => metadata,
+            NoticeEvent::GroupMemberMute { metadata, .. } => metadata,
+            NoticeEvent::GroupNameChange { metadata, .. } => metadata,
+            NoticeEvent::FriendAdd { metadata, .. } => metadata,
+            NoticeEvent::FriendDelete { metadata, .. } => metadata,
+            NoticeEvent::GroupInvite { metadata, .. } => metadata,
+            NoticeEvent::GroupDisband { metadata, .. } => metadata,
+            NoticeEvent::FriendRequestNotice { metadata, .. } => metadata,
+            NoticeEvent::SystemNotice { metadata, .. } => metadata,
+        }
+    }
+
+    fn metadata_mut(&mut self) -> &mut EventMetadata {
+        match self {
+            NoticeEvent::GroupMemberJoin { metadata, .. } => metadata,
+            NoticeEvent::GroupMemberLeave { metadata, .. } => metadata,
+            NoticeEvent::GroupMemberKick { metadata, .. } => metadata,
+            NoticeEvent::GroupMemberBan { metadata, .. } => metadata,
+            NoticeEvent::GroupMemberMute { metadata, .. } => metadata,
+            NoticeEvent::GroupNameChange { metadata, .. } => metadata,
+            NoticeEvent::FriendAdd { metadata, .. } => metadata,
+            NoticeEvent::FriendDelete { metadata, .. } => metadata,
+            NoticeEvent::GroupInvite { metadata, .. } => metadata,
+            NoticeEvent::GroupDisband { metadata, .. } => metadata,
+            NoticeEvent::FriendRequestNotice { metadata, .. } => metadata,
+            NoticeEvent::SystemNotice { metadata, .. } => metadata,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -3,7 +3,7 @@
 //! Package is the basic unit processed on the stream,
 //! containing target_sites and blocks.
 
-use crate::events::{Block, TargetSite};
+use crate::events::{Block, EventEnum, TargetSite};
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 
@@ -70,6 +70,14 @@ impl Package {
         self.extra = extra;
         self
     }
+    
+    /// Iterate over all events of all blocks and groups
+    pub fn events(&self) -> impl Iterator<Item = &EventEnum> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.groups.iter())
+            .flat_map(|group| group.events.iter())
+    }
 }
 
 impl Default for Package {
@@ -173,6 +173,28 @@ impl Event for RequestEvent {
             RequestEvent::RequestCancel { metadata, .. } => metadata.correlation_id.as_deref(),
         }
     }
+    
+    fn metadata(&self) -> &EventMetadata {
+        match self {
+            RequestEvent::FriendRequest { metadata, .. } => metadata,
+            RequestEvent::GroupInvite { metadata, .. } => metadata,
+            RequestEvent::GroupJoinRequest { metadata, .. } => metadata,
+            RequestEvent::RequestApprove { metadata, .. } => metadata,
+            RequestEvent::RequestReject { metadata, .. } => metadata,
+            RequestEvent::RequestCancel { metadata, .. } => metadata,
+        }
+    }
+
+    fn metadata_mut(&mut self) -> &mut EventMetadata {
+        match self {
+            RequestEvent::FriendRequest { metadata, .. } => metadata,
+            RequestEvent::GroupInvite { metadata, .. } => metadata,
+            RequestEvent::GroupJoinRequest { metadata, .. } => metadata,
+            RequestEvent::RequestApprove { metadata, .. } => metadata,
+            RequestEvent::RequestReject { metadata, .. } => metadata,
+            RequestEvent::RequestCancel { metadata, .. } => metadata,
+        }
+    }
 }
 
 #[cfg(test)]
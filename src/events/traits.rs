@@ -44,6 +44,27 @@ pub trait Event: Send + Sync + Debug + Serialize + for<'de> Deserialize<'de> {
     
     /// Get correlation ID for linking related events
     fn correlation_id(&self) -> Option<&str>;
+
+    /// Get event metadata
+    fn metadata(&self) -> &EventMetadata;
+
+    /// Get mutable event metadata
+    fn metadata_mut(&mut self) -> &mut EventMetadata;
+
+    /// Get channel ID if this is a channel event
+    fn channel_id(&self) -> Option<&str> {
+        self.metadata().channel_id.as_deref()
+    }
+
+    /// Get guild ID the channel belongs to
+    fn guild_id(&self) -> Option<&str> {
+        self.metadata().guild_id.as_deref()
+    }
+
+    /// Get ID of the adapter the event originated from
+    fn adapter_id(&self) -> Option<&str> {
+        self.metadata().adapter_id.as_deref()
+    }
 }
 
 /// Common metadata for events
@@ -67,6 +88,18 @@ pub struct EventMetadata {
     /// Self ID (bot's own ID)
     pub self_id: Option<String>,
     
+    /// Channel ID (for channel events)
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    
+    /// Guild ID the channel belongs to
+    #[serde(default)]
+    pub guild_id: Option<String>,
+    
+    /// ID of the adapter the event originated from
+    #[serde(default)]
+    pub adapter_id: Option<String>,
+    
     /// Correlation ID for linking events
     pub correlation_id: Option<String>,
     
@@ -84,6 +117,9 @@ impl EventMetadata {
             user_id: None,
             group_id: None,
             self_id: None,
+            channel_id: None,
+            guild_id: None,
+            adapter_id: None,
             correlation_id: None,
             extra: serde_json::json!({}),
         }
@@ -113,6 +149,24 @@ impl EventMetadata {
         self
     }
     
+    /// Set channel ID
+    pub fn with_channel_id(mut self, channel_id: &str) -> Self {
+        self.channel_id = Some(channel_id.to_string());
+        self
+    }
+    
+    /// Set guild ID
+    pub fn with_guild_id(mut self, guild_id: &str) -> Self {
+        self.guild_id = Some(guild_id.to_string());
+        self
+    }
+    
+    /// Set origin adapter ID
+    pub fn with_adapter_id(mut self, adapter_id: &str) -> Self {
+        self.adapter_id = Some(adapter_id.to_string());
+        self
+    }
+    
     /// Set correlation ID
     pub fn with_correlation_id(mut self, correlation_id: &str) -> Self {
         self.correlation_id = Some(correlation_id.to_string());
@@ -157,4 +211,24 @@ mod tests {
         
         assert_eq!(metadata.extra["custom_key"], "custom_value");
     }
+    
+    #[test]
+    fn test_event_metadata_channel() {
+        let metadata = EventMetadata::new("test_event")
+            .with_channel_id("c1")
+            .with_guild_id("g1")
+            .with_adapter_id("satori-1");
+        
+        assert_eq!(metadata.channel_id.as_deref(), Some("c1"));
+        assert_eq!(metadata.guild_id.as_deref(), Some("g1"));
+        assert_eq!(metadata.adapter_id.as_deref(), Some("satori-1"));
+        
+        // Metadata serialized before these fields existed still loads
+        let mut json = serde_json::to_value(EventMetadata::new("test_event")).unwrap();
+        for key in ["channel_id", "guild_id", "adapter_id"] {
+            json.as_object_mut().unwrap().remove(key);
+        }
+        let metadata: EventMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(metadata.channel_id, None);
+    }
 }
@@ -3,20 +3,22 @@
 //! Provides one-click startup with configuration file support
 
 use loquat::config::LoquatConfig;
-use loquat::engine::{Engine, StandardEngine};
+use loquat::engine::{Engine, IngestionBus, IngestionConfig, OutboundDispatcher, StandardEngine};
 use loquat::cli::PluginCli;
 use loquat::config::loquat_config::{LoggingConfig, AdapterConfig};
 use loquat::logging::formatters::{JsonFormatter, TextFormatter};
 use loquat::logging::writers::{ConsoleWriter, FileWriter, CombinedWriter};
-use loquat::logging::traits::{Logger, LogLevel};
+use loquat::logging::traits::{LogContext, Logger, LogLevel};
+use loquat::events::{EventEnum, MetaEvent, SystemEventType};
 use loquat::plugins::{PluginManager, HotReloadManager};
-use loquat::adapters::{AdapterManager, AdapterHotReloadManager};
+use loquat::adapters::{AdapterManager, AdapterHotReloadManager, AdapterSupervisor};
 use loquat::web::{WebService, WebServiceConfig, AppState};
 use loquat::errors::Result;
 use loquat::shutdown::{ShutdownCoordinator, ShutdownStage, ShutdownOrder};
 use std::sync::Arc;
 use std::time::Duration;
 use std::path::PathBuf;
+use tokio::sync::broadcast;
 
 /// Loquat application with configuration support
 struct LoquatApplication {
@@ -26,6 +28,7 @@ struct LoquatApplication {
     hot_reload_manager: Option<Arc<HotReloadManager>>,
     adapter_hot_reload_manager: Option<Arc<AdapterHotReloadManager>>,
     web_service: Option<Arc<WebService>>,
+    ingestion_bus: Option<Arc<IngestionBus>>,
     logger: Arc<dyn Logger>,
     shutdown_coordinator: Arc<ShutdownCoordinator>,
 }
@@ -45,9 +48,16 @@ impl LoquatApplication {
         let adapter_manager = Arc::new(AdapterManager::new(adapter_config, logger.clone()));
 
         // Register built-in adapter factories
-        use loquat::adapters::{ConsoleAdapterFactory, EchoAdapterFactory};
+        use loquat::adapters::{
+            ConsoleAdapterFactory, EchoAdapterFactory, OneBot11AdapterFactory, OneBot12AdapterFactory,
+            SatoriAdapterFactory, TelegramAdapterFactory,
+        };
         adapter_manager.register_factory(Box::new(ConsoleAdapterFactory))?;
         adapter_manager.register_factory(Box::new(EchoAdapterFactory))?;
+        adapter_manager.register_factory(Box::new(OneBot11AdapterFactory))?;
+        adapter_manager.register_factory(Box::new(OneBot12AdapterFactory))?;
+        adapter_manager.register_factory(Box::new(SatoriAdapterFactory))?;
+        adapter_manager.register_factory(Box::new(TelegramAdapterFactory))?;
 
         // Create shutdown coordinator with default order
         let shutdown_coordinator = Arc::new(
@@ -64,6 +74,7 @@ impl LoquatApplication {
             hot_reload_manager: None,
             adapter_hot_reload_manager: None,
             web_service: None,
+            ingestion_bus: None,
             logger,
             shutdown_coordinator,
         })
@@ -118,8 +129,9 @@ impl LoquatApplication {
             &Default::default(),
         );
 
-        // Create and start engine
-        let mut engine = StandardEngine::new(self.logger.clone());
+        // Create and start engine, delivering replies through the adapter manager
+        let dispatcher = Arc::new(OutboundDispatcher::new(self.adapter_manager.clone(), self.logger.clone()));
+        let mut engine = StandardEngine::new(self.logger.clone()).with_dispatcher(dispatcher);
         if let Err(e) = engine.start().await {
             self.logger.log(
                 LogLevel::Error,
@@ -129,13 +141,35 @@ impl LoquatApplication {
             return;
         }
 
-        // Register engine shutdown handler
+        // Create ingestion bus feeding adapter events into the engine
+        let ingestion_config = IngestionConfig::new()
+            .with_queue_capacity(self.config.engine.queue_capacity as usize)
+            .with_max_in_flight(self.config.engine.max_in_flight as usize);
+        let ingestion_bus = Arc::new(IngestionBus::new(engine.clone(), ingestion_config, self.logger.clone()));
+        if let Err(e) = ingestion_bus.start().await {
+            self.logger.log(
+                LogLevel::Error,
+                &format!("Failed to start ingestion bus: {}", e),
+                &Default::default(),
+            );
+        }
+        self.ingestion_bus = Some(ingestion_bus.clone());
+
+        // Log pool quarantine warnings and channel evictions; feeding them
+        // into the engine would run them through the pools that raised them
+        log_system_events(engine.pool_monitor().subscribe(), self.logger.clone());
+        log_system_events(engine.channel_manager().subscribe(), self.logger.clone());
+
+        // Register engine shutdown handler (drains ingestion first)
         let engine_for_shutdown = engine.clone();
+        let ingestion_for_shutdown = ingestion_bus.clone();
         self.shutdown_coordinator.register_handler(
             ShutdownStage::Engine,
             move || {
                 let mut engine_clone = engine_for_shutdown.clone();
+                let ingestion_clone = ingestion_for_shutdown.clone();
                 Box::pin(async move {
+                    ingestion_clone.stop().await?;
                     engine_clone.stop().await
                 })
             }
@@ -170,6 +204,38 @@ impl LoquatApplication {
             }
         }
 
+        // Start all loaded adapters
+        if self.config.adapters.enabled {
+            let _ = self.adapter_manager.start_all().await;
+
+            let attached = ingestion_bus.attach_manager(&self.adapter_manager).await;
+            self.logger.log(
+                LogLevel::Info,
+                &format!("Ingestion bus attached to {} adapters", attached),
+                &Default::default(),
+            );
+
+            // Reconnect adapters that drop their connection
+            let supervisor = Arc::new(AdapterSupervisor::new(self.adapter_manager.clone(), self.logger.clone()));
+            supervisor.supervise_all().await;
+            ingestion_bus.attach("supervisor", supervisor.subscribe()).await;
+
+            // Register adapter shutdown handler
+            let adapter_manager_for_shutdown = self.adapter_manager.clone();
+            let supervisor_for_shutdown = supervisor.clone();
+            self.shutdown_coordinator.register_handler(
+                ShutdownStage::Adapters,
+                move || {
+                    let manager_clone = adapter_manager_for_shutdown.clone();
+                    let supervisor_clone = supervisor_for_shutdown.clone();
+                    Box::pin(async move {
+                        supervisor_clone.stop().await?;
+                        manager_clone.stop_all().await
+                    })
+                }
+            ).await;
+        }
+
         // Auto-load plugins if enabled
         if self.config.plugins.enabled && self.config.plugins.auto_load {
             self.logger.log(
@@ -296,6 +362,14 @@ impl LoquatApplication {
                 ).await;
 
                 self.adapter_hot_reload_manager = Some(adapter_hot_reload_manager);
+
+                // Pick up reloaded adapters on the same interval
+                if let Some(ingestion_bus) = &self.ingestion_bus {
+                    ingestion_bus.watch_manager(
+                        self.adapter_manager.clone(),
+                        Duration::from_secs(self.config.adapters.hot_reload_interval),
+                    ).await;
+                }
             }
         }
 
@@ -463,6 +537,29 @@ impl LoquatApplication {
     }
 }
 
+/// Log the system events of `receiver` until it closes
+fn log_system_events(mut receiver: broadcast::Receiver<EventEnum>, logger: Arc<dyn Logger>) {
+    tokio::spawn(async move {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let EventEnum::Meta(MetaEvent::System { event_type, description, .. }) = &event else {
+                continue;
+            };
+            let level = match event_type {
+                SystemEventType::PackageQuarantined | SystemEventType::WorkerOverflow => LogLevel::Warn,
+                _ => LogLevel::Info,
+            };
+            let mut context = LogContext::new().with_component("SystemEvents");
+            context.add("event_type", event.event_type().to_string());
+            logger.log(level, description, &context);
+        }
+    });
+}
+
 /// Parse command line arguments
 enum Command {
     Run { environment: String, rebuild: bool },
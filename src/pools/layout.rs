@@ -0,0 +1,233 @@
+//! Stage layout of a stream
+//!
+//! A stream runs the nine built-in pools in `PoolType::processing_order`.
+//! User-defined stages (`PoolType::Custom`) are inserted before or after a
+//! built-in pool, e.g. a "moderation" stage between `PreProcess` and
+//! `Process`. Each stage decides whether third-party workers may register.
+
+use crate::errors::{ConfigError, LoquatError, Result};
+use crate::pools::PoolType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Where a custom stage is inserted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StagePosition {
+    /// Right before a built-in pool
+    Before(PoolType),
+
+    /// Right after a built-in pool
+    After(PoolType),
+}
+
+impl StagePosition {
+    /// Get the built-in pool the stage is anchored to
+    pub fn anchor(&self) -> &PoolType {
+        match self {
+            Self::Before(pool_type) | Self::After(pool_type) => pool_type,
+        }
+    }
+}
+
+/// A user-defined stage
+///
+/// ```toml
+/// [[stages]]
+/// name = "moderation"
+/// after = "pre_process"
+/// allow_third_party = true
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageDefinition {
+    /// Stage name, also its `PoolType::Custom` name
+    pub name: String,
+
+    /// Where the stage is inserted
+    #[serde(flatten)]
+    pub position: StagePosition,
+
+    /// Whether third-party workers may register in this stage
+    #[serde(default)]
+    pub allow_third_party: bool,
+}
+
+impl StageDefinition {
+    /// Define a stage right before a built-in pool
+    pub fn before(name: impl Into<String>, pool_type: PoolType) -> Self {
+        Self {
+            name: name.into(),
+            position: StagePosition::Before(pool_type),
+            allow_third_party: false,
+        }
+    }
+
+    /// Define a stage right after a built-in pool
+    pub fn after(name: impl Into<String>, pool_type: PoolType) -> Self {
+        Self {
+            name: name.into(),
+            position: StagePosition::After(pool_type),
+            allow_third_party: false,
+        }
+    }
+
+    /// Allow or forbid third-party workers
+    pub fn with_third_party(mut self, allowed: bool) -> Self {
+        self.allow_third_party = allowed;
+        self
+    }
+
+    /// Get the pool type of this stage
+    pub fn pool_type(&self) -> PoolType {
+        PoolType::custom(self.name.as_str())
+    }
+}
+
+/// Processing order of the pools of a stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageLayout {
+    stages: Vec<PoolType>,
+    third_party: HashSet<String>,
+}
+
+impl StageLayout {
+    /// Create the layout of the nine built-in pools
+    pub fn standard() -> Self {
+        Self {
+            stages: PoolType::processing_order(),
+            third_party: HashSet::new(),
+        }
+    }
+
+    /// Create a layout with custom stages
+    ///
+    /// Stages anchored to the same pool on the same side keep their
+    /// definition order. Stage names must be unique and must not name a
+    /// built-in pool; anchors must be built-in pools.
+    pub fn new(definitions: &[StageDefinition]) -> Result<Self> {
+        let mut names = HashSet::new();
+        for definition in definitions {
+            if definition.name.is_empty() || PoolType::builtin(&definition.name).is_some() {
+                return Err(LoquatError::Config(ConfigError::InvalidFormat(format!(
+                    "Invalid stage name '{}': must be non-empty and not a built-in pool",
+                    definition.name
+                ))));
+            }
+            if !names.insert(definition.name.as_str()) {
+                return Err(LoquatError::Config(ConfigError::InvalidFormat(format!(
+                    "Stage '{}' is defined more than once",
+                    definition.name
+                ))));
+            }
+            if definition.position.anchor().is_custom() {
+                return Err(LoquatError::Config(ConfigError::InvalidFormat(format!(
+                    "Stage '{}' must be placed next to a built-in pool, not '{}'",
+                    definition.name,
+                    definition.position.anchor()
+                ))));
+            }
+        }
+
+        let mut stages = Vec::new();
+        for builtin in PoolType::processing_order() {
+            let before = StagePosition::Before(builtin.clone());
+            let after = StagePosition::After(builtin.clone());
+            stages.extend(definitions.iter().filter(|d| d.position == before).map(StageDefinition::pool_type));
+            stages.push(builtin);
+            stages.extend(definitions.iter().filter(|d| d.position == after).map(StageDefinition::pool_type));
+        }
+
+        let third_party = definitions
+            .iter()
+            .filter(|definition| definition.allow_third_party)
+            .map(|definition| definition.name.clone())
+            .collect();
+
+        Ok(Self { stages, third_party })
+    }
+
+    /// Get the stages in processing order
+    pub fn stages(&self) -> &[PoolType] {
+        &self.stages
+    }
+
+    /// Get the position of a stage (`None` if the layout does not have it)
+    pub fn position(&self, pool_type: &PoolType) -> Option<usize> {
+        self.stages.iter().position(|stage| stage == pool_type)
+    }
+
+    /// Check if a stage is part of this layout
+    pub fn contains(&self, pool_type: &PoolType) -> bool {
+        self.position(pool_type).is_some()
+    }
+
+    /// Check if third-party workers may register in a stage
+    pub fn allows_third_party(&self, pool_type: &PoolType) -> bool {
+        match pool_type {
+            PoolType::Custom(name) => self.third_party.contains(name),
+            builtin => builtin.allows_third_party(),
+        }
+    }
+}
+
+impl Default for StageLayout {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_layout_order() {
+        let layout = StageLayout::new(&[
+            StageDefinition::after("moderation", PoolType::PreProcess).with_third_party(true),
+            StageDefinition::before("audit", PoolType::Process),
+            StageDefinition::after("translate", PoolType::PreProcess),
+        ])
+        .unwrap();
+
+        let names: Vec<String> = layout.stages()[3..8].iter().map(|stage| stage.to_string()).collect();
+        assert_eq!(names, vec!["pre_process", "moderation", "translate", "process_middle", "audit"]);
+        assert_eq!(layout.stages().len(), 12);
+        assert_eq!(layout.position(&PoolType::custom("moderation")), Some(4));
+        assert!(layout.allows_third_party(&PoolType::custom("moderation")));
+        assert!(!layout.allows_third_party(&PoolType::custom("audit")));
+        assert!(!layout.allows_third_party(&PoolType::PostProcess));
+        assert_eq!(StageLayout::standard().stages(), PoolType::processing_order().as_slice());
+    }
+
+    #[test]
+    fn test_stage_layout_validation() {
+        assert!(StageLayout::new(&[StageDefinition::after("process", PoolType::Input)]).is_err());
+        assert!(StageLayout::new(&[
+            StageDefinition::after("moderation", PoolType::Input),
+            StageDefinition::before("moderation", PoolType::Output),
+        ])
+        .is_err());
+        assert!(StageLayout::new(&[StageDefinition::after("b", PoolType::custom("a"))]).is_err());
+    }
+
+    #[test]
+    fn test_stage_definition_from_config() {
+        #[derive(Deserialize)]
+        struct Config {
+            stages: Vec<StageDefinition>,
+        }
+        let config: Config = toml::from_str(
+            r#"
+            [[stages]]
+            name = "moderation"
+            after = "pre_process"
+            allow_third_party = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.stages,
+            vec![StageDefinition::after("moderation", PoolType::PreProcess).with_third_party(true)]
+        );
+    }
+}
@@ -1,13 +1,18 @@
 //! Pools module for Loquat framework
 //!
 //! Pools manage workers and process packages through them.
-//! There are 9 pool types that process packages in sequence.
+//! There are 9 built-in pool types that process packages in sequence;
+//! a `StageLayout` can insert custom stages between them.
 
 pub mod traits;
 pub mod types;
 pub mod standard_pool;
 pub mod validator;
+pub mod monitor;
+pub mod layout;
 
 pub use traits::*;
 pub use types::*;
 pub use standard_pool::*;
+pub use monitor::*;
+pub use layout::*;
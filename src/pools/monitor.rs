@@ -0,0 +1,279 @@
+//! Pool metrics and quarantine
+//!
+//! A `PoolMonitor` is shared by the pools of all channel streams. It keeps
+//! per-pool-type counters, holds the packages pools took out of processing
+//! and announces quarantines, circuit breaker changes and packages dropped
+//! by overloaded workers as `MetaEvent::System` events.
+
+use crate::channels::ChannelType;
+use crate::events::{EventEnum, EventMetadata, EventSource, MetaEvent, Package, SystemEventType};
+use crate::pools::validator::{Lineage, QuarantineReason};
+use crate::pools::PoolType;
+use crate::workers::{BreakerSnapshot, BreakerState};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+use tokio::sync::broadcast;
+
+/// Default number of quarantined packages kept for inspection
+pub const DEFAULT_QUARANTINE_CAPACITY: usize = 100;
+
+/// Counters of one pool type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PoolMetrics {
+    /// Packages that entered the pool
+    pub packages_in: u64,
+    /// Packages released to the next pool
+    pub packages_released: u64,
+    /// `handle_batch` calls
+    pub batches: u64,
+    /// Packages produced by `WorkerResult::Modify`
+    pub packages_modified: u64,
+    /// Modified packages dropped because they would match their own worker
+    pub dead_loops: u64,
+    /// Packages quarantined for exceeding the iteration limit
+    pub iteration_limits: u64,
+    /// Packages quarantined for a worker cycle
+    pub cycles: u64,
+    /// `Defer` results
+    pub deferrals: u64,
+    /// Packages quarantined after too many deferrals
+    pub defer_limits: u64,
+    /// Packages consumed by `Drop`
+    pub packages_dropped: u64,
+    /// Replies attached by `Reply`
+    pub replies: u64,
+    /// Packages sent ahead by `Jump`
+    pub jumps: u64,
+    /// `handle_batch` calls that timed out
+    pub worker_timeouts: u64,
+    /// `handle_batch` calls that panicked
+    pub worker_panics: u64,
+    /// Timeouts and panics per worker name
+    pub worker_failures: BTreeMap<String, u64>,
+    /// Packages quarantined after a worker failure
+    pub failed_quarantines: u64,
+    /// Times a worker's circuit breaker opened
+    pub breaker_trips: u64,
+    /// Packages that skipped a worker because its circuit breaker was open
+    pub breaker_bypasses: u64,
+    /// Calls that waited for a worker at its concurrency or rate limit
+    pub overflow_queued: u64,
+    /// Packages handed to the next worker because theirs was at its limit
+    pub overflow_skips: u64,
+    /// Packages dropped because their worker was at its limit
+    pub overflow_drops: u64,
+    /// Batch windows flushed into one `handle_batch` call
+    pub batch_windows: u64,
+    /// Packages handed to workers through batch windows
+    pub batched_packages: u64,
+}
+
+/// A package taken out of a pool
+#[derive(Debug, Clone, Serialize)]
+pub struct QuarantinedPackage {
+    /// The package as it was when it was stopped
+    pub package: Package,
+    /// Pool that stopped the package
+    pub pool_type: PoolType,
+    /// Channel of the pool's stream, if known
+    pub channel_type: Option<ChannelType>,
+    /// Why the package was stopped
+    pub reason: QuarantineReason,
+    /// Workers that modified the package before it was stopped
+    pub lineage: Lineage,
+    /// When the package was stopped
+    pub quarantined_at: DateTime<Utc>,
+}
+
+impl QuarantinedPackage {
+    /// Build the `MetaEvent::System` warning announcing this quarantine
+    pub fn to_event(&self) -> EventEnum {
+        let mut data = HashMap::new();
+        data.insert("pool".to_string(), serde_json::json!(self.pool_type));
+        data.insert("package_id".to_string(), serde_json::json!(self.package.package_id));
+        data.insert("reason".to_string(), serde_json::json!(self.reason.kind()));
+        data.insert("lineage".to_string(), serde_json::json!(self.lineage.workers));
+        if let Some(channel_type) = &self.channel_type {
+            data.insert("channel".to_string(), serde_json::json!(channel_type.to_string()));
+        }
+
+        EventEnum::Meta(MetaEvent::System {
+            event_type: SystemEventType::PackageQuarantined,
+            description: format!(
+                "Package {} quarantined in pool '{}': {} (lineage: {})",
+                self.package.package_id, self.pool_type, self.reason, self.lineage
+            ),
+            data,
+            metadata: EventMetadata::new("pool.quarantine").with_source(EventSource::System),
+        })
+    }
+}
+
+/// Metrics and quarantine shared by pools
+#[derive(Debug)]
+pub struct PoolMonitor {
+    metrics: RwLock<HashMap<PoolType, PoolMetrics>>,
+    quarantine: Mutex<VecDeque<QuarantinedPackage>>,
+    quarantine_capacity: usize,
+    event_sender: broadcast::Sender<EventEnum>,
+}
+
+impl PoolMonitor {
+    /// Create a new monitor
+    pub fn new() -> Self {
+        Self::with_quarantine_capacity(DEFAULT_QUARANTINE_CAPACITY)
+    }
+
+    /// Create a monitor keeping at most `capacity` quarantined packages
+    pub fn with_quarantine_capacity(capacity: usize) -> Self {
+        let (event_sender, _) = broadcast::channel(64);
+        Self {
+            metrics: RwLock::new(HashMap::new()),
+            quarantine: Mutex::new(VecDeque::new()),
+            quarantine_capacity: capacity,
+            event_sender,
+        }
+    }
+
+    /// Subscribe to the events of this monitor
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnum> {
+        self.event_sender.subscribe()
+    }
+
+    /// Update the counters of a pool type
+    pub fn record<F: FnOnce(&mut PoolMetrics)>(&self, pool_type: &PoolType, update: F) {
+        if let Ok(mut metrics) = self.metrics.write() {
+            update(metrics.entry(pool_type.clone()).or_default());
+        }
+    }
+
+    /// Get the counters of a pool type
+    pub fn metrics(&self, pool_type: &PoolType) -> PoolMetrics {
+        self.metrics
+            .read()
+            .ok()
+            .and_then(|metrics| metrics.get(pool_type).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Get the counters of all pool types that saw traffic
+    pub fn all_metrics(&self) -> HashMap<PoolType, PoolMetrics> {
+        self.metrics.read().map(|metrics| metrics.clone()).unwrap_or_default()
+    }
+
+    /// Keep a quarantined package and announce it
+    pub fn quarantine(&self, entry: QuarantinedPackage) {
+        self.record(&entry.pool_type, |metrics| match entry.reason {
+            QuarantineReason::IterationLimit { .. } => metrics.iteration_limits += 1,
+            QuarantineReason::Cycle { .. } => metrics.cycles += 1,
+            QuarantineReason::DeferLimit { .. } => metrics.defer_limits += 1,
+            QuarantineReason::WorkerFailed { .. } => metrics.failed_quarantines += 1,
+            QuarantineReason::BackwardJump { .. } => metrics.dead_loops += 1,
+        });
+        let _ = self.event_sender.send(entry.to_event());
+
+        if self.quarantine_capacity == 0 {
+            return;
+        }
+        if let Ok(mut quarantine) = self.quarantine.lock() {
+            if quarantine.len() >= self.quarantine_capacity {
+                quarantine.pop_front();
+            }
+            quarantine.push_back(entry);
+        }
+    }
+
+    /// Announce that a worker's circuit breaker opened or closed
+    pub fn breaker_changed(
+        &self,
+        pool_type: &PoolType,
+        channel_type: Option<&ChannelType>,
+        worker: &str,
+        snapshot: &BreakerSnapshot,
+    ) {
+        let (event_type, description) = match snapshot.state {
+            BreakerState::Open => {
+                self.record(pool_type, |metrics| metrics.breaker_trips += 1);
+                (
+                    SystemEventType::WorkerCircuitOpened,
+                    format!(
+                        "Circuit breaker of worker '{}' in pool '{}' opened (failure rate {:.0}%)",
+                        worker, pool_type, snapshot.failure_rate * 100.0
+                    ),
+                )
+            }
+            _ => (
+                SystemEventType::WorkerCircuitClosed,
+                format!("Circuit breaker of worker '{}' in pool '{}' closed", worker, pool_type),
+            ),
+        };
+
+        let mut data = HashMap::new();
+        data.insert("pool".to_string(), serde_json::json!(pool_type));
+        data.insert("worker".to_string(), serde_json::json!(worker));
+        data.insert("breaker".to_string(), serde_json::json!(snapshot));
+        if let Some(channel_type) = channel_type {
+            data.insert("channel".to_string(), serde_json::json!(channel_type.to_string()));
+        }
+
+        let _ = self.event_sender.send(EventEnum::Meta(MetaEvent::System {
+            event_type,
+            description,
+            data,
+            metadata: EventMetadata::new("pool.breaker").with_source(EventSource::System),
+        }));
+    }
+
+    /// Announce packages dropped because their worker was at its limit
+    pub fn overflow_dropped(
+        &self,
+        pool_type: &PoolType,
+        channel_type: Option<&ChannelType>,
+        worker: &str,
+        package_ids: &[&str],
+    ) {
+        self.record(pool_type, |metrics| metrics.overflow_drops += package_ids.len() as u64);
+
+        let mut data = HashMap::new();
+        data.insert("pool".to_string(), serde_json::json!(pool_type));
+        data.insert("worker".to_string(), serde_json::json!(worker));
+        data.insert("package_ids".to_string(), serde_json::json!(package_ids));
+        if let Some(channel_type) = channel_type {
+            data.insert("channel".to_string(), serde_json::json!(channel_type.to_string()));
+        }
+
+        let _ = self.event_sender.send(EventEnum::Meta(MetaEvent::System {
+            event_type: SystemEventType::WorkerOverflow,
+            description: format!(
+                "Worker '{}' in pool '{}' is at its limit, dropped {} packages",
+                worker, pool_type, package_ids.len()
+            ),
+            data,
+            metadata: EventMetadata::new("pool.overflow").with_source(EventSource::System),
+        }));
+    }
+
+    /// Get the quarantined packages, oldest first
+    pub fn quarantined(&self) -> Vec<QuarantinedPackage> {
+        self.quarantine
+            .lock()
+            .map(|quarantine| quarantine.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Take the quarantined packages out of the monitor, oldest first
+    pub fn drain_quarantine(&self) -> Vec<QuarantinedPackage> {
+        self.quarantine
+            .lock()
+            .map(|mut quarantine| quarantine.drain(..).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for PoolMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,17 +1,26 @@
 //! Standard pool implementation
 
+use crate::channels::ChannelType;
 use crate::errors::{ConfigError, LoquatError};
 use crate::events::Package;
 use crate::pools::traits::Pool;
-use crate::pools::PoolType;
-use crate::pools::validator::PoolValidator;
+use crate::engine::dispatch::push_outbound;
+use crate::pools::{set_destination, Destination, FailureAction, PoolConfig, PoolMonitor, PoolType, QuarantinedPackage, StageLayout};
+use crate::pools::validator::{Lineage, PoolValidator, QuarantineReason};
+use crate::logging::traits::{LogContext, LogLevel};
 use crate::workers::OutputSafe;
 use crate::workers::WorkerRegistration;
 use crate::workers::WorkerResult;
+use crate::workers::result::{CallFailure, CallReply, WorkerFailure};
+use crate::workers::{BreakerState, Joined, LimitPermit, OverflowAction, WorkerRegistry, WorkerScope};
 use async_trait::async_trait;
-use std::collections::HashMap;
+use futures_util::stream::{self, StreamExt};
+use futures_util::FutureExt;
+use std::panic::AssertUnwindSafe;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Standard pool implementation
 pub struct StandardPool {
@@ -19,10 +28,52 @@ pub struct StandardPool {
     pool_type: PoolType,
     workers: Vec<WorkerRegistration>,
     worker_index: HashMap<String, usize>, // worker_name -> index in workers vec
+    shared: Option<SharedWorkers>,
+    config: PoolConfig,
+    layout: Arc<StageLayout>,
+    monitor: Arc<PoolMonitor>,
     logger: Arc<dyn crate::logging::Logger>,
     validator: PoolValidator,
 }
 
+/// A package together with its lineage in this pool
+struct Tracked {
+    package: Package,
+    lineage: Lineage,
+}
+
+/// Key of a lane: packages in one lane are handled one step at a time
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum LaneKey {
+    /// Packages of one channel (`preserve_order`)
+    Channel(Option<ChannelType>),
+    
+    /// Packages handled by one worker (`None`: no worker matched)
+    Worker(Option<usize>),
+}
+
+/// Consecutive packages of a lane handled by the same worker
+struct Step {
+    worker: Option<usize>,
+    packages: Vec<Tracked>,
+}
+
+/// Packages leaving a lane
+#[derive(Default)]
+struct LaneOutcome {
+    /// Packages moving to the next pool
+    released: Vec<Package>,
+    
+    /// Packages processed again in this pool
+    modified: Vec<Tracked>,
+}
+
+/// View of a worker registry from one channel
+struct SharedWorkers {
+    registry: Arc<WorkerRegistry>,
+    channel_type: ChannelType,
+}
+
 impl StandardPool {
     /// Create a new standard pool
     pub fn new(pool_type: PoolType, logger: Arc<dyn crate::logging::Logger>) -> Self {
@@ -31,6 +82,10 @@ impl StandardPool {
             pool_type,
             workers: Vec::new(),
             worker_index: HashMap::new(),
+            shared: None,
+            config: PoolConfig::default(),
+            layout: Arc::new(StageLayout::standard()),
+            monitor: Arc::new(PoolMonitor::new()),
             logger,
             validator: PoolValidator::new(),
         }
@@ -43,12 +98,73 @@ impl StandardPool {
             pool_type,
             workers: Vec::new(),
             worker_index: HashMap::new(),
+            shared: None,
+            config: PoolConfig::default(),
+            layout: Arc::new(StageLayout::standard()),
+            monitor: Arc::new(PoolMonitor::new()),
             logger,
             validator: PoolValidator::new(),
         }
     }
     
-    /// Get workers sorted by priority
+    /// Create a pool that runs the workers of `registry` for a channel
+    ///
+    /// Workers are looked up on every batch, so registry changes apply to
+    /// packages that are already flowing. Registering through the pool adds
+    /// a worker scoped to this channel.
+    pub fn with_registry(
+        pool_type: PoolType,
+        registry: Arc<WorkerRegistry>,
+        channel_type: ChannelType,
+        logger: Arc<dyn crate::logging::Logger>,
+    ) -> Self {
+        let mut pool = Self::new(pool_type, logger);
+        pool.shared = Some(SharedWorkers { registry, channel_type });
+        pool
+    }
+    
+    /// Get the workers this pool runs, sorted by priority
+    pub fn active_workers(&self) -> Vec<WorkerRegistration> {
+        let mut workers = self.workers.clone();
+        if let Some(shared) = &self.shared {
+            workers.extend(shared.registry.workers_for(&self.pool_type, &shared.channel_type));
+            workers.sort_by_key(|w| w.priority);
+        }
+        workers
+    }
+    
+    /// Set execution settings
+    pub fn with_config(mut self, config: PoolConfig) -> Self {
+        self.validator = PoolValidator::with_max_iterations(config.max_iterations);
+        self.config = config;
+        self
+    }
+    
+    /// Set the stage order of the stream this pool belongs to
+    ///
+    /// The layout tells jumps to earlier stages from jumps to later ones.
+    pub fn with_layout(mut self, layout: Arc<StageLayout>) -> Self {
+        self.layout = layout;
+        self
+    }
+    
+    /// Report metrics and quarantined packages to `monitor`
+    pub fn with_monitor(mut self, monitor: Arc<PoolMonitor>) -> Self {
+        self.monitor = monitor;
+        self
+    }
+    
+    /// Get the monitor this pool reports to
+    pub fn monitor(&self) -> &Arc<PoolMonitor> {
+        &self.monitor
+    }
+    
+    /// Get execution settings
+    pub fn config(&self) -> &PoolConfig {
+        &self.config
+    }
+    
+    /// Get locally registered workers sorted by priority
     pub fn workers_sorted(&self) -> &[WorkerRegistration] {
         &self.workers
     }
@@ -67,6 +183,425 @@ impl StandardPool {
         }
     }
     
+    /// Split a round of packages into lanes
+    ///
+    /// Each package goes to the first matching worker whose circuit breaker
+    /// lets calls through. Consecutive packages of a lane that go to the
+    /// same worker form one `handle_batch` call. Packages over the iteration
+    /// limit or about to close a worker cycle are quarantined instead.
+    fn plan_lanes(&self, workers: &[WorkerRegistration], packages: Vec<Tracked>) -> Vec<Vec<Step>> {
+        let mut lanes: Vec<Vec<Step>> = Vec::new();
+        let mut lane_index: HashMap<LaneKey, usize> = HashMap::new();
+        
+        for package in packages {
+            let Some((worker, package)) = self.route(workers, 0, package) else {
+                continue;
+            };
+            
+            let key = if self.config.preserve_order {
+                LaneKey::Channel(channel_of(&package.package))
+            } else {
+                LaneKey::Worker(worker)
+            };
+            let idx = *lane_index.entry(key).or_insert_with(|| {
+                lanes.push(Vec::new());
+                lanes.len() - 1
+            });
+            push_step(&mut lanes[idx], worker, package);
+        }
+        
+        lanes
+    }
+    
+    /// Find the worker for a package among `workers[from..]`
+    ///
+    /// Returns `None` if the package was quarantined.
+    fn route(&self, workers: &[WorkerRegistration], from: usize, package: Tracked) -> Option<(Option<usize>, Tracked)> {
+        for (idx, worker) in workers.iter().enumerate().skip(from) {
+            if !worker.matches_package(&package.package) {
+                continue;
+            }
+            // Checked before the breaker, so a half-open trial is only
+            // claimed for a package the worker will actually get
+            if let Some(reason) = self.validator.check_lineage(&package.lineage, worker.name()) {
+                self.quarantine(package, reason);
+                return None;
+            }
+            if worker.allows_call() {
+                return Some((Some(idx), package));
+            }
+            self.monitor.record(&self.pool_type, |m| m.breaker_bypasses += 1);
+        }
+        Some((None, package))
+    }
+    
+    /// Run the steps of a lane in order
+    async fn run_lane(&self, workers: &[WorkerRegistration], steps: Vec<Step>) -> LaneOutcome {
+        let mut outcome = LaneOutcome::default();
+        let mut steps = VecDeque::from(steps);
+        
+        while let Some(step) = steps.pop_front() {
+            // No worker matched, packages move to next pool
+            let Some(idx) = step.worker else {
+                outcome.released.extend(step.packages.into_iter().map(|t| t.package));
+                continue;
+            };
+            let worker = &workers[idx];
+            
+            let result = match self.execute(worker, &step.packages).await {
+                Ok(result) => result,
+                Err(CallFailure::Overflow(OverflowAction::Skip)) => {
+                    // Hand the packages to the workers after this one
+                    self.monitor.record(&self.pool_type, |m| m.overflow_skips += step.packages.len() as u64);
+                    let mut rerouted = Vec::new();
+                    for package in step.packages {
+                        if let Some((next, package)) = self.route(workers, idx + 1, package) {
+                            push_step(&mut rerouted, next, package);
+                        }
+                    }
+                    for next_step in rerouted.into_iter().rev() {
+                        steps.push_front(next_step);
+                    }
+                    continue;
+                }
+                Err(CallFailure::Overflow(_)) => {
+                    self.drop_overflow(worker, step.packages);
+                    continue;
+                }
+                Err(CallFailure::DeferLimit(deferrals)) => {
+                    for package in step.packages {
+                        self.quarantine(package, QuarantineReason::DeferLimit { deferrals });
+                    }
+                    continue;
+                }
+                Err(CallFailure::Worker(failure)) => {
+                    match self.config.on_failure {
+                        FailureAction::Skip => {
+                            outcome.released.extend(step.packages.into_iter().map(|t| t.package));
+                        }
+                        FailureAction::Quarantine => {
+                            for package in step.packages {
+                                self.quarantine(package, QuarantineReason::WorkerFailed {
+                                    worker: worker.name().to_string(),
+                                    error: failure.to_string(),
+                                });
+                            }
+                        }
+                    }
+                    continue;
+                }
+            };
+            
+            match result {
+                WorkerResult::Release => {
+                    // Worker completed, packages move to next pool
+                    outcome.released.extend(step.packages.into_iter().map(|t| t.package));
+                }
+                WorkerResult::Modify(new_packages) => {
+                    self.keep_modified(worker, &step.packages, new_packages, &mut outcome);
+                }
+                WorkerResult::Drop(reason) => {
+                    self.monitor.record(&self.pool_type, |m| m.packages_dropped += step.packages.len() as u64);
+                    let message = format!(
+                        "Worker '{}' dropped {} packages: {}",
+                        worker.name(), step.packages.len(), reason
+                    );
+                    let context = LogContext::new().with_component("StandardPool");
+                    self.logger.log(LogLevel::Debug, &message, &context);
+                }
+                WorkerResult::Reply(messages) => {
+                    // Replied packages leave the stream for immediate delivery;
+                    // the first one carries the messages so they are sent once
+                    self.monitor.record(&self.pool_type, |m| m.replies += messages.len() as u64);
+                    let mut messages = Some(messages);
+                    for tracked in step.packages {
+                        let mut package = tracked.package;
+                        for message in messages.take().into_iter().flatten() {
+                            push_outbound(&mut package, message);
+                        }
+                        set_destination(&mut package, Destination::Exit);
+                        outcome.released.push(package);
+                    }
+                }
+                WorkerResult::Jump(target, new_packages) if target == self.pool_type => {
+                    self.keep_modified(worker, &step.packages, new_packages, &mut outcome);
+                }
+                WorkerResult::Jump(target, new_packages) if self.is_behind(&target) => {
+                    // Going back could loop through the pools forever
+                    let lineage = Self::lineage_of(&step.packages).extended(worker.name());
+                    for new_pkg in new_packages {
+                        let tracked = Tracked { package: new_pkg, lineage: lineage.clone() };
+                        self.quarantine(tracked, QuarantineReason::BackwardJump { target: target.clone() });
+                    }
+                }
+                WorkerResult::Jump(target, new_packages) => {
+                    self.monitor.record(&self.pool_type, |m| m.jumps += new_packages.len() as u64);
+                    for mut new_pkg in new_packages {
+                        set_destination(&mut new_pkg, Destination::Pool(target.clone()));
+                        outcome.released.push(new_pkg);
+                    }
+                }
+                WorkerResult::Defer(_) => unreachable!("deferrals are resolved by handle_step"),
+            }
+        }
+        
+        outcome
+    }
+    
+    /// Hand a step's packages to a worker
+    ///
+    /// Batching workers get the packages through their current batch window;
+    /// the step leading the window calls the worker for all of its steps.
+    async fn execute(&self, worker: &WorkerRegistration, packages: &[Tracked]) -> CallReply {
+        let packages: Vec<Package> = packages.iter().map(|t| t.package.clone()).collect();
+        let Some(batcher) = &worker.batcher else {
+            return self.call(worker, &packages).await;
+        };
+        
+        match batcher.join(packages).await {
+            Joined::Member(share) => share
+                .await
+                .unwrap_or(Err(CallFailure::Worker(WorkerFailure::Cancelled))),
+            Joined::Leader(batch) => {
+                self.monitor.record(&self.pool_type, |m| {
+                    m.batch_windows += 1;
+                    m.batched_packages += batch.packages().len() as u64;
+                });
+                let results = match self.call(worker, batch.packages()).await {
+                    Ok(result) => batch.split(&result).into_iter().map(Ok).collect(),
+                    Err(failure) => vec![Err(failure); batch.steps()],
+                };
+                batch.reply(results)
+            }
+        }
+    }
+    
+    /// Admit and call a worker, feeding the outcome to its circuit breaker
+    async fn call(&self, worker: &WorkerRegistration, packages: &[Package]) -> CallReply {
+        let permit = self.admit(worker).await.map_err(CallFailure::Overflow)?;
+        let result = self.handle_step(worker, packages, permit).await;
+        match &result {
+            Ok(_) => self.record_outcome(worker, true),
+            Err(CallFailure::Worker(failure)) => {
+                self.report_failure(worker, packages, failure);
+                self.record_outcome(worker, false);
+            }
+            Err(_) => {}
+        }
+        result
+    }
+    
+    /// Get a permit to call a limited worker
+    ///
+    /// Waits for the worker if its overflow action is `Queue`; otherwise
+    /// returns the overflow action when the worker is at its limit.
+    async fn admit(&self, worker: &WorkerRegistration) -> Result<Option<LimitPermit>, OverflowAction> {
+        let Some(limiter) = &worker.limiter else {
+            return Ok(None);
+        };
+        if let Some(permit) = limiter.try_acquire() {
+            return Ok(Some(permit));
+        }
+        match limiter.limits().overflow {
+            OverflowAction::Queue => {
+                self.monitor.record(&self.pool_type, |m| m.overflow_queued += 1);
+                Ok(Some(limiter.acquire().await))
+            }
+            action => Err(action),
+        }
+    }
+    
+    /// Drop packages a worker at its limit could not take
+    fn drop_overflow(&self, worker: &WorkerRegistration, packages: Vec<Tracked>) {
+        let package_ids: Vec<&str> = packages.iter().map(|t| t.package.package_id.as_str()).collect();
+        let message = format!(
+            "Worker '{}' is at its limit in pool '{}', dropped packages: {}",
+            worker.name(), self.pool_id, package_ids.join(", ")
+        );
+        let mut context = LogContext::new().with_component("StandardPool");
+        context.add("worker", worker.name().to_string());
+        context.add("package_id", package_ids.join(","));
+        self.logger.log(LogLevel::Warn, &message, &context);
+        
+        let channel_type = self.shared.as_ref().map(|shared| &shared.channel_type);
+        self.monitor.overflow_dropped(&self.pool_type, channel_type, worker.name(), &package_ids);
+    }
+    
+    /// Hand a batch to a worker, retrying while it defers
+    ///
+    /// `permit` covers the first call; retries wait for the worker's limits.
+    async fn handle_step(
+        &self,
+        worker: &WorkerRegistration,
+        packages: &[Package],
+        mut permit: Option<LimitPermit>,
+    ) -> CallReply {
+        let mut deferrals = 0;
+        loop {
+            let call_permit = match (permit.take(), &worker.limiter) {
+                (Some(permit), _) => Some(permit),
+                (None, Some(limiter)) => Some(limiter.acquire().await),
+                (None, None) => None,
+            };
+            self.monitor.record(&self.pool_type, |m| m.batches += 1);
+            
+            // Clone to preserve ownership for Release
+            let result = self.call_worker(worker, packages.to_vec()).await;
+            drop(call_permit);
+            match result.map_err(CallFailure::Worker)? {
+                WorkerResult::Defer(delay) => {
+                    self.monitor.record(&self.pool_type, |m| m.deferrals += 1);
+                    deferrals += 1;
+                    if deferrals > self.config.max_deferrals {
+                        return Err(CallFailure::DeferLimit(deferrals));
+                    }
+                    tokio::time::sleep(delay).await;
+                }
+                result => return Ok(result),
+            }
+        }
+    }
+    
+    /// Call `handle_batch`, isolating the pool from hangs and panics
+    async fn call_worker(&self, worker: &WorkerRegistration, batch: Vec<Package>) -> Result<WorkerResult, WorkerFailure> {
+        let timeout = worker.timeout.or(match self.config.worker_timeout_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        });
+        let call = AssertUnwindSafe(worker.worker.handle_batch(batch)).catch_unwind();
+        
+        let result = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, call)
+                .await
+                .map_err(|_| WorkerFailure::Timeout(timeout))?,
+            None => call.await,
+        };
+        result.map_err(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            WorkerFailure::Panic(message)
+        })
+    }
+    
+    /// Log and count a failed worker call
+    fn report_failure(&self, worker: &WorkerRegistration, packages: &[Package], failure: &WorkerFailure) {
+        let package_ids: Vec<&str> = packages.iter().map(|p| p.package_id.as_str()).collect();
+        self.monitor.record(&self.pool_type, |m| {
+            match failure {
+                WorkerFailure::Timeout(_) => m.worker_timeouts += 1,
+                WorkerFailure::Panic(_) => m.worker_panics += 1,
+                WorkerFailure::Cancelled => {}
+            }
+            *m.worker_failures.entry(worker.name().to_string()).or_default() += 1;
+        });
+        
+        let message = format!(
+            "Worker '{}' {} in pool '{}' (packages: {})",
+            worker.name(), failure, self.pool_id, package_ids.join(", ")
+        );
+        let mut context = LogContext::new().with_component("StandardPool");
+        context.add("worker", worker.name().to_string());
+        context.add("package_id", package_ids.join(","));
+        self.logger.log(LogLevel::Error, &message, &context);
+    }
+    
+    /// Check if a stage comes before this pool in the stream
+    fn is_behind(&self, target: &PoolType) -> bool {
+        match (self.layout.position(target), self.layout.position(&self.pool_type)) {
+            (Some(target), Some(current)) => target < current,
+            _ => false,
+        }
+    }
+    
+    /// Feed a call outcome to the worker's circuit breaker
+    fn record_outcome(&self, worker: &WorkerRegistration, success: bool) {
+        let Some(breaker) = &worker.breaker else {
+            return;
+        };
+        let changed = if success { breaker.record_success() } else { breaker.record_failure() };
+        let Some(state) = changed else {
+            return;
+        };
+        
+        let snapshot = breaker.snapshot();
+        let message = format!(
+            "Circuit breaker of worker '{}' {} in pool '{}' ({} of {} recent calls failed)",
+            worker.name(), state, self.pool_id, snapshot.failures, snapshot.calls
+        );
+        let mut context = LogContext::new().with_component("StandardPool");
+        context.add("worker", worker.name().to_string());
+        context.add("breaker", state.to_string());
+        let level = if state == BreakerState::Open { LogLevel::Warn } else { LogLevel::Info };
+        self.logger.log(level, &message, &context);
+        
+        let channel_type = self.shared.as_ref().map(|shared| &shared.channel_type);
+        self.monitor.breaker_changed(&self.pool_type, channel_type, worker.name(), &snapshot);
+    }
+    
+    /// Keep the packages a worker produced for another round in this pool
+    fn keep_modified(
+        &self,
+        worker: &WorkerRegistration,
+        inputs: &[Tracked],
+        new_packages: Vec<Package>,
+        outcome: &mut LaneOutcome,
+    ) {
+        let lineage = Self::lineage_of(inputs).extended(worker.name());
+        
+        // Modified packages continue in current pool
+        for new_pkg in new_packages {
+            // Validate output safety
+            if worker.worker.is_output_safe(&new_pkg) {
+                self.monitor.record(&self.pool_type, |m| m.packages_modified += 1);
+                outcome.modified.push(Tracked { package: new_pkg, lineage: lineage.clone() });
+            } else {
+                // Log dead loop warning
+                self.monitor.record(&self.pool_type, |m| m.dead_loops += 1);
+                self.validator.log_dead_loop_warning(
+                    self.logger.as_ref(),
+                    worker.worker.name(),
+                    &new_pkg,
+                );
+            }
+        }
+    }
+    
+    /// Get the lineage outputs of a batch inherit: its longest one
+    fn lineage_of(inputs: &[Tracked]) -> Lineage {
+        inputs
+            .iter()
+            .map(|t| &t.lineage)
+            .max_by_key(|l| l.iterations())
+            .cloned()
+            .unwrap_or_default()
+    }
+    
+    /// Take a package out of processing
+    fn quarantine(&self, package: Tracked, reason: QuarantineReason) {
+        let entry = QuarantinedPackage {
+            package: package.package,
+            pool_type: self.pool_type.clone(),
+            channel_type: self.shared.as_ref().map(|shared| shared.channel_type.clone()),
+            reason,
+            lineage: package.lineage,
+            quarantined_at: chrono::Utc::now(),
+        };
+        
+        let message = format!(
+            "Quarantined package {} in pool '{}': {} (lineage: {})",
+            entry.package.package_id, self.pool_id, entry.reason, entry.lineage
+        );
+        let mut context = LogContext::new().with_component("StandardPool");
+        context.add("package_id", entry.package.package_id.clone());
+        context.add("reason", entry.reason.kind());
+        self.logger.log(LogLevel::Warn, &message, &context);
+        
+        self.monitor.quarantine(entry);
+    }
+    
     /// Sort workers by priority
     fn sort_workers(&mut self) {
         self.workers.sort_by_key(|w| w.priority);
@@ -78,19 +613,36 @@ impl StandardPool {
     }
 }
 
+/// Add a package to a lane, joining the last step if it has the same worker
+fn push_step(steps: &mut Vec<Step>, worker: Option<usize>, package: Tracked) {
+    match steps.last_mut() {
+        Some(step) if step.worker == worker => step.packages.push(package),
+        _ => steps.push(Step { worker, packages: vec![package] }),
+    }
+}
+
+/// Get the channel a package belongs to
+fn channel_of(package: &Package) -> Option<ChannelType> {
+    package
+        .events()
+        .find_map(|event| ChannelType::from_metadata(event.metadata()))
+        .or_else(|| package.extra["channel"].as_str().and_then(ChannelType::parse))
+}
+
 impl Debug for StandardPool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("StandardPool")
             .field("pool_id", &self.pool_id)
             .field("pool_type", &self.pool_type)
-            .field("workers", &self.workers.len())
+            .field("workers", &self.worker_count())
+            .field("config", &self.config)
             .finish()
     }
 }
 
 impl OutputSafe<Package> for StandardPool {
     fn is_output_safe(&self, output: &Package) -> bool {
-        !self.workers.iter().any(|w| w.worker.is_output_safe(output))
+        !self.active_workers().iter().any(|w| w.worker.is_output_safe(output))
     }
 }
 
@@ -101,10 +653,15 @@ impl Pool for StandardPool {
     }
     
     fn pool_type(&self) -> PoolType {
-        self.pool_type
+        self.pool_type.clone()
     }
     
     fn register(&mut self, registration: WorkerRegistration) -> crate::errors::Result<()> {
+        if let Some(shared) = &self.shared {
+            let scope = WorkerScope::Channel(shared.channel_type.clone());
+            return shared.registry.register(self.pool_type.clone(), scope, registration);
+        }
+        
         let worker_name = registration.worker.name();
         
         // Check if worker already exists
@@ -134,6 +691,11 @@ impl Pool for StandardPool {
     }
     
     fn unregister(&mut self, name: &str) -> crate::errors::Result<()> {
+        if let Some(shared) = &self.shared {
+            let scope = WorkerScope::Channel(shared.channel_type.clone());
+            return shared.registry.unregister(&self.pool_type, &scope, name);
+        }
+        
         if let Some(&idx) = self.worker_index.get(name) {
             self.workers.remove(idx);
             self.sort_workers();
@@ -149,18 +711,23 @@ impl Pool for StandardPool {
     }
     
     fn worker_names(&self) -> Vec<String> {
-        self.workers.iter().map(|w| w.worker.name().to_string()).collect()
+        self.active_workers().iter().map(|w| w.worker.name().to_string()).collect()
     }
     
     fn worker_count(&self) -> usize {
-        self.workers.len()
+        self.active_workers().len()
     }
     
     fn has_worker(&self, name: &str) -> bool {
-        self.worker_index.contains_key(name)
+        self.active_workers().iter().any(|w| w.worker.name() == name)
     }
     
     fn set_worker_priority(&mut self, name: &str, new_priority: u32) -> crate::errors::Result<()> {
+        if let Some(shared) = &self.shared {
+            let scope = WorkerScope::Channel(shared.channel_type.clone());
+            return shared.registry.set_priority(&self.pool_type, &scope, name, new_priority);
+        }
+        
         // Check if new priority is already used
         if let Some(&idx) = self.worker_index.get(name) {
             if self.workers.iter().any(|w| w.priority == new_priority && w.worker.name() != name) {
@@ -185,62 +752,36 @@ impl Pool for StandardPool {
     }
     
     async fn process_batch(&self, packages: Vec<Package>) -> Vec<Package> {
+        let workers = self.active_workers();
+        let concurrency = match self.config.max_concurrency {
+            0 => usize::MAX,
+            max => max,
+        };
+        self.monitor.record(&self.pool_type, |m| m.packages_in += packages.len() as u64);
         let mut next_pool_packages: Vec<Package> = Vec::new();
-        let mut current_pool_packages: Vec<Package> = packages;
+        let mut current_pool_packages: Vec<Tracked> = packages
+            .into_iter()
+            .map(|package| Tracked { package, lineage: Lineage::default() })
+            .collect();
         
         // Process packages through workers until no more modifications
         while !current_pool_packages.is_empty() {
-            let mut next_batch: Vec<Package> = Vec::new();
+            let lanes = self.plan_lanes(&workers, current_pool_packages);
             
-            for package in current_pool_packages {
-                let mut processed = false;
-                
-                // Iterate through workers in priority order
-                for worker in &self.workers {
-                    // Check if worker matches any target site in package
-                    if worker.matches_any(&package.target_sites) {
-                        // Worker matches, process (clone to preserve ownership)
-                        let result = worker.worker.handle_batch(vec![package.clone()]).await;
-                        match result {
-                            WorkerResult::Release => {
-                                // Worker completed, package moves to next pool
-                                next_pool_packages.push(package.clone());
-                                processed = true;
-                                break; // Break out of worker loop
-                            }
-                            WorkerResult::Modify(new_packages) => {
-                                // Modified packages continue in current pool
-                                for new_pkg in new_packages {
-                                    // Validate output safety
-                                    if worker.worker.is_output_safe(&new_pkg) {
-                                        next_batch.push(new_pkg);
-                                    } else {
-                                        // Log dead loop warning
-                                        self.validator.log_dead_loop_warning(
-                                            self.logger.as_ref(),
-                                            worker.worker.name(),
-                                            &new_pkg,
-                                        );
-                                    }
-                                }
-                                processed = true;
-                                break; // Break out of worker loop
-                            }
-                        }
-                    }
-                    // No match, continue to next worker
-                }
-                
-                // If no worker processed the package, move to next pool
-                if !processed {
-                    next_pool_packages.push(package);
-                }
-            }
+            // Lanes are independent; `buffered` keeps their results in order
+            let outcomes: Vec<LaneOutcome> = stream::iter(lanes.into_iter().map(|lane| self.run_lane(&workers, lane)))
+                .buffered(concurrency)
+                .collect()
+                .await;
             
-            // Move next_batch to current_pool_packages for next iteration
-            current_pool_packages = next_batch;
+            current_pool_packages = Vec::new();
+            for outcome in outcomes {
+                next_pool_packages.extend(outcome.released);
+                current_pool_packages.extend(outcome.modified);
+            }
         }
         
+        self.monitor.record(&self.pool_type, |m| m.packages_released += next_pool_packages.len() as u64);
         next_pool_packages
     }
 }
@@ -250,33 +791,9 @@ mod tests {
     use super::*;
     use crate::events::TargetSite;
     use crate::logging::StructuredLogger;
+    use crate::workers::testing::{TestWorker, WorkerCalls};
     use crate::workers::WorkerType;
 
-    #[derive(Debug)]
-    struct TestWorker {
-        name: String,
-        worker_type: WorkerType,
-    }
-
-    #[async_trait]
-    impl crate::workers::Worker for TestWorker {
-        fn name(&self) -> &str {
-            &self.name
-        }
-
-        fn worker_type(&self) -> WorkerType {
-            self.worker_type.clone()
-        }
-
-        fn matches(&self, _target_site: &TargetSite) -> bool {
-            true
-        }
-
-        async fn handle_batch(&self, _packages: Vec<Package>) -> WorkerResult {
-            WorkerResult::release()
-        }
-    }
-
     fn create_test_pool(pool_type: PoolType) -> StandardPool {
         use crate::logging::formatters::JsonFormatter;
         use crate::logging::writers::ConsoleWriter;
@@ -309,11 +826,7 @@ mod tests {
     async fn test_register_worker() {
         let mut pool = create_test_pool(PoolType::Input);
         
-        let worker = Box::new(TestWorker {
-            name: "test_worker".to_string(),
-            worker_type: WorkerType::Input,
-        });
-        let registration = WorkerRegistration::new(worker, crate::workers::MatchingRule::All, 0);
+        let registration = TestWorker::new("test_worker").with_type(WorkerType::Input).register(0);
         
         assert!(pool.register(registration).is_ok());
         assert_eq!(pool.worker_count(), 1);
@@ -324,18 +837,10 @@ mod tests {
     fn test_register_duplicate_worker() {
         let mut pool = create_test_pool(PoolType::Input);
         
-        let worker1 = Box::new(TestWorker {
-            name: "test_worker".to_string(),
-            worker_type: WorkerType::Input,
-        });
-        let reg1 = WorkerRegistration::new(worker1, crate::workers::MatchingRule::All, 0);
+        let reg1 = TestWorker::new("test_worker").with_type(WorkerType::Input).register(0);
         pool.register(reg1).unwrap();
         
-        let worker2 = Box::new(TestWorker {
-            name: "test_worker".to_string(),
-            worker_type: WorkerType::Input,
-        });
-        let reg2 = WorkerRegistration::new(worker2, crate::workers::MatchingRule::All, 1);
+        let reg2 = TestWorker::new("test_worker").with_type(WorkerType::Input).register(1);
         
         assert!(pool.register(reg2).is_err());
     }
@@ -344,11 +849,7 @@ mod tests {
     fn test_unregister_worker() {
         let mut pool = create_test_pool(PoolType::Input);
         
-        let worker = Box::new(TestWorker {
-            name: "test_worker".to_string(),
-            worker_type: WorkerType::Input,
-        });
-        let registration = WorkerRegistration::new(worker, crate::workers::MatchingRule::All, 0);
+        let registration = TestWorker::new("test_worker").with_type(WorkerType::Input).register(0);
         pool.register(registration).unwrap();
         
         assert!(pool.unregister("test_worker").is_ok());
@@ -360,11 +861,7 @@ mod tests {
     async fn test_set_worker_priority() {
         let mut pool = create_test_pool(PoolType::Input);
         
-        let worker = Box::new(TestWorker {
-            name: "test_worker".to_string(),
-            worker_type: WorkerType::Input,
-        });
-        let registration = WorkerRegistration::new(worker, crate::workers::MatchingRule::All, 0);
+        let registration = TestWorker::new("test_worker").with_type(WorkerType::Input).register(0);
         pool.register(registration).unwrap();
         
         assert!(pool.set_worker_priority("test_worker", 5).is_ok());
@@ -384,11 +881,7 @@ mod tests {
     async fn test_process_batch_release() {
         let mut pool = create_test_pool(PoolType::Input);
         
-        let worker = Box::new(TestWorker {
-            name: "test_worker".to_string(),
-            worker_type: WorkerType::Input,
-        });
-        let registration = WorkerRegistration::new(worker, crate::workers::MatchingRule::All, 0);
+        let registration = TestWorker::new("test_worker").with_type(WorkerType::Input).register(0);
         pool.register(registration).unwrap();
         
         let packages = vec![Package::new()];
@@ -396,4 +889,413 @@ mod tests {
         
         assert_eq!(result.len(), 1); // Package released to next pool
     }
+
+    /// Sleeps briefly so batches of different channels overlap
+    fn recording_worker() -> TestWorker {
+        TestWorker::new("recording_worker").with_delay(std::time::Duration::from_millis(20))
+    }
+
+    fn channel_package(channel: &str) -> Package {
+        Package::new()
+            .with_extra(serde_json::json!({"channel": channel}))
+            .with_target_site(TargetSite::worker("recording_worker"))
+    }
+
+    fn recording_pool(config: PoolConfig) -> (StandardPool, Arc<WorkerCalls>) {
+        let worker = recording_worker();
+        let calls = worker.calls();
+        let mut pool = create_test_pool(PoolType::Process).with_config(config);
+        pool.register(worker.register(0)).unwrap();
+        (pool, calls)
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_groups_channels() {
+        let (pool, recorder) = recording_pool(PoolConfig::new());
+
+        let packages = vec![
+            channel_package("group:g1"),
+            channel_package("group:g2"),
+            channel_package("group:g1"),
+        ];
+        let ids: Vec<_> = packages.iter().map(|p| p.package_id.clone()).collect();
+        let result = pool.process_batch(packages).await;
+
+        // One batch per channel, run concurrently, order kept within a channel
+        let mut batches = recorder.channels();
+        batches.sort();
+        assert_eq!(batches, vec![vec!["group:g1", "group:g1"], vec!["group:g2"]]);
+        assert_eq!(recorder.peak(), 2);
+        assert_eq!(result.iter().map(|p| p.package_id.as_str()).collect::<Vec<_>>(), vec![&ids[0], &ids[2], &ids[1]]);
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_concurrency_limit() {
+        let (pool, recorder) = recording_pool(PoolConfig::new().with_max_concurrency(1));
+
+        let packages = vec![channel_package("group:g1"), channel_package("group:g2")];
+        assert_eq!(pool.process_batch(packages).await.len(), 2);
+        assert_eq!(recorder.count(), 2);
+        assert_eq!(recorder.peak(), 1);
+
+        // Without ordering all packages of a worker form a single batch
+        let (pool, recorder) = recording_pool(PoolConfig::new().with_preserve_order(false));
+        let packages = vec![channel_package("group:g1"), channel_package("group:g2")];
+        assert_eq!(pool.process_batch(packages).await.len(), 2);
+        assert_eq!(recorder.channels(), vec![vec!["group:g1", "group:g2"]]);
+    }
+
+    fn forwarding_pool(config: PoolConfig, chain: &[(&str, &str)]) -> StandardPool {
+        let mut pool = create_test_pool(PoolType::Process).with_config(config);
+        for (priority, (name, next)) in chain.iter().enumerate() {
+            let worker = TestWorker::new(name).matching_own_site().forwarding_to(next);
+            pool.register(worker.register(priority as u32)).unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_quarantines_cycles() {
+        use crate::events::{EventEnum, MetaEvent, SystemEventType};
+        let pool = forwarding_pool(PoolConfig::new(), &[("a", "b"), ("b", "a")]);
+        let mut events = pool.monitor().subscribe();
+
+        let package = Package::new().with_target_site(TargetSite::worker("a"));
+        assert!(pool.process_batch(vec![package]).await.is_empty());
+
+        let quarantined = pool.monitor().quarantined();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].reason, QuarantineReason::Cycle { worker: "a".to_string() });
+        assert_eq!(quarantined[0].lineage.workers, vec!["a", "b"]);
+
+        let metrics = pool.monitor().metrics(&PoolType::Process);
+        assert_eq!((metrics.packages_in, metrics.batches, metrics.cycles), (1, 2, 1));
+
+        match events.try_recv().unwrap() {
+            EventEnum::Meta(MetaEvent::System { event_type, data, .. }) => {
+                assert_eq!(event_type, SystemEventType::PackageQuarantined);
+                assert_eq!(data["reason"], "cycle");
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_iteration_limit() {
+        let config = PoolConfig::new().with_max_iterations(2);
+        let pool = forwarding_pool(config, &[("a", "b"), ("b", "c"), ("c", "d")]);
+
+        let package = Package::new().with_target_site(TargetSite::worker("a"));
+        assert!(pool.process_batch(vec![package]).await.is_empty());
+
+        let quarantined = pool.monitor().drain_quarantine();
+        assert_eq!(quarantined[0].reason, QuarantineReason::IterationLimit { iterations: 2 });
+        assert_eq!(pool.monitor().metrics(&PoolType::Process).iteration_limits, 1);
+        assert!(pool.monitor().quarantined().is_empty());
+
+        // Without the limit the chain ends at `d`, which no worker handles
+        let pool = forwarding_pool(PoolConfig::new(), &[("a", "b"), ("b", "c"), ("c", "d")]);
+        let package = Package::new().with_target_site(TargetSite::worker("a"));
+        let released = pool.process_batch(vec![package]).await;
+        assert_eq!(released[0].target_sites[0].site_type, crate::events::SiteType::Worker("d".to_string()));
+    }
+
+    fn scripted_pool(config: PoolConfig, results: Vec<WorkerResult>) -> StandardPool {
+        let mut pool = create_test_pool(PoolType::Process).with_config(config);
+        let worker = TestWorker::new("scripted_worker").matching_own_site().with_results(results);
+        pool.register(worker.register(0)).unwrap();
+        pool
+    }
+
+    fn scripted_package() -> Package {
+        Package::new().with_target_site(TargetSite::worker("scripted_worker"))
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_drop_and_reply() {
+        use crate::adapters::{Message, Target};
+        use crate::engine::dispatch::{outbound_messages, OutboundMessage};
+        use crate::pools::take_destination;
+
+        let pool = scripted_pool(PoolConfig::new(), vec![WorkerResult::drop("spam")]);
+        assert!(pool.process_batch(vec![scripted_package()]).await.is_empty());
+        assert_eq!(pool.monitor().metrics(&PoolType::Process).packages_dropped, 1);
+
+        let reply = OutboundMessage::new(Target::User { user_id: "u1".to_string() }, Message::Text { content: "hi".to_string() });
+        let pool = scripted_pool(PoolConfig::new(), vec![WorkerResult::reply(vec![reply.clone()])]);
+        let mut released = pool.process_batch(vec![scripted_package()]).await;
+        assert_eq!(outbound_messages(&released[0]), vec![reply.clone()]);
+        assert_eq!(take_destination(&mut released[0]), Some(Destination::Exit));
+
+        // A step of several packages replies once
+        let pool = scripted_pool(PoolConfig::new(), vec![WorkerResult::reply(vec![reply.clone()])]);
+        let mut released = pool.process_batch(vec![scripted_package(), scripted_package()]).await;
+        assert_eq!(released.len(), 2);
+        assert_eq!(outbound_messages(&released[0]), vec![reply]);
+        assert!(outbound_messages(&released[1]).is_empty());
+        assert_eq!(take_destination(&mut released[1]), Some(Destination::Exit));
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_jump() {
+        use crate::pools::take_destination;
+
+        let pool = scripted_pool(PoolConfig::new(), vec![WorkerResult::jump(PoolType::Output, vec![Package::new()])]);
+        let mut released = pool.process_batch(vec![scripted_package()]).await;
+        assert_eq!(take_destination(&mut released[0]), Some(Destination::Pool(PoolType::Output)));
+
+        // Jumping back is quarantined as a potential dead loop
+        let pool = scripted_pool(PoolConfig::new(), vec![WorkerResult::jump(PoolType::Input, vec![Package::new()])]);
+        assert!(pool.process_batch(vec![scripted_package()]).await.is_empty());
+        assert_eq!(pool.monitor().metrics(&PoolType::Process).dead_loops, 1);
+        let quarantined = pool.monitor().quarantined();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].reason, QuarantineReason::BackwardJump { target: PoolType::Input });
+        assert_eq!(quarantined[0].lineage.workers, vec!["scripted_worker"]);
+
+        // Jumping to the current pool is a Modify and still checked for self-matching
+        let pool = scripted_pool(PoolConfig::new(), vec![WorkerResult::jump(PoolType::Process, vec![scripted_package()])]);
+        assert!(pool.process_batch(vec![scripted_package()]).await.is_empty());
+        assert_eq!(pool.monitor().metrics(&PoolType::Process).dead_loops, 1);
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_defer() {
+        let delay = std::time::Duration::from_millis(5);
+        let pool = scripted_pool(PoolConfig::new(), vec![WorkerResult::defer(delay), WorkerResult::defer(delay)]);
+        assert_eq!(pool.process_batch(vec![scripted_package()]).await.len(), 1);
+        let metrics = pool.monitor().metrics(&PoolType::Process);
+        assert_eq!((metrics.batches, metrics.deferrals), (3, 2));
+
+        let config = PoolConfig::new().with_max_deferrals(1);
+        let pool = scripted_pool(config, vec![WorkerResult::defer(delay), WorkerResult::defer(delay)]);
+        assert!(pool.process_batch(vec![scripted_package()]).await.is_empty());
+        assert_eq!(pool.monitor().quarantined()[0].reason, QuarantineReason::DeferLimit { deferrals: 2 });
+    }
+
+    fn failing_registration(name: &str, hang: bool, priority: u32) -> WorkerRegistration {
+        let worker = TestWorker::new(name);
+        let worker = if hang { worker.hanging() } else { worker.panicking() };
+        worker.register(priority)
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_isolates_panics() {
+        let mut pool = create_test_pool(PoolType::Process);
+        pool.register(failing_registration("panicking", false, 0)).unwrap();
+
+        let packages = vec![Package::new().with_target_site(TargetSite::worker("x")), Package::new()];
+        let released = pool.process_batch(packages).await;
+
+        // The failed batch is skipped; the package no worker matched passes too
+        assert_eq!(released.len(), 2);
+        let metrics = pool.monitor().metrics(&PoolType::Process);
+        assert_eq!(metrics.worker_panics, 1);
+        assert_eq!(metrics.worker_failures.get("panicking"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_worker_timeout() {
+        let config = PoolConfig::new().with_on_failure(crate::pools::FailureAction::Quarantine);
+        let mut pool = create_test_pool(PoolType::Process).with_config(config);
+        let registration = failing_registration("hanging", true, 0).with_timeout(std::time::Duration::from_millis(20));
+        pool.register(registration).unwrap();
+
+        let package = Package::new().with_target_site(TargetSite::worker("x"));
+        assert!(pool.process_batch(vec![package]).await.is_empty());
+
+        assert_eq!(pool.monitor().metrics(&PoolType::Process).worker_timeouts, 1);
+        let quarantined = pool.monitor().quarantined();
+        assert_eq!(
+            quarantined[0].reason,
+            QuarantineReason::WorkerFailed {
+                worker: "hanging".to_string(),
+                error: "timed out after 20ms".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_circuit_breaker() {
+        use crate::events::{EventEnum, MetaEvent, SystemEventType};
+        use crate::workers::CircuitBreakerConfig;
+
+        let mut pool = create_test_pool(PoolType::Process);
+        let mut events = pool.monitor().subscribe();
+        let breaker = CircuitBreakerConfig::new().with_min_calls(2).with_cooldown_ms(60_000);
+        pool.register(failing_registration("flaky", false, 0).with_circuit_breaker(breaker)).unwrap();
+        pool.register(TestWorker::new("fallback").register(1)).unwrap();
+
+        for _ in 0..3 {
+            let package = Package::new().with_target_site(TargetSite::worker("x"));
+            assert_eq!(pool.process_batch(vec![package]).await.len(), 1);
+        }
+
+        // The third package went to the next worker without calling the open one
+        let metrics = pool.monitor().metrics(&PoolType::Process);
+        assert_eq!(metrics.worker_panics, 2);
+        assert_eq!(metrics.breaker_trips, 1);
+        assert_eq!(metrics.breaker_bypasses, 1);
+        let flaky = pool.get_worker("flaky").unwrap();
+        assert_eq!(flaky.breaker.as_ref().unwrap().state(), BreakerState::Open);
+        assert!(!flaky.allows_call());
+
+        match events.try_recv().unwrap() {
+            EventEnum::Meta(MetaEvent::System { event_type, data, .. }) => {
+                assert_eq!(event_type, SystemEventType::WorkerCircuitOpened);
+                assert_eq!(data["worker"], "flaky");
+                assert_eq!(data["breaker"]["failures"], 2);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_quarantine_keeps_breaker_trial() {
+        use crate::workers::CircuitBreakerConfig;
+
+        let mut pool = create_test_pool(PoolType::Process);
+        let breaker = CircuitBreakerConfig::new().with_min_calls(1).with_cooldown_ms(50);
+        pool.register(TestWorker::new("a").matching_own_site().register(0).with_circuit_breaker(breaker)).unwrap();
+        let worker = pool.get_worker("a").unwrap();
+        assert_eq!(worker.breaker.as_ref().unwrap().record_failure(), Some(BreakerState::Open));
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // A package about to close a cycle is quarantined without using up the trial
+        let package = Tracked {
+            package: Package::new().with_target_site(TargetSite::worker("a")),
+            lineage: Lineage { workers: vec!["a".to_string()] },
+        };
+        assert!(pool.route(pool.workers_sorted(), 0, package).is_none());
+        assert_eq!(pool.monitor().quarantined().len(), 1);
+        assert!(pool.get_worker("a").unwrap().allows_call());
+    }
+
+    fn limited_pool(limits: crate::workers::WorkerLimits) -> (StandardPool, Arc<WorkerCalls>) {
+        let worker = recording_worker();
+        let calls = worker.calls();
+        let mut pool = create_test_pool(PoolType::Process);
+        pool.register(worker.register(0).with_limits(limits)).unwrap();
+        pool.register(TestWorker::new("fallback").register(1)).unwrap();
+        (pool, calls)
+    }
+
+    fn two_channels() -> Vec<Package> {
+        vec![channel_package("group:g1"), channel_package("group:g2")]
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_limit_queue() {
+        use crate::workers::WorkerLimits;
+        let (pool, recorder) = limited_pool(WorkerLimits::new().with_max_concurrency(1));
+
+        assert_eq!(pool.process_batch(two_channels()).await.len(), 2);
+
+        // Both channels reached the worker, one after the other
+        assert_eq!(recorder.count(), 2);
+        assert_eq!(recorder.peak(), 1);
+        assert_eq!(pool.monitor().metrics(&PoolType::Process).overflow_queued, 1);
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_limit_skip() {
+        use crate::workers::{OverflowAction, WorkerLimits};
+        let limits = WorkerLimits::new().with_max_concurrency(1).with_overflow(OverflowAction::Skip);
+        let (pool, recorder) = limited_pool(limits);
+
+        assert_eq!(pool.process_batch(two_channels()).await.len(), 2);
+
+        // The second channel went to the fallback worker
+        assert_eq!(recorder.count(), 1);
+        assert_eq!(pool.monitor().metrics(&PoolType::Process).overflow_skips, 1);
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_limit_drop() {
+        use crate::events::{EventEnum, MetaEvent, SystemEventType};
+        use crate::workers::{OverflowAction, RateLimit, WorkerLimits};
+        let limits = WorkerLimits::new()
+            .with_rate_limit(RateLimit::per_minute(1))
+            .with_overflow(OverflowAction::Drop);
+        let (pool, recorder) = limited_pool(limits);
+        let mut events = pool.monitor().subscribe();
+
+        assert_eq!(pool.process_batch(two_channels()).await.len(), 1);
+
+        assert_eq!(recorder.count(), 1);
+        assert_eq!(pool.monitor().metrics(&PoolType::Process).overflow_drops, 1);
+        match events.try_recv().unwrap() {
+            EventEnum::Meta(MetaEvent::System { event_type, data, .. }) => {
+                assert_eq!(event_type, SystemEventType::WorkerOverflow);
+                assert_eq!(data["worker"], "recording_worker");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_windowed_batching() {
+        use crate::workers::BatchPolicy;
+        let worker = recording_worker();
+        let recorder = worker.calls();
+        let registration = worker.register(0).with_batching(BatchPolicy::new(2, std::time::Duration::from_secs(5)));
+
+        // Pools of two channels share the registration and its batch window
+        let monitor = Arc::new(PoolMonitor::new());
+        let mut first = create_test_pool(PoolType::Process).with_monitor(monitor.clone());
+        let mut second = create_test_pool(PoolType::Process).with_monitor(monitor.clone());
+        first.register(registration.clone()).unwrap();
+        second.register(registration).unwrap();
+
+        let (released_first, released_second) = tokio::join!(
+            first.process_batch(vec![channel_package("group:g1")]),
+            second.process_batch(vec![channel_package("group:g2")]),
+        );
+        assert_eq!(released_first.len(), 1);
+        assert_eq!(released_second.len(), 1);
+
+        // The full window was flushed into a single call
+        assert_eq!(recorder.channels(), vec![vec!["group:g1", "group:g2"]]);
+        let metrics = monitor.metrics(&PoolType::Process);
+        assert_eq!(metrics.batch_windows, 1);
+        assert_eq!(metrics.batched_packages, 2);
+        assert_eq!(metrics.batches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_process_batch_windowed_reply_sent_once() {
+        use crate::adapters::{Message, Target};
+        use crate::engine::dispatch::{outbound_messages, OutboundMessage};
+        use crate::pools::take_destination;
+        use crate::workers::BatchPolicy;
+
+        let reply = OutboundMessage::new(Target::User { user_id: "u1".to_string() }, Message::Text { content: "hi".to_string() });
+        let worker = recording_worker().with_results(vec![WorkerResult::reply(vec![reply.clone()])]);
+        let registration = worker.register(0).with_batching(BatchPolicy::new(2, std::time::Duration::from_secs(5)));
+
+        let monitor = Arc::new(PoolMonitor::new());
+        let mut first = create_test_pool(PoolType::Process).with_monitor(monitor.clone());
+        let mut second = create_test_pool(PoolType::Process).with_monitor(monitor.clone());
+        first.register(registration.clone()).unwrap();
+        second.register(registration).unwrap();
+
+        let (released_first, released_second) = tokio::join!(
+            first.process_batch(vec![channel_package("group:g1")]),
+            second.process_batch(vec![channel_package("group:g2")]),
+        );
+
+        // One call for both channels sends its reply once
+        let messages: Vec<OutboundMessage> = released_first
+            .iter()
+            .chain(&released_second)
+            .flat_map(outbound_messages)
+            .collect();
+        assert_eq!(messages, vec![reply]);
+        assert_eq!(monitor.metrics(&PoolType::Process).replies, 1);
+
+        // Both channels' packages leave the stream, as an unbatched reply would
+        for mut package in released_first.into_iter().chain(released_second) {
+            assert_eq!(take_destination(&mut package), Some(Destination::Exit));
+        }
+    }
 }
@@ -33,7 +33,8 @@ pub trait Pool: Send + Sync + Debug + OutputSafe<Package> {
     fn set_worker_priority(&mut self, name: &str, new_priority: u32) -> crate::errors::Result<()>;
     
     /// Process a batch of packages asynchronously
-    /// Returns packages that should go to the next pool
+    /// Returns packages that should go to the next pool, or to the
+    /// `Destination` recorded on them with `set_destination`
     async fn process_batch(&self, packages: Vec<Package>) -> Vec<Package>;
 }
 
@@ -61,7 +62,7 @@ mod tests {
         }
 
         fn pool_type(&self) -> PoolType {
-            self.pool_type
+            self.pool_type.clone()
         }
 
         fn register(&mut self, _registration: WorkerRegistration) -> crate::errors::Result<()> {
@@ -1,11 +1,19 @@
 //! Pool type definitions
 
+use crate::events::Package;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
-/// Pool type classification - 9 pool types in processing order
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+/// Key of the jump destination in `Package::extra`
+pub const DESTINATION_KEY: &str = "destination";
+
+/// Pool type classification - 9 built-in pool types in processing order
+/// plus user-defined stages
+///
+/// Pool types are (de)serialized by name; any name that is not a built-in
+/// pool names a custom stage.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum PoolType {
     /// 预输入池
     PreInput,
@@ -25,10 +33,27 @@ pub enum PoolType {
     Output,
     /// 后输出池
     PostOutput,
+    /// 自定义阶段 - 由 `StageLayout` 插入到内置池之间
+    Custom(String),
 }
 
 impl PoolType {
-    /// Get all pool types in processing order
+    /// Create a custom stage type
+    pub fn custom(name: impl Into<String>) -> Self {
+        Self::Custom(name.into())
+    }
+    
+    /// Get a built-in pool type by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        Self::processing_order().into_iter().find(|pool_type| pool_type.to_string() == name)
+    }
+    
+    /// Check if this is a user-defined stage
+    pub fn is_custom(&self) -> bool {
+        matches!(self, Self::Custom(_))
+    }
+    
+    /// Get all built-in pool types in processing order
     pub fn processing_order() -> Vec<Self> {
         vec![
             Self::PreInput,
@@ -44,6 +69,8 @@ impl PoolType {
     }
     
     /// Check if this pool allows third-party worker registration
+    ///
+    /// Custom stages decide this in their `StageDefinition`.
     pub fn allows_third_party(&self) -> bool {
         matches!(
             self,
@@ -51,18 +78,21 @@ impl PoolType {
         )
     }
     
-    /// Get the pool position in processing order (0-based)
-    pub fn position(&self) -> usize {
+    /// Get the pool position in the built-in processing order (0-based)
+    ///
+    /// Custom stages have no fixed position; see `StageLayout::position`.
+    pub fn position(&self) -> Option<usize> {
         match self {
-            Self::PreInput => 0,
-            Self::Input => 1,
-            Self::InputMiddle => 2,
-            Self::PreProcess => 3,
-            Self::ProcessMiddle => 4,
-            Self::Process => 5,
-            Self::PostProcess => 6,
-            Self::Output => 7,
-            Self::PostOutput => 8,
+            Self::PreInput => Some(0),
+            Self::Input => Some(1),
+            Self::InputMiddle => Some(2),
+            Self::PreProcess => Some(3),
+            Self::ProcessMiddle => Some(4),
+            Self::Process => Some(5),
+            Self::PostProcess => Some(6),
+            Self::Output => Some(7),
+            Self::PostOutput => Some(8),
+            Self::Custom(_) => None,
         }
     }
 }
@@ -79,10 +109,141 @@ impl std::fmt::Display for PoolType {
             Self::PostProcess => write!(f, "post_process"),
             Self::Output => write!(f, "output"),
             Self::PostOutput => write!(f, "post_output"),
+            Self::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl From<String> for PoolType {
+    fn from(name: String) -> Self {
+        Self::builtin(&name).unwrap_or(Self::Custom(name))
+    }
+}
+
+impl From<PoolType> for String {
+    fn from(pool_type: PoolType) -> Self {
+        pool_type.to_string()
+    }
+}
+
+/// Execution settings of a pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolConfig {
+    /// Maximum number of worker batches running at once (0 = unlimited)
+    pub max_concurrency: usize,
+    
+    /// Process packages of the same channel one batch at a time, in arrival order
+    pub preserve_order: bool,
+    
+    /// Maximum Modify iterations behind a package before it is quarantined
+    pub max_iterations: usize,
+    
+    /// Maximum `Defer` retries of a batch before its packages are quarantined
+    pub max_deferrals: u32,
+    
+    /// Default `handle_batch` timeout in milliseconds (0 = no timeout)
+    pub worker_timeout_ms: u64,
+    
+    /// What happens to packages whose worker timed out or panicked
+    pub on_failure: FailureAction,
+}
+
+/// Handling of packages whose worker failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureAction {
+    /// Move the packages on as if the worker had released them
+    #[default]
+    Skip,
+    
+    /// Take the packages out of processing
+    Quarantine,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 16,
+            preserve_order: true,
+            max_iterations: 100,
+            max_deferrals: 3,
+            worker_timeout_ms: 30_000,
+            on_failure: FailureAction::Skip,
         }
     }
 }
 
+impl PoolConfig {
+    /// Create a new pool config
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Set max concurrency
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max;
+        self
+    }
+    
+    /// Enable or disable per-channel ordering
+    pub fn with_preserve_order(mut self, enabled: bool) -> Self {
+        self.preserve_order = enabled;
+        self
+    }
+    
+    /// Set max iterations
+    pub fn with_max_iterations(mut self, max: usize) -> Self {
+        self.max_iterations = max;
+        self
+    }
+    
+    /// Set max deferrals
+    pub fn with_max_deferrals(mut self, max: u32) -> Self {
+        self.max_deferrals = max;
+        self
+    }
+    
+    /// Set the default worker timeout (milliseconds)
+    pub fn with_worker_timeout_ms(mut self, timeout: u64) -> Self {
+        self.worker_timeout_ms = timeout;
+        self
+    }
+    
+    /// Set the handling of failed workers
+    pub fn with_on_failure(mut self, action: FailureAction) -> Self {
+        self.on_failure = action;
+        self
+    }
+}
+
+/// Where a package released by a pool continues, if not in the next pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Destination {
+    /// Skip ahead to a later pool
+    Pool(PoolType),
+    
+    /// Leave the stream
+    Exit,
+}
+
+/// Set the destination of a package
+pub fn set_destination(package: &mut Package, destination: Destination) {
+    if !package.extra.is_object() {
+        package.extra = serde_json::json!({});
+    }
+    if let Ok(value) = serde_json::to_value(destination) {
+        package.extra[DESTINATION_KEY] = value;
+    }
+}
+
+/// Remove and return the destination of a package
+pub fn take_destination(package: &mut Package) -> Option<Destination> {
+    let value = package.extra.as_object_mut()?.remove(DESTINATION_KEY)?;
+    serde_json::from_value(value).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,9 +272,21 @@ mod tests {
 
     #[test]
     fn test_pool_type_position() {
-        assert_eq!(PoolType::PreInput.position(), 0);
-        assert_eq!(PoolType::Input.position(), 1);
-        assert_eq!(PoolType::PostOutput.position(), 8);
+        assert_eq!(PoolType::PreInput.position(), Some(0));
+        assert_eq!(PoolType::Input.position(), Some(1));
+        assert_eq!(PoolType::PostOutput.position(), Some(8));
+        assert_eq!(PoolType::custom("moderation").position(), None);
+    }
+
+    #[test]
+    fn test_pool_type_serde() {
+        assert_eq!(serde_json::to_value(PoolType::PreProcess).unwrap(), "pre_process");
+        assert_eq!(serde_json::to_value(PoolType::custom("moderation")).unwrap(), "moderation");
+        assert_eq!(serde_json::from_value::<PoolType>("process".into()).unwrap(), PoolType::Process);
+        assert_eq!(
+            serde_json::from_value::<PoolType>("moderation".into()).unwrap(),
+            PoolType::custom("moderation")
+        );
     }
 
     #[test]
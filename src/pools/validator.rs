@@ -2,6 +2,105 @@
 
 use crate::events::Package;
 use crate::logging::traits::Logger;
+use crate::pools::PoolType;
+use serde::{Deserialize, Serialize};
+
+/// Processing history of a package inside one pool
+///
+/// Packages produced by `WorkerResult::Modify` inherit the lineage of the
+/// batch they came from, extended by the worker that produced them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lineage {
+    /// Workers that modified the package or its ancestors, in order
+    pub workers: Vec<String>,
+}
+
+impl Lineage {
+    /// Number of Modify iterations behind the package
+    pub fn iterations(&self) -> usize {
+        self.workers.len()
+    }
+    
+    /// Check if a worker already modified the package or its ancestors
+    pub fn contains(&self, worker_name: &str) -> bool {
+        self.workers.iter().any(|w| w == worker_name)
+    }
+    
+    /// Get the lineage of packages a worker produced from this one
+    pub fn extended(&self, worker_name: &str) -> Self {
+        let mut workers = self.workers.clone();
+        workers.push(worker_name.to_string());
+        Self { workers }
+    }
+}
+
+impl std::fmt::Display for Lineage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.workers.join(" -> "))
+    }
+}
+
+/// Why a package was taken out of a pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuarantineReason {
+    /// The package went through too many Modify iterations
+    IterationLimit {
+        /// Iterations behind the package
+        iterations: usize,
+    },
+    
+    /// The package would be handed back to a worker that already modified it
+    Cycle {
+        /// Worker closing the cycle
+        worker: String,
+    },
+    
+    /// The worker kept deferring the package
+    DeferLimit {
+        /// Deferrals of the batch
+        deferrals: u32,
+    },
+    
+    /// The worker timed out or panicked
+    WorkerFailed {
+        /// Failed worker
+        worker: String,
+        /// Failure description
+        error: String,
+    },
+    
+    /// The worker sent the package back to an earlier pool
+    BackwardJump {
+        /// Pool the worker jumped to
+        target: PoolType,
+    },
+}
+
+impl QuarantineReason {
+    /// Short machine-readable name
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::IterationLimit { .. } => "iteration_limit",
+            Self::Cycle { .. } => "cycle",
+            Self::DeferLimit { .. } => "defer_limit",
+            Self::WorkerFailed { .. } => "worker_failed",
+            Self::BackwardJump { .. } => "backward_jump",
+        }
+    }
+}
+
+impl std::fmt::Display for QuarantineReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IterationLimit { iterations } => write!(f, "exceeded {} iterations", iterations),
+            Self::Cycle { worker } => write!(f, "cycle back to worker '{}'", worker),
+            Self::DeferLimit { deferrals } => write!(f, "deferred {} times", deferrals),
+            Self::WorkerFailed { worker, error } => write!(f, "worker '{}' failed: {}", worker, error),
+            Self::BackwardJump { target } => write!(f, "jumped back to pool '{}'", target),
+        }
+    }
+}
 
 /// Pool validator - checks for dead loops and validation issues
 #[derive(Debug, Clone)]
@@ -32,6 +131,15 @@ impl PoolValidator {
         ), &LogContext::new());
     }
     
+    /// Log a jump back to an earlier pool
+    pub fn log_backward_jump_warning(&self, logger: &dyn Logger, worker_name: &str, package: &Package, target: &PoolType) {
+        use crate::logging::{LogLevel, LogContext};
+        logger.log(LogLevel::Warn, &format!(
+            "Potential dead loop detected: Worker '{}' jumped back to pool '{}'. Package ID: {}",
+            worker_name, target, package.package_id
+        ), &LogContext::new());
+    }
+    
     /// Check if package has exceeded processing iterations
     pub fn check_iterations(&self, current_iterations: usize) -> bool {
         current_iterations < self.max_iterations
@@ -41,6 +149,23 @@ impl PoolValidator {
     pub fn max_iterations(&self) -> usize {
         self.max_iterations
     }
+    
+    /// Check whether a package with `lineage` may be handed to `worker_name`
+    ///
+    /// Returns the reason to quarantine the package instead.
+    pub fn check_lineage(&self, lineage: &Lineage, worker_name: &str) -> Option<QuarantineReason> {
+        if !self.check_iterations(lineage.iterations()) {
+            return Some(QuarantineReason::IterationLimit {
+                iterations: lineage.iterations(),
+            });
+        }
+        if lineage.contains(worker_name) {
+            return Some(QuarantineReason::Cycle {
+                worker: worker_name.to_string(),
+            });
+        }
+        None
+    }
 }
 
 impl Default for PoolValidator {
@@ -73,6 +198,26 @@ mod tests {
         assert!(!validator.check_iterations(100));
     }
 
+    #[test]
+    fn test_check_lineage() {
+        let validator = PoolValidator::with_max_iterations(2);
+        let lineage = Lineage::default().extended("a");
+        assert!(validator.check_lineage(&lineage, "b").is_none());
+
+        let lineage = lineage.extended("b");
+        assert_eq!(lineage.to_string(), "a -> b");
+        assert_eq!(
+            validator.check_lineage(&lineage, "c"),
+            Some(QuarantineReason::IterationLimit { iterations: 2 })
+        );
+
+        let validator = PoolValidator::new();
+        assert_eq!(
+            validator.check_lineage(&lineage, "a"),
+            Some(QuarantineReason::Cycle { worker: "a".to_string() })
+        );
+    }
+
     #[test]
     fn test_log_dead_loop_warning() {
         use crate::logging::formatters::JsonFormatter;
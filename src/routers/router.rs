@@ -1,6 +1,5 @@
 //! Standard router implementation
 
-use crate::events::EventEnum;
 use crate::logging::traits::{LogLevel, LogContext};
 use crate::routers::traits::Router;
 use crate::routers::types::{RouteResult, RouterConfig, RouteState, RouteTarget};
@@ -163,50 +162,6 @@ impl Router for StandardRouter {
     }
 }
 
-// Extension trait to extract channel_id from EventEnum
-trait ChannelIdExt {
-    fn channel_id(&self) -> Option<String>;
-}
-
-impl ChannelIdExt for EventEnum {
-    fn channel_id(&self) -> Option<String> {
-        // Extract from metadata extra field
-        match self {
-            EventEnum::Message(evt) => {
-                if let Ok(extra) = serde_json::from_value::<serde_json::Value>(serde_json::json!(evt)) {
-                    if let Some(obj) = extra.as_object() {
-                        if let Some(channel_id) = obj.get("channel_id").and_then(|v| v.as_str()) {
-                            return Some(channel_id.to_string());
-                        }
-                    }
-                }
-                None
-            }
-            EventEnum::Notice(evt) => {
-                if let Ok(extra) = serde_json::from_value::<serde_json::Value>(serde_json::json!(evt)) {
-                    if let Some(obj) = extra.as_object() {
-                        if let Some(channel_id) = obj.get("channel_id").and_then(|v| v.as_str()) {
-                            return Some(channel_id.to_string());
-                        }
-                    }
-                }
-                None
-            }
-            EventEnum::Request(evt) => {
-                if let Ok(extra) = serde_json::from_value::<serde_json::Value>(serde_json::json!(evt)) {
-                    if let Some(obj) = extra.as_object() {
-                        if let Some(channel_id) = obj.get("channel_id").and_then(|v| v.as_str()) {
-                            return Some(channel_id.to_string());
-                        }
-                    }
-                }
-                None
-            }
-            EventEnum::Meta(_) => None,
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -292,6 +247,26 @@ mod tests {
         assert_eq!(channel_type, Some("private:user1".to_string()));
     }
 
+    #[test]
+    fn test_extract_channel_type_channel() {
+        let router = create_test_router();
+
+        let channel_event = EventEnum::Message(message::MessageEvent::Text {
+            text: "Test".to_string(),
+            metadata: traits::EventMetadata::new("message")
+                .with_user_id("user1")
+                .with_guild_id("guild1")
+                .with_channel_id("channel1"),
+        });
+
+        let group = Group::new("test_group").with_event(channel_event);
+        let block = Block::new(BlockType::Message).with_group(group);
+        let package = Package::new().with_block(block);
+
+        let channel_type = router.extract_channel_type(&package);
+        assert_eq!(channel_type, Some("channel:channel1".to_string()));
+    }
+
     #[test]
     fn test_determine_target_group() {
         let router = create_test_router();
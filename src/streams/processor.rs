@@ -2,10 +2,10 @@
 
 use crate::events::Package;
 use crate::logging::traits::{LogLevel, LogContext};
-use crate::pools::{Pool, PoolType};
+use crate::pools::{take_destination, Destination, Pool, PoolType};
 use std::sync::Arc;
 
-/// Stream processor - processes packages through 9 pools in sequence
+/// Stream processor - processes packages through the pools of a stream in sequence
 pub struct StreamProcessor {
     logger: Arc<dyn crate::logging::traits::Logger>,
 }
@@ -18,14 +18,24 @@ impl StreamProcessor {
     
     /// Process packages through pools in sequence
     /// Takes a list of (pool_type, pool) tuples
+    ///
+    /// Packages a pool sends to a later pool skip the pools in between;
+    /// packages leaving the stream early are returned as they are.
     pub async fn process_sequence(
         &self,
         pools: &[(PoolType, Arc<dyn Pool>)],
         packages: Vec<Package>,
     ) -> Vec<Package> {
         let mut current_packages = packages;
+        let mut jumped: Vec<(PoolType, Package)> = Vec::new();
+        let mut finished: Vec<Package> = Vec::new();
         
-        for (pool_type, pool) in pools {
+        for (index, (pool_type, pool)) in pools.iter().enumerate() {
+            // Pick up packages that jumped to this pool
+            let (arrived, waiting): (Vec<_>, Vec<_>) = jumped.into_iter().partition(|(target, _)| target == pool_type);
+            jumped = waiting;
+            current_packages.extend(arrived.into_iter().map(|(_, package)| package));
+            
             let message = format!(
                 "Processing {} packages through {:?} pool",
                 current_packages.len(),
@@ -35,9 +45,22 @@ impl StreamProcessor {
             self.logger.log(LogLevel::Debug, &message, &context);
             
             let next_packages = pool.process_batch(current_packages).await;
-            current_packages = next_packages;
+            
+            current_packages = Vec::new();
+            for mut package in next_packages {
+                match take_destination(&mut package) {
+                    Some(Destination::Pool(target)) if pools[index + 1..].iter().any(|(pt, _)| *pt == target) => {
+                        jumped.push((target, package));
+                    }
+                    Some(Destination::Exit) => finished.push(package),
+                    _ => current_packages.push(package),
+                }
+            }
         }
         
+        // Targets missing from the sequence end the jump here
+        current_packages.extend(jumped.into_iter().map(|(_, package)| package));
+        current_packages.extend(finished);
         current_packages
     }
 }
@@ -47,3 +70,85 @@ impl std::fmt::Debug for StreamProcessor {
         f.debug_struct("StreamProcessor").finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::TargetSite;
+    use crate::pools::StandardPool;
+    use crate::workers::{MatchingRule, Worker, WorkerRegistration, WorkerResult, WorkerType};
+    use async_trait::async_trait;
+
+    fn create_test_logger() -> Arc<dyn crate::logging::traits::Logger> {
+        let formatter = Arc::new(crate::logging::formatters::JsonFormatter::new());
+        let writer = Arc::new(crate::logging::writers::ConsoleWriter::new());
+        Arc::new(crate::logging::StructuredLogger::new(formatter, writer))
+    }
+
+    /// Records its pool in the package and jumps ahead
+    #[derive(Debug)]
+    struct TagWorker {
+        pool_type: PoolType,
+        jump_to: PoolType,
+    }
+
+    #[async_trait]
+    impl Worker for TagWorker {
+        fn name(&self) -> &str {
+            "tag_worker"
+        }
+
+        fn worker_type(&self) -> WorkerType {
+            WorkerType::Process
+        }
+
+        fn matches(&self, _target_site: &TargetSite) -> bool {
+            true
+        }
+
+        async fn handle_batch(&self, packages: Vec<Package>) -> WorkerResult {
+            let tagged = packages
+                .into_iter()
+                .map(|mut package| {
+                    let mut visited = package.extra["visited"].as_array().cloned().unwrap_or_default();
+                    visited.push(serde_json::json!(self.pool_type));
+                    package.extra["visited"] = serde_json::json!(visited);
+                    package
+                })
+                .collect();
+            WorkerResult::jump(self.jump_to.clone(), tagged)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_process_sequence_jump() {
+        let logger = create_test_logger();
+        let tagged = [
+            (PoolType::Input, PoolType::Output),
+            (PoolType::Process, PoolType::PostProcess),
+            (PoolType::Output, PoolType::PostOutput),
+        ];
+
+        let pools: Vec<(PoolType, Arc<dyn Pool>)> = PoolType::processing_order()
+            .into_iter()
+            .map(|pool_type| {
+                let mut pool = StandardPool::new(pool_type.clone(), logger.clone());
+                if let Some((_, jump_to)) = tagged.iter().find(|(tagged, _)| *tagged == pool_type) {
+                    let worker = Box::new(TagWorker { pool_type: pool_type.clone(), jump_to: jump_to.clone() });
+                    pool.register(WorkerRegistration::new(worker, MatchingRule::All, 0)).unwrap();
+                }
+                let pool: Arc<dyn Pool> = Arc::new(pool);
+                (pool_type, pool)
+            })
+            .collect();
+
+        let processor = StreamProcessor::new(logger);
+        let package = Package::new().with_target_site(TargetSite::worker("tag_worker"));
+        let result = processor.process_sequence(&pools, vec![package]).await;
+
+        // The Process pool was skipped
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].extra["visited"], serde_json::json!(["input", "output"]));
+        assert!(result[0].extra.get(crate::pools::DESTINATION_KEY).is_none());
+    }
+}
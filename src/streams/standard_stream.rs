@@ -1,23 +1,26 @@
-//! Standard stream implementation with 9 pools
+//! Standard stream implementation with 9 pools and optional custom stages
 
 use async_trait::async_trait;
 use crate::channels::ChannelType;
 use crate::events::Package;
-use crate::pools::{Pool, PoolType, StandardPool};
+use crate::pools::{Pool, PoolConfig, PoolMonitor, PoolType, StageLayout, StandardPool};
 use crate::streams::processor::StreamProcessor;
 use crate::streams::traits::Stream;
 use crate::logging::traits::Logger;
+use crate::workers::WorkerRegistry;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
-/// Standard stream - contains 9 pools processing packages in sequence
+/// Standard stream - contains 9 pools processing packages in sequence,
+/// plus the custom stages of its `StageLayout`
 #[derive(Debug)]
 pub struct StandardStream {
     stream_id: String,
     channel_id: String,
     channel_type: ChannelType,
     pools: HashMap<PoolType, Arc<dyn Pool>>,
+    layout: Arc<StageLayout>,
     processor: StreamProcessor,
 }
 
@@ -30,7 +33,7 @@ impl StandardStream {
         let mut pools: HashMap<PoolType, Arc<dyn Pool>> = HashMap::new();
         
         for pool_type in PoolType::processing_order() {
-            let pool: Arc<dyn Pool> = Arc::new(StandardPool::new(pool_type, logger.clone()));
+            let pool: Arc<dyn Pool> = Arc::new(StandardPool::new(pool_type.clone(), logger.clone()));
             pools.insert(pool_type, pool);
         }
         
@@ -41,6 +44,7 @@ impl StandardStream {
             channel_id,
             channel_type,
             pools,
+            layout: Arc::new(StageLayout::standard()),
             processor,
         }
     }
@@ -55,7 +59,7 @@ impl StandardStream {
         let mut pools: HashMap<PoolType, Arc<dyn Pool>> = HashMap::new();
         
         for pool_type in PoolType::processing_order() {
-            let pool: Arc<dyn Pool> = Arc::new(StandardPool::new(pool_type, logger.clone()));
+            let pool: Arc<dyn Pool> = Arc::new(StandardPool::new(pool_type.clone(), logger.clone()));
             pools.insert(pool_type, pool);
         }
         
@@ -66,18 +70,66 @@ impl StandardStream {
             channel_id,
             channel_type,
             pools,
+            layout: Arc::new(StageLayout::standard()),
             processor,
         }
     }
     
+    /// Create a new standard stream whose pools run the workers of `registry`
+    ///
+    /// Workers added to the registry later are picked up by this stream.
+    /// The stream has a pool for every stage of `layout`. Pools missing
+    /// from `pool_configs` use the default `PoolConfig`; all pools report
+    /// to `monitor`.
+    pub fn with_registry(
+        channel_id: String,
+        channel_type: ChannelType,
+        registry: Arc<WorkerRegistry>,
+        pool_configs: &HashMap<PoolType, PoolConfig>,
+        layout: Arc<StageLayout>,
+        monitor: Arc<PoolMonitor>,
+        logger: Arc<dyn Logger>,
+    ) -> Self {
+        let stream_id = format!("stream_{}", channel_type.id());
+        
+        let mut pools: HashMap<PoolType, Arc<dyn Pool>> = HashMap::new();
+        
+        for pool_type in layout.stages() {
+            let config = pool_configs.get(pool_type).cloned().unwrap_or_default();
+            let pool: Arc<dyn Pool> = Arc::new(
+                StandardPool::with_registry(pool_type.clone(), registry.clone(), channel_type.clone(), logger.clone())
+                    .with_config(config)
+                    .with_layout(layout.clone())
+                    .with_monitor(monitor.clone()),
+            );
+            pools.insert(pool_type.clone(), pool);
+        }
+        
+        let processor = StreamProcessor::new(logger);
+        
+        Self {
+            stream_id,
+            channel_id,
+            channel_type,
+            pools,
+            layout,
+            processor,
+        }
+    }
+    
+    /// Get the processing order of this stream's pools
+    pub fn layout(&self) -> &Arc<StageLayout> {
+        &self.layout
+    }
+    
     /// Get a specific pool by type
-    pub fn get_pool(&self, pool_type: PoolType) -> Option<&Arc<dyn Pool>> {
-        self.pools.get(&pool_type)
+    pub fn get_pool(&self, pool_type: &PoolType) -> Option<&Arc<dyn Pool>> {
+        self.pools.get(pool_type)
     }
     
     /// Get a mutable reference to a specific pool by type
-    pub fn get_pool_mut(&mut self, pool_type: PoolType) -> Option<&mut Arc<dyn Pool>> {
-        self.pools.get_mut(&pool_type)
+    pub fn get_pool_mut(&mut self, pool_type: &PoolType) -> Option<&mut Arc<dyn Pool>> {
+        self.pools.get_mut(pool_type)
     }
 }
 
@@ -93,9 +145,11 @@ impl Stream for StandardStream {
     
     async fn process(&self, packages: Vec<Package>) -> crate::errors::Result<Vec<Package>> {
         // Build pool list in processing order
-        let pool_list: Vec<(PoolType, Arc<dyn Pool>)> = PoolType::processing_order()
-            .into_iter()
-            .filter_map(|pt| self.pools.get(&pt).map(|p| (pt, p.clone())))
+        let pool_list: Vec<(PoolType, Arc<dyn Pool>)> = self
+            .layout
+            .stages()
+            .iter()
+            .filter_map(|pt| self.pools.get(pt).map(|p| (pt.clone(), p.clone())))
             .collect();
         
         // Process through all pools in sequence
@@ -420,6 +420,40 @@ pub async fn get_config(State(state): State<AppState>) -> Json<ApiResponse<Confi
     Json(ApiResponse::success(response))
 }
 
+/// List the circuit breakers of registered workers
+pub async fn list_worker_breakers(
+    State(state): State<AppState>,
+    Query(params): Query<ListParams>,
+) -> Json<ApiResponse<Vec<WorkerBreakerInfo>>> {
+    if let Some(engine) = &state.engine {
+        let breakers: Vec<WorkerBreakerInfo> = engine
+            .worker_registry()
+            .registrations()
+            .into_iter()
+            .filter_map(|(pool_type, scope, registration)| {
+                let breaker = registration.breaker.as_ref()?;
+                Some(WorkerBreakerInfo {
+                    pool: pool_type.to_string(),
+                    scope: scope.to_string(),
+                    worker: registration.name().to_string(),
+                    breaker: breaker.snapshot(),
+                })
+            })
+            .filter(|b| {
+                if let Some(name_filter) = &params.name {
+                    b.worker.contains(name_filter)
+                } else {
+                    true
+                }
+            })
+            .collect();
+
+        Json(ApiResponse::success(breakers))
+    } else {
+        Json(ApiResponse::error("Engine is not available".to_string()))
+    }
+}
+
 /// Query parameters for list endpoints
 #[derive(Debug, Deserialize)]
 pub struct ListParams {
@@ -84,6 +84,7 @@ impl WebService {
             .route("/api/adapters/reload", post(handlers::reload_adapters))
             .route("/api/reload", post(handlers::reload_all))
             .route("/api/config", get(handlers::get_config))
+            .route("/api/workers/breakers", get(handlers::list_worker_breakers))
             .layer(cors)
             .with_state(app_state)
     }
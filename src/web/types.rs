@@ -167,6 +167,19 @@ pub struct AdapterInfo {
     pub description: Option<String>,
 }
 
+/// Circuit breaker of a registered worker for API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerBreakerInfo {
+    /// Pool the worker is registered in
+    pub pool: String,
+    /// Worker scope (`global` or a channel)
+    pub scope: String,
+    /// Worker name
+    pub worker: String,
+    /// Breaker state
+    pub breaker: crate::workers::BreakerSnapshot,
+}
+
 /// Reload request body
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReloadRequest {
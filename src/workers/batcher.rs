@@ -0,0 +1,295 @@
+//! Windowed batching of `handle_batch` calls
+//!
+//! Packages usually reach a worker one by one. A worker registered with a
+//! `BatchPolicy` instead has the packages of concurrent steps (from any
+//! channel) collected into one `handle_batch` call: the first step to arrive
+//! opens a window and waits until it holds `max_size` packages or
+//! `max_wait_ms` has passed, then calls the worker for everyone and hands
+//! each step its share of the result.
+
+use crate::events::Package;
+use crate::workers::WorkerResult;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{oneshot, Notify};
+
+/// When a batch window is flushed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatchPolicy {
+    /// Packages that flush the window right away
+    pub max_size: usize,
+
+    /// Time the window stays open after its first package
+    pub max_wait_ms: u64,
+}
+
+impl Default for BatchPolicy {
+    fn default() -> Self {
+        Self {
+            max_size: 16,
+            max_wait_ms: 100,
+        }
+    }
+}
+
+impl BatchPolicy {
+    /// Collect up to `max_size` packages for at most `max_wait`
+    pub fn new(max_size: usize, max_wait: Duration) -> Self {
+        Self {
+            max_size,
+            max_wait_ms: max_wait.as_millis() as u64,
+        }
+    }
+}
+
+/// Packages handed in by one step
+struct Submission<R> {
+    len: usize,
+    /// `None` for the step that opened the window
+    reply: Option<oneshot::Sender<R>>,
+}
+
+/// The window being collected
+struct Pending<R> {
+    packages: Vec<Package>,
+    submissions: Vec<Submission<R>>,
+    full: Arc<Notify>,
+}
+
+/// Result of joining a batch window
+pub enum Joined<R> {
+    /// The window was opened by this step, which now calls the worker
+    Leader(Batch<R>),
+
+    /// Another step calls the worker and sends this step its share
+    Member(oneshot::Receiver<R>),
+}
+
+/// A flushed window, held by the step that calls the worker
+pub struct Batch<R> {
+    packages: Vec<Package>,
+    submissions: Vec<Submission<R>>,
+}
+
+impl<R> Batch<R> {
+    /// Get the packages of all steps, the leader's first
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    /// Get the number of steps in the batch
+    pub fn steps(&self) -> usize {
+        self.submissions.len()
+    }
+
+    /// Split a worker result into one result per step
+    ///
+    /// Outputs of `Modify` and `Jump` go to the step that handed in the
+    /// package with the same `package_id`; new packages (e.g. a summary of
+    /// the whole batch) go to the leader. A `Reply` is sent once, by the
+    /// leader; the members get an empty `Reply`, so their packages leave the
+    /// stream as well. Other results apply to every step.
+    pub fn split(&self, result: &WorkerResult) -> Vec<WorkerResult> {
+        let distribute = |outputs: &[Package]| {
+            let mut shares = vec![Vec::new(); self.submissions.len()];
+            for output in outputs {
+                shares[self.owner_of(&output.package_id).unwrap_or(0)].push(output.clone());
+            }
+            shares
+        };
+        match result {
+            WorkerResult::Modify(outputs) => distribute(outputs).into_iter().map(WorkerResult::Modify).collect(),
+            WorkerResult::Jump(target, outputs) => distribute(outputs)
+                .into_iter()
+                .map(|share| WorkerResult::Jump(target.clone(), share))
+                .collect(),
+            WorkerResult::Reply(_) => std::iter::once(result.clone())
+                .chain(std::iter::repeat_n(WorkerResult::Reply(Vec::new()), self.submissions.len() - 1))
+                .collect(),
+            result => vec![result.clone(); self.submissions.len()],
+        }
+    }
+
+    /// Send the members their results and return the leader's
+    ///
+    /// `results` holds one result per step, in the order of `split`.
+    pub fn reply(self, results: Vec<R>) -> R {
+        let mut leader = None;
+        for (submission, result) in self.submissions.into_iter().zip(results) {
+            match submission.reply {
+                Some(reply) => {
+                    let _ = reply.send(result);
+                }
+                None => leader = Some(result),
+            }
+        }
+        leader.expect("a batch has a result for its leader")
+    }
+
+    fn owner_of(&self, package_id: &str) -> Option<usize> {
+        let index = self.packages.iter().position(|package| package.package_id == package_id)?;
+        let mut end = 0;
+        self.submissions.iter().position(|submission| {
+            end += submission.len;
+            index < end
+        })
+    }
+}
+
+/// Collects the packages of one worker registration into batch windows
+///
+/// Shared by all clones of the registration, so packages of every channel
+/// end up in the same window.
+pub struct WorkerBatcher<R> {
+    policy: BatchPolicy,
+    pending: Mutex<Option<Pending<R>>>,
+}
+
+impl<R> WorkerBatcher<R> {
+    /// Create a batcher
+    pub fn new(policy: BatchPolicy) -> Self {
+        Self {
+            policy,
+            pending: Mutex::new(None),
+        }
+    }
+
+    /// Get the batch policy
+    pub fn policy(&self) -> &BatchPolicy {
+        &self.policy
+    }
+
+    /// Hand packages to the worker's current window
+    ///
+    /// Opens a window if there is none; the opener waits for the window to
+    /// fill or time out and becomes the leader of the batch.
+    pub async fn join(&self, packages: Vec<Package>) -> Joined<R> {
+        let max_size = self.policy.max_size.max(1);
+        let (full, filled) = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(window) = pending.as_mut() {
+                let (sender, receiver) = oneshot::channel();
+                window.submissions.push(Submission { len: packages.len(), reply: Some(sender) });
+                window.packages.extend(packages);
+                if window.packages.len() >= max_size {
+                    window.full.notify_one();
+                }
+                return Joined::Member(receiver);
+            }
+
+            let full = Arc::new(Notify::new());
+            let filled = packages.len() >= max_size;
+            *pending = Some(Pending {
+                submissions: vec![Submission { len: packages.len(), reply: None }],
+                packages,
+                full: full.clone(),
+            });
+            (full, filled)
+        };
+
+        // A cancelled leader closes its window so members are not left waiting
+        let mut guard = WindowGuard { pending: Some(&self.pending) };
+        if !filled {
+            let _ = tokio::time::timeout(Duration::from_millis(self.policy.max_wait_ms), full.notified()).await;
+        }
+
+        let window = guard.take().expect("the leader owns the open window");
+        Joined::Leader(Batch {
+            packages: window.packages,
+            submissions: window.submissions,
+        })
+    }
+}
+
+impl<R> std::fmt::Debug for WorkerBatcher<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerBatcher").field("policy", &self.policy).finish()
+    }
+}
+
+/// Drops the open window if its leader is cancelled while waiting
+struct WindowGuard<'a, R> {
+    pending: Option<&'a Mutex<Option<Pending<R>>>>,
+}
+
+impl<R> WindowGuard<'_, R> {
+    /// Close the window and take it out of the batcher
+    fn take(&mut self) -> Option<Pending<R>> {
+        self.pending.take()?.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+impl<R> Drop for WindowGuard<'_, R> {
+    fn drop(&mut self) {
+        self.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{Message, Target};
+    use crate::engine::dispatch::OutboundMessage;
+
+    fn package(id: &str) -> Package {
+        let mut package = Package::new();
+        package.package_id = id.to_string();
+        package
+    }
+
+    #[tokio::test]
+    async fn test_batcher_flushes_when_full() {
+        let batcher: Arc<WorkerBatcher<WorkerResult>> =
+            Arc::new(WorkerBatcher::new(BatchPolicy::new(3, Duration::from_secs(60))));
+
+        let leader = tokio::spawn({
+            let batcher = batcher.clone();
+            async move {
+                let Joined::Leader(batch) = batcher.join(vec![package("a")]).await else {
+                    panic!("first step leads");
+                };
+                let ids: Vec<String> = batch.packages().iter().map(|p| p.package_id.clone()).collect();
+                assert_eq!(ids, vec!["a", "b", "c"]);
+
+                // Only the leader sends a reply, the members' packages exit silently
+                let reply = OutboundMessage::new(Target::User { user_id: "u1".to_string() }, Message::Text { content: "hi".to_string() });
+                let split = batch.split(&WorkerResult::Reply(vec![reply]));
+                assert!(matches!(split.as_slice(), [WorkerResult::Reply(own), WorkerResult::Reply(share)] if own.len() == 1 && share.is_empty()));
+
+                let result = WorkerResult::Modify(vec![package("c"), package("summary")]);
+                let results = batch.split(&result);
+                batch.reply(results)
+            }
+        });
+        tokio::task::yield_now().await;
+        while batcher.pending.lock().unwrap().is_none() {
+            tokio::task::yield_now().await;
+        }
+
+        let Joined::Member(member) = batcher.join(vec![package("b"), package("c")]).await else {
+            panic!("second step joins");
+        };
+        let WorkerResult::Modify(own) = leader.await.unwrap() else { panic!() };
+        let WorkerResult::Modify(share) = member.await.unwrap() else { panic!() };
+        assert_eq!(own[0].package_id, "summary");
+        assert_eq!(share[0].package_id, "c");
+    }
+
+    #[tokio::test]
+    async fn test_batcher_flushes_after_wait() {
+        let batcher: WorkerBatcher<WorkerResult> = WorkerBatcher::new(BatchPolicy::new(10, Duration::from_millis(20)));
+
+        let started = std::time::Instant::now();
+        let Joined::Leader(batch) = batcher.join(vec![package("a")]).await else {
+            panic!("first step leads");
+        };
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert_eq!(batch.steps(), 1);
+        assert!(matches!(batch.split(&WorkerResult::Release).as_slice(), [WorkerResult::Release]));
+
+        // The next step opens a new window
+        assert!(matches!(batcher.join(vec![package("b")]).await, Joined::Leader(_)));
+    }
+}
@@ -0,0 +1,332 @@
+//! Per-worker circuit breaker
+//!
+//! A worker that keeps timing out or panicking (usually because an external
+//! service it calls is down) is bypassed by its pools for a cooldown instead
+//! of being handed every package. Once the cooldown has passed the breaker
+//! lets a single trial call through (half-open): its outcome closes the
+//! breaker or opens it for another cooldown.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Circuit breaker settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Failure rate (0.0 - 1.0) of the recent calls at which the breaker opens
+    pub failure_threshold: f64,
+
+    /// Number of recent calls the failure rate is computed over
+    pub window: usize,
+
+    /// Calls needed in the window before the breaker can open
+    pub min_calls: usize,
+
+    /// Time the breaker stays open before calls are tried again
+    pub cooldown_ms: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 0.5,
+            window: 20,
+            min_calls: 5,
+            cooldown_ms: 30_000,
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Create the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the failure rate at which the breaker opens
+    pub fn with_failure_threshold(mut self, failure_threshold: f64) -> Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// Set the number of recent calls the failure rate is computed over
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Set the calls needed before the breaker can open
+    pub fn with_min_calls(mut self, min_calls: usize) -> Self {
+        self.min_calls = min_calls;
+        self
+    }
+
+    /// Set the cooldown in milliseconds
+    pub fn with_cooldown_ms(mut self, cooldown_ms: u64) -> Self {
+        self.cooldown_ms = cooldown_ms;
+        self
+    }
+}
+
+/// Circuit breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Calls go through
+    Closed,
+
+    /// Calls are bypassed until the cooldown has passed
+    Open,
+
+    /// One call goes through on trial; its outcome decides the state
+    HalfOpen,
+}
+
+impl std::fmt::Display for BreakerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed => write!(f, "closed"),
+            Self::Open => write!(f, "open"),
+            Self::HalfOpen => write!(f, "half_open"),
+        }
+    }
+}
+
+/// Point-in-time view of a circuit breaker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakerSnapshot {
+    /// Current state
+    pub state: BreakerState,
+    /// Calls in the window
+    pub calls: usize,
+    /// Failed calls in the window
+    pub failures: usize,
+    /// Failure rate of the window
+    pub failure_rate: f64,
+    /// Times the breaker opened
+    pub trips: u64,
+    /// When the breaker last opened
+    pub opened_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: BreakerState,
+    /// Recent outcomes, `true` for a failure
+    outcomes: VecDeque<bool>,
+    opened: Option<(Instant, DateTime<Utc>)>,
+    trips: u64,
+    /// When the pending half-open trial call was let through
+    trial: Option<Instant>,
+}
+
+impl BreakerInner {
+    fn failures(&self) -> usize {
+        self.outcomes.iter().filter(|&&failed| failed).count()
+    }
+
+    fn failure_rate(&self) -> f64 {
+        match self.outcomes.len() {
+            0 => 0.0,
+            calls => self.failures() as f64 / calls as f64,
+        }
+    }
+
+    fn open(&mut self) {
+        self.state = BreakerState::Open;
+        self.opened = Some((Instant::now(), Utc::now()));
+        self.trips += 1;
+        self.trial = None;
+    }
+}
+
+/// Circuit breaker of one worker registration
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerInner>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed breaker
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                outcomes: VecDeque::new(),
+                opened: None,
+                trips: 0,
+                trial: None,
+            }),
+        }
+    }
+
+    /// Get the settings
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Get the current state
+    pub fn state(&self) -> BreakerState {
+        self.inner.lock().map(|inner| inner.state).unwrap_or(BreakerState::Closed)
+    }
+
+    /// Check whether a call may go through
+    ///
+    /// An open breaker whose cooldown has passed becomes half-open and lets
+    /// one trial call through; further calls are bypassed until its outcome
+    /// is recorded. A trial that is never recorded (e.g. the call was
+    /// dropped) is replaced by another after a cooldown.
+    pub fn allows_call(&self) -> bool {
+        let Ok(mut inner) = self.inner.lock() else {
+            return true;
+        };
+        let cooldown = Duration::from_millis(self.config.cooldown_ms);
+        match inner.state {
+            BreakerState::Closed => true,
+            BreakerState::HalfOpen => {
+                let trial_open = inner.trial.is_none_or(|at| at.elapsed() >= cooldown);
+                if trial_open {
+                    inner.trial = Some(Instant::now());
+                }
+                trial_open
+            }
+            BreakerState::Open => {
+                let cooled_down = inner.opened.is_none_or(|(at, _)| at.elapsed() >= cooldown);
+                if cooled_down {
+                    inner.state = BreakerState::HalfOpen;
+                    inner.trial = Some(Instant::now());
+                }
+                cooled_down
+            }
+        }
+    }
+
+    /// Record a successful call
+    ///
+    /// Returns the new state if the breaker closed.
+    pub fn record_success(&self) -> Option<BreakerState> {
+        let mut inner = self.inner.lock().ok()?;
+        match inner.state {
+            BreakerState::HalfOpen => {
+                inner.state = BreakerState::Closed;
+                inner.outcomes.clear();
+                inner.trial = None;
+                Some(BreakerState::Closed)
+            }
+            _ => {
+                self.push_outcome(&mut inner, false);
+                None
+            }
+        }
+    }
+
+    /// Record a failed call
+    ///
+    /// Returns the new state if the breaker opened.
+    pub fn record_failure(&self) -> Option<BreakerState> {
+        let mut inner = self.inner.lock().ok()?;
+        match inner.state {
+            BreakerState::HalfOpen => {
+                inner.open();
+                Some(BreakerState::Open)
+            }
+            BreakerState::Open => None,
+            BreakerState::Closed => {
+                self.push_outcome(&mut inner, true);
+                if inner.outcomes.len() >= self.config.min_calls.max(1)
+                    && inner.failure_rate() >= self.config.failure_threshold
+                {
+                    inner.open();
+                    Some(BreakerState::Open)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Get a point-in-time view of the breaker
+    pub fn snapshot(&self) -> BreakerSnapshot {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        BreakerSnapshot {
+            state: inner.state,
+            calls: inner.outcomes.len(),
+            failures: inner.failures(),
+            failure_rate: inner.failure_rate(),
+            trips: inner.trips,
+            opened_at: inner.opened.map(|(_, at)| at),
+        }
+    }
+
+    fn push_outcome(&self, inner: &mut BreakerInner, failed: bool) {
+        inner.outcomes.push_back(failed);
+        while inner.outcomes.len() > self.config.window.max(1) {
+            inner.outcomes.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_on_failure_rate() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig::new().with_min_calls(4).with_failure_threshold(0.5));
+
+        assert_eq!(breaker.record_success(), None);
+        assert_eq!(breaker.record_failure(), None);
+        assert_eq!(breaker.record_success(), None);
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert_eq!(breaker.snapshot().failure_rate, 1.0 / 3.0);
+
+        assert_eq!(breaker.record_failure(), Some(BreakerState::Open));
+        assert!(!breaker.allows_call());
+        let snapshot = breaker.snapshot();
+        assert_eq!(snapshot.trips, 1);
+        assert!(snapshot.opened_at.is_some());
+    }
+
+    #[test]
+    fn test_breaker_half_open_after_cooldown() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig::new().with_min_calls(1).with_cooldown_ms(0));
+
+        assert_eq!(breaker.record_failure(), Some(BreakerState::Open));
+        assert!(breaker.allows_call());
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        // A failed trial call opens the breaker again
+        assert_eq!(breaker.record_failure(), Some(BreakerState::Open));
+        assert_eq!(breaker.snapshot().trips, 2);
+
+        assert!(breaker.allows_call());
+        assert_eq!(breaker.record_success(), Some(BreakerState::Closed));
+        assert_eq!(breaker.snapshot().calls, 0);
+    }
+
+    #[test]
+    fn test_breaker_half_open_admits_one_trial() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig::new().with_min_calls(1).with_cooldown_ms(50));
+
+        assert_eq!(breaker.record_failure(), Some(BreakerState::Open));
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.allows_call());
+        assert!(!breaker.allows_call());
+        assert!(!breaker.allows_call());
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        // A trial that never reports back is replaced after a cooldown
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.allows_call());
+        assert!(!breaker.allows_call());
+
+        assert_eq!(breaker.record_success(), Some(BreakerState::Closed));
+        assert!(breaker.allows_call());
+        assert!(breaker.allows_call());
+    }
+}
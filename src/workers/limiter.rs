@@ -0,0 +1,256 @@
+//! Per-worker concurrency and rate limits
+//!
+//! Workers that call LLMs or paid APIs declare how many `handle_batch` calls
+//! may run at once and how many may start per time window. A `WorkerLimiter`
+//! enforces both with a semaphore and a token bucket; its `OverflowAction`
+//! tells the pool what to do with packages that arrive while the worker is
+//! at its limit.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// What a pool does with packages while their worker is at its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowAction {
+    /// Wait until the worker can be called
+    #[default]
+    Queue,
+
+    /// Hand the packages to the next matching worker
+    Skip,
+
+    /// Drop the packages and announce it with a system event
+    Drop,
+}
+
+/// Token-bucket rate limit: `calls` calls per `per_ms` milliseconds
+///
+/// The bucket holds at most `calls` tokens, so bursts up to that size are
+/// allowed after a quiet period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Calls allowed per window
+    pub calls: u32,
+
+    /// Window length in milliseconds
+    pub per_ms: u64,
+}
+
+impl RateLimit {
+    /// Allow `calls` calls per `per`
+    pub fn new(calls: u32, per: Duration) -> Self {
+        Self {
+            calls,
+            per_ms: per.as_millis() as u64,
+        }
+    }
+
+    /// Allow `calls` calls per second
+    pub fn per_second(calls: u32) -> Self {
+        Self::new(calls, Duration::from_secs(1))
+    }
+
+    /// Allow `calls` calls per minute
+    pub fn per_minute(calls: u32) -> Self {
+        Self::new(calls, Duration::from_secs(60))
+    }
+}
+
+/// Declarative limits of a worker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkerLimits {
+    /// Maximum concurrent `handle_batch` calls (`None`: unlimited)
+    pub max_concurrency: Option<usize>,
+
+    /// Maximum call rate (`None`: unlimited)
+    pub rate_limit: Option<RateLimit>,
+
+    /// What to do with packages while the worker is at its limit
+    pub overflow: OverflowAction,
+}
+
+impl WorkerLimits {
+    /// Create limits that allow everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum concurrent calls
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = Some(max_concurrency);
+        self
+    }
+
+    /// Set the call rate limit
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Set the overflow behavior
+    pub fn with_overflow(mut self, overflow: OverflowAction) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    /// Tokens added per second
+    refill_rate: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate_limit: RateLimit) -> Self {
+        let capacity = rate_limit.calls.max(1) as f64;
+        Self {
+            capacity,
+            refill_rate: capacity / Duration::from_millis(rate_limit.per_ms.max(1)).as_secs_f64(),
+            tokens: capacity,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        self.refilled_at = now;
+    }
+
+    /// Take a token, or return how long until one is available
+    fn take(&mut self) -> Result<(), Duration> {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.refill_rate))
+        }
+    }
+}
+
+/// Permission to make one `handle_batch` call
+///
+/// The concurrency slot is released when the permit is dropped.
+#[derive(Debug)]
+pub struct LimitPermit {
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+/// Enforces the limits of one worker registration
+#[derive(Debug)]
+pub struct WorkerLimiter {
+    limits: WorkerLimits,
+    semaphore: Option<Arc<Semaphore>>,
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+impl WorkerLimiter {
+    /// Create a limiter
+    pub fn new(limits: WorkerLimits) -> Self {
+        Self {
+            semaphore: limits.max_concurrency.map(|max| Arc::new(Semaphore::new(max.max(1)))),
+            bucket: limits.rate_limit.map(|rate_limit| Mutex::new(TokenBucket::new(rate_limit))),
+            limits,
+        }
+    }
+
+    /// Get the limits
+    pub fn limits(&self) -> &WorkerLimits {
+        &self.limits
+    }
+
+    /// Get a permit if the worker can be called right now
+    pub fn try_acquire(&self) -> Option<LimitPermit> {
+        let slot = match &self.semaphore {
+            Some(semaphore) => Some(semaphore.clone().try_acquire_owned().ok()?),
+            None => None,
+        };
+        // The slot is given back if no token is left
+        self.take_token().ok()?;
+        Some(LimitPermit { _slot: slot })
+    }
+
+    /// Wait for a permit
+    pub async fn acquire(&self) -> LimitPermit {
+        let slot = match &self.semaphore {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        while let Err(wait) = self.take_token() {
+            tokio::time::sleep(wait).await;
+        }
+        LimitPermit { _slot: slot }
+    }
+
+    /// Get the number of calls that could start right now without waiting
+    /// for a concurrency slot (`None`: unlimited)
+    pub fn available_slots(&self) -> Option<usize> {
+        self.semaphore.as_ref().map(|semaphore| semaphore.available_permits())
+    }
+
+    fn take_token(&self) -> Result<(), Duration> {
+        match &self.bucket {
+            Some(bucket) => bucket.lock().map_err(|_| Duration::ZERO)?.take(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limiter_concurrency() {
+        let limiter = WorkerLimiter::new(WorkerLimits::new().with_max_concurrency(2));
+
+        let first = limiter.try_acquire().unwrap();
+        let _second = limiter.try_acquire().unwrap();
+        assert!(limiter.try_acquire().is_none());
+        assert_eq!(limiter.available_slots(), Some(0));
+
+        drop(first);
+        assert!(limiter.try_acquire().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_limiter_rate_limit() {
+        let limits = WorkerLimits::new().with_rate_limit(RateLimit::new(2, Duration::from_millis(100)));
+        let limiter = WorkerLimiter::new(limits);
+
+        assert!(limiter.try_acquire().is_some());
+        assert!(limiter.try_acquire().is_some());
+        assert!(limiter.try_acquire().is_none());
+
+        // One token comes back after half the window
+        let started = Instant::now();
+        let _permit = limiter.acquire().await;
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_limits_from_config() {
+        let limits: WorkerLimits = serde_json::from_value(serde_json::json!({
+            "max_concurrency": 1,
+            "rate_limit": {"calls": 30, "per_ms": 60000},
+            "overflow": "skip"
+        }))
+        .unwrap();
+        assert_eq!(
+            limits,
+            WorkerLimits::new()
+                .with_max_concurrency(1)
+                .with_rate_limit(RateLimit::per_minute(30))
+                .with_overflow(OverflowAction::Skip)
+        );
+    }
+}
@@ -2,11 +2,25 @@
 //!
 //! Workers are processing units registered to pools by plugins.
 //! They handle Packages asynchronously and can split/merge packages.
+//! The `WorkerRegistry` holds the workers shared by every channel's stream.
+//! A `CircuitBreaker` on a registration bypasses a worker that keeps failing,
+//! a `WorkerLimiter` caps how often and how many times at once it is called,
+//! and a `WorkerBatcher` collects its packages into windowed batches.
 
 pub mod traits;
 pub mod result;
 pub mod registration;
+pub mod breaker;
+pub mod limiter;
+pub mod batcher;
+pub mod worker_registry;
+#[cfg(test)]
+pub(crate) mod testing;
 
 pub use traits::*;
 pub use result::*;
 pub use registration::*;
+pub use breaker::*;
+pub use limiter::*;
+pub use batcher::*;
+pub use worker_registry::*;
@@ -1,11 +1,26 @@
 //! Worker registration and matching rules
 
-use crate::events::TargetSite;
-use crate::workers::Worker;
+use crate::events::{EventCategory, EventEnum, Package, SiteType, TargetSite};
+use crate::workers::result::CallReply;
+use crate::workers::{BatchPolicy, CircuitBreaker, CircuitBreakerConfig, Worker, WorkerBatcher, WorkerLimiter, WorkerLimits};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
 
-/// TargetSite matching rule
+/// Matching rule deciding which packages a worker handles
+///
+/// Site rules look at a single `TargetSite`; content rules look at the
+/// events of the package and hold if any event satisfies them. Rules can be
+/// combined and loaded from configuration:
+///
+/// ```toml
+/// [rule]
+/// and = [{ group = "123456" }, { any_of = [{ command = "/ping" }, "mentions_self"] }]
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MatchingRule {
     /// Match all target sites
     All,
@@ -26,9 +41,42 @@ pub enum MatchingRule {
     Channel(String),
     
     /// Regex pattern matching on site_id
-    Regex(Regex),
+    Regex(#[serde(with = "regex_serde")] Regex),
     
-    /// Custom matching logic
+    /// Both rules match
+    And(Box<MatchingRule>, Box<MatchingRule>),
+    
+    /// Either rule matches
+    Or(Box<MatchingRule>, Box<MatchingRule>),
+    
+    /// The rule does not match
+    Not(Box<MatchingRule>),
+    
+    /// Any of the rules matches (false if empty)
+    AnyOf(Vec<MatchingRule>),
+    
+    /// An event of the given category
+    Category(EventCategory),
+    
+    /// An event whose type equals or is nested under the given type
+    /// (`message` matches `message.text`)
+    EventType(String),
+    
+    /// A message whose plain text matches the regex
+    Text(#[serde(with = "regex_serde")] Regex),
+    
+    /// A message whose plain text starts with the command prefix
+    /// followed by whitespace or the end of the text
+    Command(String),
+    
+    /// An event received by the given bot account
+    SelfId(String),
+    
+    /// A message mentioning (@) the bot account that received it
+    MentionsSelf,
+    
+    /// Custom matching logic (cannot be loaded from configuration)
+    #[serde(skip)]
     Custom(Box<dyn Fn(&TargetSite) -> bool + Send + Sync>),
 }
 
@@ -42,6 +90,16 @@ impl std::fmt::Debug for MatchingRule {
             Self::User(name) => write!(f, "User({})", name),
             Self::Channel(name) => write!(f, "Channel({})", name),
             Self::Regex(regex) => write!(f, "Regex({:?})", regex.as_str()),
+            Self::And(left, right) => write!(f, "And({:?}, {:?})", left, right),
+            Self::Or(left, right) => write!(f, "Or({:?}, {:?})", left, right),
+            Self::Not(rule) => write!(f, "Not({:?})", rule),
+            Self::AnyOf(rules) => write!(f, "AnyOf({:?})", rules),
+            Self::Category(category) => write!(f, "Category({:?})", category),
+            Self::EventType(event_type) => write!(f, "EventType({})", event_type),
+            Self::Text(regex) => write!(f, "Text({:?})", regex.as_str()),
+            Self::Command(prefix) => write!(f, "Command({})", prefix),
+            Self::SelfId(self_id) => write!(f, "SelfId({})", self_id),
+            Self::MentionsSelf => write!(f, "MentionsSelf"),
             Self::Custom(_) => write!(f, "Custom(<closure>)"),
         }
     }
@@ -49,46 +107,66 @@ impl std::fmt::Debug for MatchingRule {
 
 impl MatchingRule {
     /// Check if target site matches this rule
+    ///
+    /// Content rules need the package, so their outcome is unknown here; a
+    /// rule only matches if it holds whatever they would say (so neither
+    /// `command("/x")` nor `!command("/x")` matches).
     pub fn matches(&self, target_site: &TargetSite) -> bool {
+        self.evaluate(target_site, None) == Some(true)
+    }
+    
+    /// Check if a target site of a package matches this rule
+    pub fn matches_in(&self, package: &Package, target_site: &TargetSite) -> bool {
+        self.evaluate(target_site, Some(package)) == Some(true)
+    }
+    
+    /// Evaluate the rule, `None` meaning unknown (content rule without package)
+    fn evaluate(&self, target_site: &TargetSite, package: Option<&Package>) -> Option<bool> {
         match self {
-            Self::All => true,
-            Self::Worker(name) => {
-                if let crate::events::SiteType::Worker(ref w) = target_site.site_type {
-                    w == name
-                } else {
-                    false
-                }
-            }
-            Self::Bot(name) => {
-                if let crate::events::SiteType::Bot(ref b) = target_site.site_type {
-                    b == name
-                } else {
-                    false
-                }
-            }
-            Self::Group(name) => {
-                if let crate::events::SiteType::Group(ref g) = target_site.site_type {
-                    g == name
-                } else {
-                    false
-                }
-            }
-            Self::User(name) => {
-                if let crate::events::SiteType::User(ref u) = target_site.site_type {
-                    u == name
-                } else {
-                    false
-                }
-            }
-            Self::Channel(name) => {
-                if let crate::events::SiteType::Channel(ref c) = target_site.site_type {
-                    c == name
-                } else {
-                    false
-                }
-            }
-            Self::Regex(regex) => regex.is_match(&target_site.site_id),
-            Self::Custom(f) => f(target_site),
+            Self::All => Some(true),
+            Self::Worker(name) => Some(matches!(&target_site.site_type, SiteType::Worker(w) if w == name)),
+            Self::Bot(name) => Some(matches!(&target_site.site_type, SiteType::Bot(b) if b == name)),
+            Self::Group(name) => Some(matches!(&target_site.site_type, SiteType::Group(g) if g == name)),
+            Self::User(name) => Some(matches!(&target_site.site_type, SiteType::User(u) if u == name)),
+            Self::Channel(name) => Some(matches!(&target_site.site_type, SiteType::Channel(c) if c == name)),
+            Self::Regex(regex) => Some(regex.is_match(&target_site.site_id)),
+            Self::And(left, right) => match (left.evaluate(target_site, package), right.evaluate(target_site, package)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Self::Or(left, right) => any_of([left.as_ref(), right.as_ref()], target_site, package),
+            Self::Not(rule) => rule.evaluate(target_site, package).map(|matched| !matched),
+            Self::AnyOf(rules) => any_of(rules, target_site, package),
+            Self::Custom(f) => Some(f(target_site)),
+            _ => package.map(|package| package.events().any(|event| self.matches_event(event))),
+        }
+    }
+    
+    /// Evaluate a content rule against one event
+    fn matches_event(&self, event: &EventEnum) -> bool {
+        match self {
+            Self::Category(category) => event.category() == *category,
+            Self::EventType(event_type) => event
+                .event_type()
+                .strip_prefix(event_type.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.')),
+            Self::Text(regex) => event
+                .as_message()
+                .is_some_and(|message| regex.is_match(&message.plain_text())),
+            Self::Command(prefix) => event.as_message().is_some_and(|message| {
+                message
+                    .plain_text()
+                    .trim_start()
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+            }),
+            Self::SelfId(self_id) => event.self_id() == Some(self_id.as_str()),
+            Self::MentionsSelf => match (event.as_message(), event.self_id()) {
+                (Some(message), Some(self_id)) => message.mentions().contains(&self_id),
+                _ => false,
+            },
+            _ => false,
         }
     }
     
@@ -96,30 +174,160 @@ impl MatchingRule {
     pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
         Ok(Self::Regex(Regex::new(pattern)?))
     }
+    
+    /// Create a message text regex rule
+    pub fn text(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self::Text(Regex::new(pattern)?))
+    }
+    
+    /// Create a command prefix rule
+    pub fn command(prefix: impl Into<String>) -> Self {
+        Self::Command(prefix.into())
+    }
+    
+    /// Match if both this rule and `other` match
+    pub fn and(self, other: MatchingRule) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+    
+    /// Match if this rule or `other` matches
+    pub fn or(self, other: MatchingRule) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+}
+
+/// Evaluate a disjunction: true if any rule is, unknown if any rule is unknown
+fn any_of<'a>(
+    rules: impl IntoIterator<Item = &'a MatchingRule>,
+    target_site: &TargetSite,
+    package: Option<&Package>,
+) -> Option<bool> {
+    let mut result = Some(false);
+    for rule in rules {
+        match rule.evaluate(target_site, package) {
+            Some(true) => return Some(true),
+            Some(false) => {}
+            None => result = None,
+        }
+    }
+    result
+}
+
+impl std::ops::Not for MatchingRule {
+    type Output = MatchingRule;
+
+    /// Invert this rule
+    fn not(self) -> Self::Output {
+        Self::Not(Box::new(self))
+    }
+}
+
+/// (De)serialize a regex as its pattern
+mod regex_serde {
+    use regex::Regex;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(regex: &Regex, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(regex.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Regex::new(&pattern).map_err(serde::de::Error::custom)
+    }
 }
 
 /// Worker registration with priority and matching rule
+///
+/// Cloning is cheap: the worker and its matching rule are shared, so the
+/// same registration can be handed to every stream that runs it.
+#[derive(Clone)]
 pub struct WorkerRegistration {
     /// Worker instance
-    pub worker: Box<dyn Worker>,
+    pub worker: Arc<dyn Worker>,
     
     /// TargetSite matching rule
-    pub matching_rule: MatchingRule,
+    pub matching_rule: Arc<MatchingRule>,
     
     /// Worker priority (assigned at load time, starting from 0)
     pub priority: u32,
+    
+    /// Maximum time a `handle_batch` call may take (overrides the pool default)
+    pub timeout: Option<Duration>,
+    
+    /// Circuit breaker bypassing the worker while it keeps failing
+    pub breaker: Option<Arc<CircuitBreaker>>,
+    
+    /// Concurrency and rate limits of the worker
+    pub limiter: Option<Arc<WorkerLimiter>>,
+    
+    /// Batch windows collecting the worker's packages
+    pub(crate) batcher: Option<Arc<WorkerBatcher<CallReply>>>,
 }
 
 impl WorkerRegistration {
     /// Create a new worker registration
     pub fn new(worker: Box<dyn Worker>, matching_rule: MatchingRule, priority: u32) -> Self {
         Self {
-            worker,
-            matching_rule,
+            worker: Arc::from(worker),
+            matching_rule: Arc::new(matching_rule),
             priority,
+            timeout: None,
+            breaker: None,
+            limiter: None,
+            batcher: None,
         }
     }
     
+    /// Set the `handle_batch` timeout of this worker
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    
+    /// Guard this worker with a circuit breaker
+    ///
+    /// Timeouts and panics count as failures. The breaker is shared by all
+    /// clones of the registration, so it trips for every channel at once.
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.breaker = Some(Arc::new(CircuitBreaker::new(config)));
+        self
+    }
+    
+    /// Limit how many calls of this worker run at once and how often they start
+    ///
+    /// Like the circuit breaker, the limits are shared by all clones of the
+    /// registration and therefore hold across channels.
+    pub fn with_limits(mut self, limits: WorkerLimits) -> Self {
+        self.limiter = Some(Arc::new(WorkerLimiter::new(limits)));
+        self
+    }
+    
+    /// Collect the packages of this worker into batches before calling it
+    ///
+    /// Packages arriving within `policy.max_wait_ms` of each other, from any
+    /// channel, are handed to one `handle_batch` call of up to
+    /// `policy.max_size` packages.
+    pub fn with_batching(mut self, policy: BatchPolicy) -> Self {
+        self.batcher = Some(Arc::new(WorkerBatcher::new(policy)));
+        self
+    }
+    
+    /// Get the batch policy of this worker, if it batches
+    pub fn batch_policy(&self) -> Option<&BatchPolicy> {
+        self.batcher.as_ref().map(|batcher| batcher.policy())
+    }
+    
+    /// Check if the worker's circuit breaker lets a call through
+    pub fn allows_call(&self) -> bool {
+        self.breaker.as_ref().is_none_or(|breaker| breaker.allows_call())
+    }
+    
+    /// Get the worker name
+    pub fn name(&self) -> &str {
+        self.worker.name()
+    }
+    
     /// Check if this registration matches any of the target sites
     pub fn matches_any(&self, target_sites: &[TargetSite]) -> bool {
         target_sites
@@ -127,6 +335,17 @@ impl WorkerRegistration {
             .any(|ts| self.matching_rule.matches(ts) && self.worker.matches(ts))
     }
     
+    /// Check if this registration matches a package
+    ///
+    /// Unlike `matches_any`, content rules are evaluated against the
+    /// package's events.
+    pub fn matches_package(&self, package: &Package) -> bool {
+        package
+            .target_sites
+            .iter()
+            .any(|ts| self.matching_rule.matches_in(package, ts) && self.worker.matches(ts))
+    }
+    
     /// Check if this registration matches a specific target site
     pub fn matches(&self, target_site: &TargetSite) -> bool {
         self.matching_rule.matches(target_site) && self.worker.matches(target_site)
@@ -139,6 +358,10 @@ impl Debug for WorkerRegistration {
             .field("worker_name", &self.worker.name())
             .field("worker_type", &self.worker.worker_type())
             .field("priority", &self.priority)
+            .field("timeout", &self.timeout)
+            .field("breaker", &self.breaker.as_ref().map(|breaker| breaker.state()))
+            .field("limits", &self.limiter.as_ref().map(|limiter| limiter.limits()))
+            .field("batching", &self.batch_policy())
             .finish()
     }
 }
@@ -146,7 +369,7 @@ impl Debug for WorkerRegistration {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::events::SiteType;
+    use crate::events::{Block, BlockType, EventMetadata, Group, MessageEvent, NoticeEvent};
     use crate::workers::WorkerResult;
     use crate::workers::WorkerType;
     use async_trait::async_trait;
@@ -242,4 +465,118 @@ mod tests {
         
         assert!(registration.matches_any(&sites));
     }
+
+    fn event_package(event: EventEnum) -> Package {
+        Package::new()
+            .with_target_site(TargetSite::group("123456"))
+            .with_block(Block::new(BlockType::Message).with_group(Group::new("g").with_event(event)))
+    }
+
+    fn at_message(text: &str, at_list: &[&str]) -> EventEnum {
+        EventEnum::Message(MessageEvent::At {
+            text: text.to_string(),
+            at_list: at_list.iter().map(|s| s.to_string()).collect(),
+            metadata: EventMetadata::new("message.at").with_self_id("bot"),
+        })
+    }
+
+    #[test]
+    fn test_matching_rule_combinators() {
+        let group = TargetSite::group("123456");
+        let other = TargetSite::group("789012");
+
+        let rule = MatchingRule::Group("123456".to_string()).or(MatchingRule::Group("789012".to_string()));
+        assert!(rule.matches(&group) && rule.matches(&other));
+
+        let rule = MatchingRule::All.and(!MatchingRule::Group("123456".to_string()));
+        assert!(!rule.matches(&group));
+        assert!(rule.matches(&other));
+
+        assert!(!MatchingRule::AnyOf(Vec::new()).matches(&group));
+        // Content rules need the package, and so do rules built on them
+        assert!(!MatchingRule::command("/ping").matches(&group));
+        assert!(!(!MatchingRule::command("/ping")).matches(&group));
+        assert!(!MatchingRule::Not(Box::new(MatchingRule::MentionsSelf)).matches(&group));
+        assert!(!MatchingRule::All.and(!MatchingRule::MentionsSelf).matches(&group));
+        assert!(!(!MatchingRule::Group("123456".to_string()).and(MatchingRule::MentionsSelf)).matches(&group));
+        // unless the site alone settles them
+        assert!(MatchingRule::Group("123456".to_string()).or(!MatchingRule::MentionsSelf).matches(&group));
+        assert!(!MatchingRule::Group("789012".to_string()).and(MatchingRule::MentionsSelf).matches(&group));
+        assert!((!MatchingRule::Group("789012".to_string()).and(MatchingRule::MentionsSelf)).matches(&group));
+    }
+
+    #[test]
+    fn test_matching_rule_content_predicates() {
+        let site = TargetSite::group("123456");
+        let package = event_package(at_message("  /ping now", &["bot"]));
+
+        assert!(MatchingRule::Category(EventCategory::Message).matches_in(&package, &site));
+        assert!(!MatchingRule::Category(EventCategory::Notice).matches_in(&package, &site));
+        assert!(MatchingRule::EventType("message".to_string()).matches_in(&package, &site));
+        assert!(MatchingRule::EventType("message.at".to_string()).matches_in(&package, &site));
+        assert!(!MatchingRule::EventType("message.a".to_string()).matches_in(&package, &site));
+        assert!(MatchingRule::text(r"\bnow$").unwrap().matches_in(&package, &site));
+        assert!(MatchingRule::command("/ping").matches_in(&package, &site));
+        assert!(!MatchingRule::command("/pi").matches_in(&package, &site));
+        assert!(MatchingRule::SelfId("bot".to_string()).matches_in(&package, &site));
+        assert!(MatchingRule::MentionsSelf.matches_in(&package, &site));
+        assert!(!MatchingRule::MentionsSelf.matches_in(&event_package(at_message("hi", &["u1"])), &site));
+
+        let notice = EventEnum::Notice(NoticeEvent::GroupMemberJoin {
+            user_id: "u1".to_string(),
+            group_id: "123456".to_string(),
+            user_info: None,
+            metadata: EventMetadata::new("notice.group_member_join"),
+        });
+        let package = event_package(notice);
+        assert!(MatchingRule::Category(EventCategory::Notice).matches_in(&package, &site));
+        assert!(!MatchingRule::text(".*").unwrap().matches_in(&package, &site));
+    }
+
+    #[test]
+    fn test_matching_rule_from_config() {
+        let rule: MatchingRule = serde_json::from_value(serde_json::json!({
+            "and": [
+                {"group": "123456"},
+                {"any_of": [{"command": "/ping"}, "mentions_self", {"text": "^hello"}]}
+            ]
+        }))
+        .unwrap();
+        let site = TargetSite::group("123456");
+        assert!(rule.matches_in(&event_package(at_message("/ping", &[])), &site));
+        assert!(rule.matches_in(&event_package(at_message("hey", &["bot"])), &site));
+        assert!(!rule.matches_in(&event_package(at_message("hey", &[])), &site));
+        assert!(!rule.matches_in(&event_package(at_message("/ping", &[])), &TargetSite::group("789012")));
+
+        #[derive(Deserialize)]
+        struct Config {
+            rule: MatchingRule,
+        }
+        let config: Config = toml::from_str(
+            r#"
+            [rule]
+            not = { category = "meta" }
+            "#,
+        )
+        .unwrap();
+        assert!(config.rule.matches_in(&event_package(at_message("hi", &[])), &site));
+
+        assert!(serde_json::from_value::<MatchingRule>(serde_json::json!({"text": "("})).is_err());
+        let json = serde_json::to_value(MatchingRule::regex("^w").unwrap().or(MatchingRule::MentionsSelf)).unwrap();
+        assert_eq!(json, serde_json::json!({"or": [{"regex": "^w"}, "mentions_self"]}));
+    }
+
+    #[test]
+    fn test_worker_registration_matches_package() {
+        let worker = Box::new(MockWorker::new("test_worker".to_string()));
+        let registration = WorkerRegistration::new(worker, MatchingRule::command("/ping"), 0);
+
+        assert!(registration.matches_package(&event_package(at_message("/ping", &[]))));
+        assert!(!registration.matches_package(&event_package(at_message("ping", &[]))));
+        assert!(!registration.matches_any(&event_package(at_message("/ping", &[])).target_sites));
+
+        let registration = WorkerRegistration::new(Box::new(MockWorker::new("test_worker".to_string())), !MatchingRule::command("/ping"), 0);
+        assert!(registration.matches_package(&event_package(at_message("ping", &[]))));
+        assert!(!registration.matches_any(&event_package(at_message("ping", &[])).target_sites));
+    }
 }
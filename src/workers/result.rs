@@ -1,7 +1,11 @@
 //! Worker processing result
 
+use crate::engine::dispatch::OutboundMessage;
 use crate::events::Package;
+use crate::pools::PoolType;
+use crate::workers::OverflowAction;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Worker processing result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +16,59 @@ pub enum WorkerResult {
     /// Modified packages, continue processing in current pool
     /// Workers must ensure output packages won't be matched by themselves again
     Modify(Vec<Package>),
+    
+    /// Consume the packages, they leave the stream
+    Drop(String),
+    
+    /// Attach replies to the packages, which leave the stream right away
+    /// so the engine delivers the replies without further processing
+    Reply(Vec<OutboundMessage>),
+    
+    /// Continue with these packages in a later pool, skipping the pools in between
+    /// Jumping to the current pool behaves like `Modify`
+    Jump(PoolType, Vec<Package>),
+    
+    /// Hand the same packages to the worker again after a delay
+    Defer(Duration),
+}
+
+/// Why a call produced no worker result
+#[derive(Debug, Clone)]
+pub(crate) enum CallFailure {
+    /// The worker was at its limit and its overflow action is not `Queue`
+    Overflow(OverflowAction),
+    
+    /// The worker deferred more than `max_deferrals` times
+    DeferLimit(u32),
+    
+    /// The worker timed out or panicked
+    Worker(WorkerFailure),
+}
+
+/// Result of a call, shared with the other steps of a batch window
+pub(crate) type CallReply = Result<WorkerResult, CallFailure>;
+
+/// A `handle_batch` call that did not return normally
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WorkerFailure {
+    /// The call exceeded the worker's timeout
+    Timeout(Duration),
+    
+    /// The call panicked
+    Panic(String),
+    
+    /// The step leading the batch was cancelled before it called the worker
+    Cancelled,
+}
+
+impl std::fmt::Display for WorkerFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout(timeout) => write!(f, "timed out after {}ms", timeout.as_millis()),
+            Self::Panic(message) => write!(f, "panicked: {}", message),
+            Self::Cancelled => write!(f, "batch call was cancelled"),
+        }
+    }
 }
 
 impl WorkerResult {
@@ -25,6 +82,26 @@ impl WorkerResult {
         Self::Modify(packages)
     }
     
+    /// Create a Drop result
+    pub fn drop(reason: impl Into<String>) -> Self {
+        Self::Drop(reason.into())
+    }
+    
+    /// Create a Reply result
+    pub fn reply(messages: Vec<OutboundMessage>) -> Self {
+        Self::Reply(messages)
+    }
+    
+    /// Create a Jump result
+    pub fn jump(pool_type: PoolType, packages: Vec<Package>) -> Self {
+        Self::Jump(pool_type, packages)
+    }
+    
+    /// Create a Defer result
+    pub fn defer(delay: Duration) -> Self {
+        Self::Defer(delay)
+    }
+    
     /// Check if this is a Release result
     pub fn is_release(&self) -> bool {
         matches!(self, Self::Release)
@@ -34,6 +111,26 @@ impl WorkerResult {
     pub fn is_modify(&self) -> bool {
         matches!(self, Self::Modify(_))
     }
+    
+    /// Check if this is a Drop result
+    pub fn is_drop(&self) -> bool {
+        matches!(self, Self::Drop(_))
+    }
+    
+    /// Check if this is a Reply result
+    pub fn is_reply(&self) -> bool {
+        matches!(self, Self::Reply(_))
+    }
+    
+    /// Check if this is a Jump result
+    pub fn is_jump(&self) -> bool {
+        matches!(self, Self::Jump(..))
+    }
+    
+    /// Check if this is a Defer result
+    pub fn is_defer(&self) -> bool {
+        matches!(self, Self::Defer(_))
+    }
 }
 
 #[cfg(test)]
@@ -54,4 +151,18 @@ mod tests {
         assert!(!result.is_release());
         assert!(result.is_modify());
     }
+
+    #[test]
+    fn test_worker_result_outcomes() {
+        assert!(WorkerResult::drop("spam").is_drop());
+        assert!(WorkerResult::reply(Vec::new()).is_reply());
+        assert!(WorkerResult::jump(PoolType::Output, Vec::new()).is_jump());
+
+        let result = WorkerResult::defer(Duration::from_millis(50));
+        assert!(result.is_defer());
+        assert!(!result.is_release());
+
+        let json = serde_json::to_value(WorkerResult::drop("spam")).unwrap();
+        assert_eq!(json, serde_json::json!({"Drop": "spam"}));
+    }
 }
@@ -0,0 +1,225 @@
+//! Configurable worker shared by tests
+//!
+//! `TestWorker` releases by default and can be set up to return scripted
+//! results, forward packages to another worker, sleep, panic or hang. Calls
+//! are recorded in a `WorkerCalls` that several workers may share, so tests
+//! can check which worker saw which packages and in what order.
+
+use crate::events::{Package, SiteType, TargetSite};
+use crate::workers::{MatchingRule, Worker, WorkerRegistration, WorkerResult, WorkerType};
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A finished `handle_batch` call
+#[derive(Debug, Clone)]
+pub struct WorkerCall {
+    /// Worker that was called
+    pub worker: String,
+    /// Packages it was handed
+    pub packages: Vec<Package>,
+}
+
+/// Calls recorded by one or more `TestWorker`s, in the order they finished
+#[derive(Debug, Default)]
+pub struct WorkerCalls {
+    calls: Mutex<Vec<WorkerCall>>,
+    running: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl WorkerCalls {
+    /// Create an empty record
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Get the finished calls
+    pub fn calls(&self) -> Vec<WorkerCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Get the number of finished calls
+    pub fn count(&self) -> usize {
+        self.calls.lock().unwrap().len()
+    }
+
+    /// Get the name of the worker of each call
+    pub fn workers(&self) -> Vec<String> {
+        self.calls().into_iter().map(|call| call.worker).collect()
+    }
+
+    /// Get a string field of `extra` of every package, per call
+    pub fn field(&self, key: &str) -> Vec<Vec<String>> {
+        self.calls()
+            .iter()
+            .map(|call| {
+                call.packages
+                    .iter()
+                    .map(|package| package.extra[key].as_str().unwrap_or_default().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Get the channel (`extra["channel"]`) of every package, per call
+    pub fn channels(&self) -> Vec<Vec<String>> {
+        self.field("channel")
+    }
+
+    /// Get the highest number of calls that ran at once
+    pub fn peak(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+}
+
+/// What a `TestWorker` does with its packages
+#[derive(Debug)]
+enum Behavior {
+    Release,
+    Script(Mutex<VecDeque<WorkerResult>>),
+    Forward(String),
+    Panic,
+    Hang,
+}
+
+/// Worker with configurable behavior
+#[derive(Debug)]
+pub struct TestWorker {
+    name: String,
+    worker_type: WorkerType,
+    own_site_only: bool,
+    delay: Duration,
+    behavior: Behavior,
+    calls: Arc<WorkerCalls>,
+}
+
+impl TestWorker {
+    /// Create a `Process` worker that matches every site and releases
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            worker_type: WorkerType::Process,
+            own_site_only: false,
+            delay: Duration::ZERO,
+            behavior: Behavior::Release,
+            calls: WorkerCalls::new(),
+        }
+    }
+
+    /// Set the worker type
+    pub fn with_type(mut self, worker_type: WorkerType) -> Self {
+        self.worker_type = worker_type;
+        self
+    }
+
+    /// Only match `TargetSite::worker(name)`
+    pub fn matching_own_site(mut self) -> Self {
+        self.own_site_only = true;
+        self
+    }
+
+    /// Sleep before returning
+    ///
+    /// A batch whose first package has `extra["delay"]` (milliseconds)
+    /// sleeps that long instead.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Return `results` one call at a time, then release
+    pub fn with_results(mut self, results: Vec<WorkerResult>) -> Self {
+        self.behavior = Behavior::Script(Mutex::new(results.into()));
+        self
+    }
+
+    /// Hand every package on to the worker named `next`
+    pub fn forwarding_to(mut self, next: &str) -> Self {
+        self.behavior = Behavior::Forward(next.to_string());
+        self
+    }
+
+    /// Panic in every call
+    pub fn panicking(mut self) -> Self {
+        self.behavior = Behavior::Panic;
+        self
+    }
+
+    /// Never return (for timeouts)
+    pub fn hanging(mut self) -> Self {
+        self.behavior = Behavior::Hang;
+        self
+    }
+
+    /// Record calls in `calls`, e.g. shared with other workers
+    pub fn with_calls(mut self, calls: &Arc<WorkerCalls>) -> Self {
+        self.calls = calls.clone();
+        self
+    }
+
+    /// Get the calls record of this worker
+    pub fn calls(&self) -> Arc<WorkerCalls> {
+        self.calls.clone()
+    }
+
+    /// Wrap the worker in a registration matching every site
+    pub fn register(self, priority: u32) -> WorkerRegistration {
+        WorkerRegistration::new(Box::new(self), MatchingRule::All, priority)
+    }
+}
+
+#[async_trait]
+impl Worker for TestWorker {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn worker_type(&self) -> WorkerType {
+        self.worker_type.clone()
+    }
+
+    fn matches(&self, target_site: &TargetSite) -> bool {
+        !self.own_site_only || target_site.site_type == SiteType::Worker(self.name.clone())
+    }
+
+    async fn handle_batch(&self, packages: Vec<Package>) -> WorkerResult {
+        let running = self.calls.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.calls.peak.fetch_max(running, Ordering::SeqCst);
+
+        let delay = packages
+            .first()
+            .and_then(|package| package.extra["delay"].as_u64())
+            .map(Duration::from_millis)
+            .unwrap_or(self.delay);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        self.calls.running.fetch_sub(1, Ordering::SeqCst);
+
+        let result = match &self.behavior {
+            Behavior::Release => WorkerResult::release(),
+            Behavior::Script(results) => results.lock().unwrap().pop_front().unwrap_or(WorkerResult::Release),
+            Behavior::Forward(next) => WorkerResult::modify(
+                packages
+                    .iter()
+                    .cloned()
+                    .map(|mut package| {
+                        package.target_sites = vec![TargetSite::worker(next)];
+                        package
+                    })
+                    .collect(),
+            ),
+            Behavior::Panic => panic!("worker exploded"),
+            Behavior::Hang => std::future::pending().await,
+        };
+
+        self.calls.calls.lock().unwrap().push(WorkerCall {
+            worker: self.name.clone(),
+            packages,
+        });
+        result
+    }
+}
@@ -1,6 +1,7 @@
 //! Worker trait and related types
 
 use crate::events::{Package, TargetSite};
+use crate::pools::PoolType;
 use crate::workers::WorkerResult;
 use async_trait::async_trait;
 use std::fmt::Debug;
@@ -20,6 +21,21 @@ pub enum WorkerType {
     Custom(String),
 }
 
+impl WorkerType {
+    /// Get the pool this worker type is registered in
+    ///
+    /// A custom worker type lives in the custom stage of the same name.
+    pub fn pool_type(&self) -> PoolType {
+        match self {
+            Self::Input => PoolType::Input,
+            Self::PreProcess => PoolType::PreProcess,
+            Self::Process => PoolType::Process,
+            Self::Output => PoolType::Output,
+            Self::Custom(name) => PoolType::custom(name.as_str()),
+        }
+    }
+}
+
 impl std::fmt::Display for WorkerType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -104,6 +120,12 @@ mod tests {
         assert_eq!(WorkerType::Custom("test".to_string()).to_string(), "test");
     }
 
+    #[test]
+    fn test_worker_type_pool_type() {
+        assert_eq!(WorkerType::PreProcess.pool_type(), PoolType::PreProcess);
+        assert_eq!(WorkerType::Custom("moderation".to_string()).pool_type(), PoolType::custom("moderation"));
+    }
+
     #[test]
     fn test_output_safe() {
         let worker = TestWorker {
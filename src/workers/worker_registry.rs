@@ -0,0 +1,285 @@
+//! Engine-level worker registry
+//!
+//! Streams do not own their workers. Every `StandardPool` created for a
+//! channel reads its workers from a shared `WorkerRegistry` when a batch
+//! arrives, so workers registered, removed or re-prioritized at runtime
+//! take effect in new and existing channels alike.
+
+use crate::channels::ChannelType;
+use crate::errors::{ConfigError, LoquatError, Result};
+use crate::pools::PoolType;
+use crate::workers::WorkerRegistration;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Where a registered worker runs
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WorkerScope {
+    /// Runs in every channel
+    Global,
+
+    /// Runs only in the given channel
+    Channel(ChannelType),
+}
+
+impl WorkerScope {
+    /// Check if this scope applies to a channel
+    pub fn applies_to(&self, channel_type: &ChannelType) -> bool {
+        match self {
+            Self::Global => true,
+            Self::Channel(scoped) => scoped == channel_type,
+        }
+    }
+
+    /// Check if workers of both scopes can run in the same channel
+    fn overlaps(&self, other: &WorkerScope) -> bool {
+        match (self, other) {
+            (Self::Channel(a), Self::Channel(b)) => a == b,
+            _ => true,
+        }
+    }
+}
+
+impl std::fmt::Display for WorkerScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::Channel(channel_type) => write!(f, "{}", channel_type),
+        }
+    }
+}
+
+/// A registration together with its scope
+#[derive(Debug, Clone)]
+struct ScopedRegistration {
+    scope: WorkerScope,
+    registration: WorkerRegistration,
+}
+
+/// Worker registry shared by all streams of an engine
+///
+/// Worker names and priorities must be unique among the workers that can
+/// run together in one pool: a global worker conflicts with every worker
+/// of the same pool, a channel-scoped worker only with global workers and
+/// workers scoped to the same channel.
+#[derive(Debug, Default)]
+pub struct WorkerRegistry {
+    pools: RwLock<HashMap<PoolType, Vec<ScopedRegistration>>>,
+}
+
+impl WorkerRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a worker into a pool
+    pub fn register(&self, pool_type: PoolType, scope: WorkerScope, registration: WorkerRegistration) -> Result<()> {
+        let mut pools = self.pools.write().map_err(|e| {
+            LoquatError::Internal(format!("Failed to acquire write lock: {}", e))
+        })?;
+        let entries = pools.entry(pool_type.clone()).or_default();
+
+        for entry in entries.iter().filter(|entry| entry.scope.overlaps(&scope)) {
+            if entry.registration.name() == registration.name() {
+                return Err(LoquatError::Config(ConfigError::InvalidFormat(format!(
+                    "Worker '{}' already exists in pool '{}' ({})",
+                    registration.name(), pool_type, entry.scope
+                ))));
+            }
+            if entry.registration.priority == registration.priority {
+                return Err(LoquatError::Config(ConfigError::InvalidFormat(format!(
+                    "Worker priority {} already exists in pool '{}' ({})",
+                    registration.priority, pool_type, entry.scope
+                ))));
+            }
+        }
+
+        entries.push(ScopedRegistration { scope, registration });
+        entries.sort_by_key(|entry| entry.registration.priority);
+        Ok(())
+    }
+
+    /// Unregister a worker from a pool
+    pub fn unregister(&self, pool_type: &PoolType, scope: &WorkerScope, name: &str) -> Result<()> {
+        let mut pools = self.pools.write().map_err(|e| {
+            LoquatError::Internal(format!("Failed to acquire write lock: {}", e))
+        })?;
+        let entries = pools.entry(pool_type.clone()).or_default();
+
+        match Self::position(entries, scope, name) {
+            Some(idx) => {
+                entries.remove(idx);
+                Ok(())
+            }
+            None => Err(Self::not_found(pool_type, scope, name)),
+        }
+    }
+
+    /// Change the priority of a registered worker
+    pub fn set_priority(&self, pool_type: &PoolType, scope: &WorkerScope, name: &str, new_priority: u32) -> Result<()> {
+        let mut pools = self.pools.write().map_err(|e| {
+            LoquatError::Internal(format!("Failed to acquire write lock: {}", e))
+        })?;
+        let entries = pools.entry(pool_type.clone()).or_default();
+
+        let idx = Self::position(entries, scope, name).ok_or_else(|| Self::not_found(pool_type, scope, name))?;
+        if entries.iter().enumerate().any(|(i, entry)| {
+            i != idx && entry.scope.overlaps(scope) && entry.registration.priority == new_priority
+        }) {
+            return Err(LoquatError::Config(ConfigError::InvalidFormat(format!(
+                "Worker priority {} already used in pool '{}'",
+                new_priority, pool_type
+            ))));
+        }
+
+        entries[idx].registration.priority = new_priority;
+        entries.sort_by_key(|entry| entry.registration.priority);
+        Ok(())
+    }
+
+    /// Get the workers that run in a channel's pool, sorted by priority
+    pub fn workers_for(&self, pool_type: &PoolType, channel_type: &ChannelType) -> Vec<WorkerRegistration> {
+        let pools = self.pools.read().unwrap();
+        pools
+            .get(pool_type)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|entry| entry.scope.applies_to(channel_type))
+                    .map(|entry| entry.registration.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get every registration with its pool and scope
+    pub fn registrations(&self) -> Vec<(PoolType, WorkerScope, WorkerRegistration)> {
+        let pools = self.pools.read().unwrap();
+        pools
+            .iter()
+            .flat_map(|(pool_type, entries)| {
+                entries
+                    .iter()
+                    .map(|entry| (pool_type.clone(), entry.scope.clone(), entry.registration.clone()))
+            })
+            .collect()
+    }
+
+    /// Get the names of the workers registered in a pool with exactly this scope
+    pub fn worker_names(&self, pool_type: &PoolType, scope: &WorkerScope) -> Vec<String> {
+        let pools = self.pools.read().unwrap();
+        pools
+            .get(pool_type)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|entry| &entry.scope == scope)
+                    .map(|entry| entry.registration.name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the total number of registrations
+    pub fn len(&self) -> usize {
+        self.pools.read().unwrap().values().map(Vec::len).sum()
+    }
+
+    /// Check if no worker is registered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn position(entries: &[ScopedRegistration], scope: &WorkerScope, name: &str) -> Option<usize> {
+        entries
+            .iter()
+            .position(|entry| &entry.scope == scope && entry.registration.name() == name)
+    }
+
+    fn not_found(pool_type: &PoolType, scope: &WorkerScope, name: &str) -> LoquatError {
+        LoquatError::Config(ConfigError::MissingRequired(format!(
+            "Worker '{}' not found in pool '{}' ({})",
+            name, pool_type, scope
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{Package, TargetSite};
+    use crate::workers::{MatchingRule, Worker, WorkerResult, WorkerType};
+    use async_trait::async_trait;
+
+    #[derive(Debug)]
+    struct MockWorker {
+        name: String,
+    }
+
+    #[async_trait]
+    impl Worker for MockWorker {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn worker_type(&self) -> WorkerType {
+            WorkerType::Process
+        }
+
+        fn matches(&self, _target_site: &TargetSite) -> bool {
+            true
+        }
+
+        async fn handle_batch(&self, _packages: Vec<Package>) -> WorkerResult {
+            WorkerResult::release()
+        }
+    }
+
+    fn registration(name: &str, priority: u32) -> WorkerRegistration {
+        let worker = Box::new(MockWorker { name: name.to_string() });
+        WorkerRegistration::new(worker, MatchingRule::All, priority)
+    }
+
+    fn names(workers: &[WorkerRegistration]) -> Vec<&str> {
+        workers.iter().map(|w| w.name()).collect()
+    }
+
+    #[test]
+    fn test_scoped_workers() {
+        let registry = WorkerRegistry::new();
+        let group1 = ChannelType::group("g1");
+        let group2 = ChannelType::group("g2");
+
+        registry.register(PoolType::Process, WorkerScope::Global, registration("global", 1)).unwrap();
+        registry.register(PoolType::Process, WorkerScope::Channel(group1.clone()), registration("scoped", 0)).unwrap();
+        // Same priority is fine in a different channel
+        registry.register(PoolType::Process, WorkerScope::Channel(group2.clone()), registration("other", 0)).unwrap();
+
+        assert_eq!(names(&registry.workers_for(&PoolType::Process, &group1)), vec!["scoped", "global"]);
+        assert_eq!(names(&registry.workers_for(&PoolType::Process, &group2)), vec!["other", "global"]);
+        assert!(registry.workers_for(&PoolType::Input, &group1).is_empty());
+        assert_eq!(registry.len(), 3);
+
+        // Conflicts with the global worker
+        assert!(registry.register(PoolType::Process, WorkerScope::Channel(group1.clone()), registration("dup", 1)).is_err());
+        assert!(registry.register(PoolType::Process, WorkerScope::Global, registration("scoped", 5)).is_err());
+    }
+
+    #[test]
+    fn test_unregister_and_reprioritize() {
+        let registry = WorkerRegistry::new();
+        let group = ChannelType::group("g1");
+
+        registry.register(PoolType::Process, WorkerScope::Global, registration("a", 0)).unwrap();
+        registry.register(PoolType::Process, WorkerScope::Global, registration("b", 1)).unwrap();
+
+        registry.set_priority(&PoolType::Process, &WorkerScope::Global, "a", 2).unwrap();
+        assert_eq!(names(&registry.workers_for(&PoolType::Process, &group)), vec!["b", "a"]);
+        assert!(registry.set_priority(&PoolType::Process, &WorkerScope::Global, "a", 1).is_err());
+
+        registry.unregister(&PoolType::Process, &WorkerScope::Global, "b").unwrap();
+        assert_eq!(registry.worker_names(&PoolType::Process, &WorkerScope::Global), vec!["a"]);
+        assert!(registry.unregister(&PoolType::Process, &WorkerScope::Channel(group), "a").is_err());
+    }
+}